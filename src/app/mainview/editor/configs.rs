@@ -1,38 +1,69 @@
 use std::cell::RefCell;
-use std::ffi::OsStr;
 use std::path::PathBuf;
 use std::rc::Rc;
 
-use fltk::button::Button;
-use fltk::dialog::{FileDialogType, NativeFileChooser};
+use fltk::button::{Button, ToggleButton};
 use fltk::enums::{Align, Color, Cursor, Event, Font, FrameType};
 use fltk::frame::Frame;
 use fltk::group::Group;
-use fltk::input::{FloatInput, IntInput};
+use fltk::input::{FloatInput, Input, IntInput};
 use fltk::menu::Choice;
-use fltk::prelude::{GroupExt, InputExt, WidgetBase};
+use fltk::prelude::{ButtonExt, GroupExt, InputExt, WidgetBase};
 use fltk::prelude::{MenuExt, WidgetExt};
 use fltk::window::Window;
 use pyo3::Python;
 use tch::utils::has_vulkan;
 use tch::Device;
 
+use crate::app::mainview::editor::early_stopping::EarlyStoppingWidget;
+use crate::app::mainview::editor::gradients::{batch_label, GradientWidget};
 use crate::app::mainview::editor::playground::Playground;
+use crate::app::mainview::editor::scheduler::SchedulerWidget;
+use crate::model::weights::SAVE_EXTENSIONS;
+use crate::model::{parse_shape, Hyperparameters};
+use crate::project::{device_name, parse_device, Settings};
+use crate::training::early_stopping::EarlyStopping;
+use crate::training::gradients::GradientConfig;
+use crate::training::scheduler::Scheduler;
 use crate::utils::check_mps_availability;
 use crate::utils::consts::{
     BG_COLOR, DEFAULT_BATCH_SIZE, DEFAULT_BATCH_SIZE_STR, DEFAULT_EPOCHS, DEFAULT_EPOCHS_STR,
-    DEFAULT_LR, DEFAULT_LR_STR, DEVICES, DRAG_THRESHOLD, HIGHLIGHT_COLOR, LOSS_FUNCTIONS,
-    MENU_BAR_COLOR, MENU_BAR_RATIO, OPTIMIZERS,
+    DEFAULT_INPUT_SHAPE, DEFAULT_LR, DEFAULT_LR_STR, DEVICES, DRAG_THRESHOLD, HIGHLIGHT_COLOR,
+    LOSS_FUNCTIONS, MENU_BAR_COLOR, MENU_BAR_RATIO, OPTIMIZERS, SCHEDULERS,
 };
 use crate::utils::loss_fn::{LossFunction, LossWidget};
-use crate::utils::CustomDialog;
+use crate::utils::optimizer::{OptimizerType, OptimizerWidget};
+use crate::utils::{save_file_dialog, CustomDialog};
 
 pub(crate) struct ConfingList {
     pub(crate) window: Window,
     pub(crate) save_path: Rc<RefCell<Option<PathBuf>>>,
     pub(crate) device: Rc<RefCell<Option<Device>>>,
-    pub(crate) optimizer: Rc<RefCell<Option<String>>>,
+    pub(crate) optimizer: Rc<RefCell<Option<OptimizerType>>>,
     pub(crate) loss_fn: Rc<RefCell<Option<LossFunction>>>,
+    lr: Rc<RefCell<f64>>,
+    scheduler: Rc<RefCell<Scheduler>>,
+    batch_size: Rc<RefCell<i64>>,
+    gradients: Rc<RefCell<GradientConfig>>,
+    epochs: Rc<RefCell<usize>>,
+    early_stopping: Rc<RefCell<EarlyStopping>>,
+    seed: Rc<RefCell<Option<u64>>>,
+    deterministic: Rc<RefCell<bool>>,
+    input_shape: Rc<RefCell<String>>,
+    save_btn: Button,
+    device_selector: Choice,
+    optimizer_selector: Choice,
+    loss_selector: Choice,
+    lr_selector: FloatInput,
+    scheduler_selector: Choice,
+    batch_selector: IntInput,
+    batch_text: Frame,
+    gradients_selector: Button,
+    epoch_selector: IntInput,
+    early_stopping_selector: Button,
+    seed_selector: IntInput,
+    deterministic_selector: ToggleButton,
+    input_shape_selector: Input,
 }
 
 fltk::widget_extends!(ConfingList, Window, window);
@@ -82,7 +113,7 @@ impl ConfingList {
         let (device_border, mut device_selector) = device_entry(device.clone(), &border, p_h);
 
         // Optimizer
-        let optimizer: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+        let optimizer = Rc::new(RefCell::new(None));
         let (optimizer_border, mut optimizer_selector) =
             optimizer_entry(optimizer.clone(), &device_border, p_h);
 
@@ -94,13 +125,59 @@ impl ConfingList {
         let lr = Rc::new(RefCell::new(DEFAULT_LR));
         let (lr_border, mut lr_selector) = lr_entry(lr.clone(), &loss_border, p_h);
 
+        // Learning Rate Scheduler, previewed over the epochs
+        let epochs = Rc::new(RefCell::new(DEFAULT_EPOCHS));
+        let scheduler = Rc::new(RefCell::new(Scheduler::default()));
+        let (scheduler_border, mut scheduler_selector) = scheduler_entry(
+            scheduler.clone(),
+            lr.clone(),
+            epochs.clone(),
+            &lr_border,
+            p_h,
+        );
+
         // Batch Size
         let batch_size = Rc::new(RefCell::new(DEFAULT_BATCH_SIZE));
-        let (batch_border, mut batch_selector) = batch_entry(batch_size.clone(), &lr_border, p_h);
+        let gradients = Rc::new(RefCell::new(GradientConfig::default()));
+        let (batch_border, mut batch_selector, batch_text) = batch_entry(
+            batch_size.clone(),
+            gradients.clone(),
+            &scheduler_border,
+            p_h,
+        );
+
+        // Gradient clipping and accumulation
+        let (gradients_border, mut gradients_selector) = gradients_entry(
+            gradients.clone(),
+            batch_size.clone(),
+            batch_text.clone(),
+            &batch_border,
+            p_h,
+        );
 
         // Epochs
-        let epochs = Rc::new(RefCell::new(DEFAULT_EPOCHS));
-        let (epoch_border, mut epoch_selector) = epoch_entry(epochs.clone(), &batch_border, p_h);
+        let (epoch_border, mut epoch_selector) =
+            epoch_entry(epochs.clone(), &gradients_border, p_h);
+
+        // Early Stopping
+        let early_stopping = Rc::new(RefCell::new(EarlyStopping::default()));
+        let (early_stopping_border, mut early_stopping_selector) =
+            early_stopping_entry(early_stopping.clone(), &epoch_border, p_h);
+
+        // Seed, a new one for every run when empty
+        let seed = Rc::new(RefCell::new(None));
+        let (seed_border, mut seed_selector) =
+            seed_entry(seed.clone(), &early_stopping_border, p_h);
+
+        // Deterministic algorithms and a single thread
+        let deterministic = Rc::new(RefCell::new(false));
+        let (deterministic_border, mut deterministic_selector) =
+            deterministic_entry(deterministic.clone(), &seed_border, p_h);
+
+        // Input Shape
+        let input_shape = Rc::new(RefCell::new(DEFAULT_INPUT_SHAPE.to_string()));
+        let (_, mut input_shape_selector) =
+            input_shape_entry(input_shape.clone(), &deterministic_border, p_h);
 
         let mut build_btn_bg = Frame::default()
             .with_pos(p_w / 3, p_h - epoch_border.h())
@@ -119,6 +196,15 @@ impl ConfingList {
         let check_device = device.clone();
         let check_optimizer = optimizer.clone();
         let check_loss_fn = loss_fn.clone();
+        let check_lr = lr.clone();
+        let check_scheduler = scheduler.clone();
+        let check_batch_size = batch_size.clone();
+        let check_gradients = gradients.clone();
+        let check_epochs = epochs.clone();
+        let check_early_stopping = early_stopping.clone();
+        let check_seed = seed.clone();
+        let check_deterministic = deterministic.clone();
+        let check_input_shape = input_shape.clone();
 
         build_btn.set_callback(move |_| {
             // check if all fields are filled and correct
@@ -159,20 +245,52 @@ impl ConfingList {
                     Color::Red,
                 );
             } else {
-                graph.borrow_mut().build_model(
-                    check_save_path.borrow().as_ref().unwrap().clone(),
-                    *check_device.borrow().as_ref().unwrap(),
-                    check_optimizer.borrow().as_ref().unwrap().clone(),
-                    check_loss_fn.borrow().as_ref().unwrap().clone(),
-                    *lr.borrow(),
-                    *batch_size.borrow(),
-                    *epochs.borrow(),
-                );
+                match parse_shape(&check_input_shape.borrow()) {
+                    Ok(shape) => graph.borrow_mut().build_model(
+                        Hyperparameters {
+                            save_path: check_save_path.borrow().as_ref().unwrap().clone(),
+                            device: *check_device.borrow().as_ref().unwrap(),
+                            optimizer: check_optimizer.borrow().as_ref().unwrap().clone(),
+                            loss_fn: check_loss_fn.borrow().as_ref().unwrap().clone(),
+                            lr: *check_lr.borrow(),
+                            scheduler: check_scheduler.borrow().clone(),
+                            batch_size: *check_batch_size.borrow(),
+                            gradients: *check_gradients.borrow(),
+                            epochs: *check_epochs.borrow(),
+                            early_stopping: check_early_stopping.borrow().clone(),
+                            seed: *check_seed.borrow(),
+                            deterministic: *check_deterministic.borrow(),
+                        },
+                        shape,
+                    ),
+                    Err(e) => CustomDialog::show(
+                        300,
+                        40,
+                        "Error",
+                        &format!("Invalid input shape: {}", e),
+                        BG_COLOR,
+                        Color::Red,
+                    ),
+                }
             }
         });
 
         group.end();
         window.end();
+        // handles kept to show loaded settings
+        let save_btn_handle = save_btn.clone();
+        let device_handle = device_selector.clone();
+        let optimizer_handle = optimizer_selector.clone();
+        let loss_handle = loss_selector.clone();
+        let lr_handle = lr_selector.clone();
+        let scheduler_handle = scheduler_selector.clone();
+        let batch_handle = batch_selector.clone();
+        let gradients_handle = gradients_selector.clone();
+        let epoch_handle = epoch_selector.clone();
+        let early_stopping_handle = early_stopping_selector.clone();
+        let seed_handle = seed_selector.clone();
+        let deterministic_handle = deterministic_selector.clone();
+        let input_shape_handle = input_shape_selector.clone();
         let mut enabled = false;
         window.handle(move |window, event| match event {
             Event::Push => {
@@ -217,18 +335,54 @@ impl ConfingList {
                     loss_selector.w(),
                     lr_selector.h(),
                 );
-                batch_selector.resize(
+                scheduler_selector.resize(
                     lr_selector.x(),
-                    batch_selector.y(),
+                    scheduler_selector.y(),
                     lr_selector.w(),
+                    scheduler_selector.h(),
+                );
+                batch_selector.resize(
+                    scheduler_selector.x(),
+                    batch_selector.y(),
+                    scheduler_selector.w(),
                     batch_selector.h(),
                 );
-                epoch_selector.resize(
+                gradients_selector.resize(
                     batch_selector.x(),
-                    epoch_selector.y(),
+                    gradients_selector.y(),
                     batch_selector.w(),
+                    gradients_selector.h(),
+                );
+                epoch_selector.resize(
+                    gradients_selector.x(),
+                    epoch_selector.y(),
+                    gradients_selector.w(),
                     epoch_selector.h(),
                 );
+                early_stopping_selector.resize(
+                    epoch_selector.x(),
+                    early_stopping_selector.y(),
+                    epoch_selector.w(),
+                    early_stopping_selector.h(),
+                );
+                seed_selector.resize(
+                    early_stopping_selector.x(),
+                    seed_selector.y(),
+                    early_stopping_selector.w(),
+                    seed_selector.h(),
+                );
+                deterministic_selector.resize(
+                    seed_selector.x(),
+                    deterministic_selector.y(),
+                    seed_selector.w(),
+                    deterministic_selector.h(),
+                );
+                input_shape_selector.resize(
+                    deterministic_selector.x(),
+                    input_shape_selector.y(),
+                    deterministic_selector.w(),
+                    input_shape_selector.h(),
+                );
                 build_btn.resize(
                     build_btn_bg.x() + 2,
                     build_btn_bg.y() + 2,
@@ -258,8 +412,261 @@ impl ConfingList {
             device,
             optimizer,
             loss_fn,
+            lr,
+            scheduler,
+            batch_size,
+            gradients,
+            epochs,
+            early_stopping,
+            seed,
+            deterministic,
+            input_shape,
+            save_btn: save_btn_handle,
+            device_selector: device_handle,
+            optimizer_selector: optimizer_handle,
+            loss_selector: loss_handle,
+            lr_selector: lr_handle,
+            scheduler_selector: scheduler_handle,
+            batch_selector: batch_handle,
+            batch_text,
+            gradients_selector: gradients_handle,
+            epoch_selector: epoch_handle,
+            early_stopping_selector: early_stopping_handle,
+            seed_selector: seed_handle,
+            deterministic_selector: deterministic_handle,
+            input_shape_selector: input_shape_handle,
         }
     }
+
+    pub(crate) fn settings(&self) -> Settings {
+        Settings {
+            save_path: self.save_path.borrow().clone(),
+            device: self.device.borrow().map(device_name),
+            optimizer: self.optimizer.borrow().clone(),
+            loss_fn: self.loss_fn.borrow().clone(),
+            lr: *self.lr.borrow(),
+            scheduler: self.scheduler.borrow().clone(),
+            batch_size: *self.batch_size.borrow(),
+            gradients: *self.gradients.borrow(),
+            epochs: *self.epochs.borrow(),
+            early_stopping: self.early_stopping.borrow().clone(),
+            seed: *self.seed.borrow(),
+            deterministic: *self.deterministic.borrow(),
+            input_shape: self.input_shape.borrow().clone(),
+        }
+    }
+
+    pub(crate) fn load_settings(&mut self, settings: &Settings) -> Result<(), String> {
+        let device = settings.device.as_deref().map(parse_device).transpose()?;
+        let save_label = match settings.save_path.as_ref().and_then(|p| p.file_name()) {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => "Select save path".to_string(),
+        };
+        let loss_label = match settings.loss_fn.as_ref() {
+            Some(loss_fn) => loss_fn.name(),
+            None => "Select loss fn:",
+        };
+        self.save_path.replace(settings.save_path.clone());
+        self.save_btn.set_label(&save_label);
+        self.device.replace(device);
+        self.device_selector
+            .set_label(settings.device.as_deref().unwrap_or("Select device:"));
+        self.optimizer.replace(settings.optimizer.clone());
+        self.optimizer_selector
+            .set_label(match settings.optimizer.as_ref() {
+                Some(optimizer) => optimizer.name(),
+                None => "Select optimizer:",
+            });
+        self.loss_fn.replace(settings.loss_fn.clone());
+        self.loss_selector.set_label(loss_label);
+        self.lr.replace(settings.lr);
+        self.lr_selector.set_value(&settings.lr.to_string());
+        self.scheduler.replace(settings.scheduler.clone());
+        self.scheduler_selector.set_label(settings.scheduler.name());
+        self.batch_size.replace(settings.batch_size);
+        self.batch_selector
+            .set_value(&settings.batch_size.to_string());
+        self.gradients.replace(settings.gradients);
+        self.gradients_selector
+            .set_label(&settings.gradients.summary());
+        self.batch_text
+            .set_label(&batch_label(&settings.gradients, settings.batch_size));
+        self.epochs.replace(settings.epochs);
+        self.epoch_selector.set_value(&settings.epochs.to_string());
+        self.early_stopping.replace(settings.early_stopping.clone());
+        self.early_stopping_selector
+            .set_label(&settings.early_stopping.summary());
+        self.seed.replace(settings.seed);
+        self.seed_selector
+            .set_value(&settings.seed.map(|s| s.to_string()).unwrap_or_default());
+        self.deterministic.replace(settings.deterministic);
+        self.deterministic_selector.set(settings.deterministic);
+        self.deterministic_selector
+            .set_label(if settings.deterministic {
+                "✔️"
+            } else {
+                "✖️"
+            });
+        self.input_shape.replace(settings.input_shape.clone());
+        self.input_shape_selector.set_value(&settings.input_shape);
+        self.window.redraw();
+        Ok(())
+    }
+}
+
+fn input_shape_entry(
+    input_shape: Rc<RefCell<String>>,
+    epoch_border: &Frame,
+    p_h: i32,
+) -> (Frame, Input) {
+    let mut shape_border = Frame::default()
+        .with_pos(epoch_border.x(), epoch_border.y() + p_h / MENU_BAR_RATIO)
+        .with_size(epoch_border.w(), epoch_border.h());
+    shape_border.set_color(Color::White);
+    shape_border.set_frame(FrameType::FlatBox);
+    let mut shape_text = Frame::default()
+        .with_pos(shape_border.x(), shape_border.y() + 1)
+        .with_size(shape_border.w() / 2, shape_border.h() - 2)
+        .with_label("Input Shape: ")
+        .with_align(Align::Inside | Align::Left);
+    shape_text.set_label_color(Color::White);
+    shape_text.set_frame(FrameType::FlatBox);
+    shape_text.set_color(BG_COLOR);
+    // Handle events, the shape is validated when building the model
+    let mut shape_selector = Input::default()
+        .with_pos(shape_text.w() + 4, shape_text.y())
+        .with_size(shape_text.w() - 5, shape_border.h() - 2);
+    shape_selector.set_color(BG_COLOR);
+    shape_selector.set_frame(FrameType::FlatBox);
+    shape_selector.set_selection_color(HIGHLIGHT_COLOR);
+    shape_selector.set_value(DEFAULT_INPUT_SHAPE);
+    shape_selector.set_cursor_color(Color::White);
+    shape_selector.set_text_color(Color::White);
+    shape_selector.handle(move |input, event| match event {
+        Event::KeyUp => {
+            input_shape.replace(input.value());
+            true
+        }
+        _ => false,
+    });
+
+    (shape_border, shape_selector)
+}
+
+fn deterministic_entry(
+    deterministic: Rc<RefCell<bool>>,
+    seed_border: &Frame,
+    p_h: i32,
+) -> (Frame, ToggleButton) {
+    let mut deterministic_border = Frame::default()
+        .with_pos(seed_border.x(), seed_border.y() + p_h / MENU_BAR_RATIO)
+        .with_size(seed_border.w(), seed_border.h());
+    deterministic_border.set_color(Color::White);
+    deterministic_border.set_frame(FrameType::FlatBox);
+    let mut deterministic_text = Frame::default()
+        .with_pos(deterministic_border.x(), deterministic_border.y() + 1)
+        .with_size(deterministic_border.w() / 2, deterministic_border.h() - 2)
+        .with_label("Deterministic: ")
+        .with_align(Align::Inside | Align::Left);
+    deterministic_text.set_label_color(Color::White);
+    deterministic_text.set_frame(FrameType::FlatBox);
+    deterministic_text.set_color(BG_COLOR);
+    // Handle events
+    let mut deterministic_selector = ToggleButton::default()
+        .with_pos(deterministic_text.w() + 4, deterministic_text.y())
+        .with_size(deterministic_text.w() - 5, deterministic_border.h() - 2)
+        .with_label("✖️");
+    deterministic_selector.set_color(BG_COLOR);
+    deterministic_selector.set_frame(FrameType::FlatBox);
+    deterministic_selector.set_selection_color(HIGHLIGHT_COLOR);
+    deterministic_selector.set_callback(move |btn| {
+        btn.set_label(if btn.is_set() { "✔️" } else { "✖️" });
+        deterministic.replace(btn.is_set());
+    });
+    (deterministic_border, deterministic_selector)
+}
+
+fn seed_entry(
+    seed: Rc<RefCell<Option<u64>>>,
+    early_stopping_border: &Frame,
+    p_h: i32,
+) -> (Frame, IntInput) {
+    let mut seed_border = Frame::default()
+        .with_pos(
+            early_stopping_border.x(),
+            early_stopping_border.y() + p_h / MENU_BAR_RATIO,
+        )
+        .with_size(early_stopping_border.w(), early_stopping_border.h());
+    seed_border.set_color(Color::White);
+    seed_border.set_frame(FrameType::FlatBox);
+    let mut seed_text = Frame::default()
+        .with_pos(seed_border.x(), seed_border.y() + 1)
+        .with_size(seed_border.w() / 2, seed_border.h() - 2)
+        .with_label("Seed (empty: random): ")
+        .with_align(Align::Inside | Align::Left);
+    seed_text.set_label_color(Color::White);
+    seed_text.set_frame(FrameType::FlatBox);
+    seed_text.set_color(BG_COLOR);
+    // Handle events
+    let mut seed_selector = IntInput::default()
+        .with_pos(seed_text.w() + 4, seed_text.y())
+        .with_size(seed_text.w() - 5, seed_border.h() - 2);
+    seed_selector.set_color(BG_COLOR);
+    seed_selector.set_frame(FrameType::FlatBox);
+    seed_selector.set_selection_color(HIGHLIGHT_COLOR);
+    seed_selector.set_cursor_color(Color::White);
+    seed_selector.set_text_color(Color::White);
+    seed_selector.handle(move |input, event| match event {
+        Event::KeyUp => {
+            seed.replace(match input.value().parse::<u64>() {
+                _ if input.value().is_empty() => None,
+                Ok(v) => Some(v),
+                _ => {
+                    CustomDialog::show(200, 40, "Error", "Invalid seed", BG_COLOR, Color::Red);
+                    input.set_value("");
+                    None
+                }
+            });
+            true
+        }
+        _ => false,
+    });
+
+    (seed_border, seed_selector)
+}
+
+fn early_stopping_entry(
+    early_stopping: Rc<RefCell<EarlyStopping>>,
+    epoch_border: &Frame,
+    p_h: i32,
+) -> (Frame, Button) {
+    let mut early_stopping_border = Frame::default()
+        .with_pos(epoch_border.x(), epoch_border.y() + p_h / MENU_BAR_RATIO)
+        .with_size(epoch_border.w(), epoch_border.h());
+    early_stopping_border.set_color(Color::White);
+    early_stopping_border.set_frame(FrameType::FlatBox);
+    let mut early_stopping_text = Frame::default()
+        .with_pos(early_stopping_border.x(), early_stopping_border.y() + 1)
+        .with_size(early_stopping_border.w() / 2, early_stopping_border.h() - 2)
+        .with_label("Early Stopping: ")
+        .with_align(Align::Inside | Align::Left);
+    early_stopping_text.set_label_color(Color::White);
+    early_stopping_text.set_frame(FrameType::FlatBox);
+    early_stopping_text.set_color(BG_COLOR);
+    // Handle events, the values are set in a popup
+    let mut early_stopping_selector = Button::default()
+        .with_pos(early_stopping_text.w() + 4, early_stopping_text.y())
+        .with_size(early_stopping_text.w() - 5, early_stopping_border.h() - 2)
+        .with_label(&early_stopping.borrow().summary());
+    early_stopping_selector.set_align(Align::Inside | Align::Center);
+    early_stopping_selector.set_label_color(Color::White);
+    early_stopping_selector.set_frame(FrameType::FlatBox);
+    early_stopping_selector.set_color(BG_COLOR);
+    early_stopping_selector.set_selection_color(HIGHLIGHT_COLOR);
+    early_stopping_selector.set_callback(move |selector| {
+        EarlyStoppingWidget::show(early_stopping.clone(), selector.clone());
+    });
+    (early_stopping_border, early_stopping_selector)
 }
 
 fn epoch_entry(epochs: Rc<RefCell<usize>>, batch_border: &Frame, p_h: i32) -> (Frame, IntInput) {
@@ -311,7 +718,53 @@ fn epoch_entry(epochs: Rc<RefCell<usize>>, batch_border: &Frame, p_h: i32) -> (F
     (epoch_border, epochs_selector)
 }
 
-fn batch_entry(batch_size: Rc<RefCell<i64>>, lr_border: &Frame, p_h: i32) -> (Frame, IntInput) {
+fn gradients_entry(
+    gradients: Rc<RefCell<GradientConfig>>,
+    batch_size: Rc<RefCell<i64>>,
+    batch_text: Frame,
+    batch_border: &Frame,
+    p_h: i32,
+) -> (Frame, Button) {
+    let mut gradients_border = Frame::default()
+        .with_pos(batch_border.x(), batch_border.y() + p_h / MENU_BAR_RATIO)
+        .with_size(batch_border.w(), batch_border.h());
+    gradients_border.set_color(Color::White);
+    gradients_border.set_frame(FrameType::FlatBox);
+    let mut gradients_text = Frame::default()
+        .with_pos(gradients_border.x(), gradients_border.y() + 1)
+        .with_size(gradients_border.w() / 2, gradients_border.h() - 2)
+        .with_label("Gradients: ")
+        .with_align(Align::Inside | Align::Left);
+    gradients_text.set_label_color(Color::White);
+    gradients_text.set_frame(FrameType::FlatBox);
+    gradients_text.set_color(BG_COLOR);
+    // Handle events, the values are set in a popup
+    let mut gradients_selector = Button::default()
+        .with_pos(gradients_text.w() + 4, gradients_text.y())
+        .with_size(gradients_text.w() - 5, gradients_border.h() - 2)
+        .with_label(&gradients.borrow().summary());
+    gradients_selector.set_align(Align::Inside | Align::Center);
+    gradients_selector.set_label_color(Color::White);
+    gradients_selector.set_frame(FrameType::FlatBox);
+    gradients_selector.set_color(BG_COLOR);
+    gradients_selector.set_selection_color(HIGHLIGHT_COLOR);
+    gradients_selector.set_callback(move |selector| {
+        GradientWidget::show(
+            gradients.clone(),
+            batch_size.clone(),
+            selector.clone(),
+            batch_text.clone(),
+        );
+    });
+    (gradients_border, gradients_selector)
+}
+
+fn batch_entry(
+    batch_size: Rc<RefCell<i64>>,
+    gradients: Rc<RefCell<GradientConfig>>,
+    lr_border: &Frame,
+    p_h: i32,
+) -> (Frame, IntInput, Frame) {
     let mut batch_border = Frame::default()
         .with_pos(lr_border.x(), lr_border.y() + p_h / MENU_BAR_RATIO)
         .with_size(lr_border.w(), lr_border.h());
@@ -335,6 +788,7 @@ fn batch_entry(batch_size: Rc<RefCell<i64>>, lr_border: &Frame, p_h: i32) -> (Fr
     batch_selector.set_value(DEFAULT_BATCH_SIZE_STR);
     batch_selector.set_cursor_color(Color::White);
     batch_selector.set_text_color(Color::White);
+    let mut effective_text = batch_text.clone();
     batch_selector.handle(move |input, event| match event {
         Event::KeyUp => {
             batch_size.replace(match input.value().parse::<i64>() {
@@ -352,12 +806,62 @@ fn batch_entry(batch_size: Rc<RefCell<i64>>, lr_border: &Frame, p_h: i32) -> (Fr
                     DEFAULT_BATCH_SIZE
                 }
             });
+            effective_text.set_label(&batch_label(&gradients.borrow(), *batch_size.borrow()));
             true
         }
         _ => false,
     });
 
-    (batch_border, batch_selector)
+    (batch_border, batch_selector, batch_text)
+}
+
+fn scheduler_entry(
+    scheduler: Rc<RefCell<Scheduler>>,
+    lr: Rc<RefCell<f64>>,
+    epochs: Rc<RefCell<usize>>,
+    lr_border: &Frame,
+    p_h: i32,
+) -> (Frame, Choice) {
+    let mut scheduler_border = Frame::default()
+        .with_pos(lr_border.x(), lr_border.y() + p_h / MENU_BAR_RATIO)
+        .with_size(lr_border.w(), lr_border.h());
+    scheduler_border.set_color(Color::White);
+    scheduler_border.set_frame(FrameType::FlatBox);
+    let mut scheduler_text = Frame::default()
+        .with_pos(scheduler_border.x(), scheduler_border.y() + 1)
+        .with_size(scheduler_border.w() / 2, scheduler_border.h() - 2)
+        .with_label("LR Scheduler: ")
+        .with_align(Align::Inside | Align::Left);
+    scheduler_text.set_label_color(Color::White);
+    scheduler_text.set_frame(FrameType::FlatBox);
+    scheduler_text.set_color(BG_COLOR);
+    // Handle events
+    let mut scheduler_selector = Choice::default()
+        .with_pos(scheduler_text.w() + 4, scheduler_text.y())
+        .with_size(scheduler_text.w() - 5, scheduler_border.h() - 2)
+        .with_label(scheduler.borrow().name());
+    scheduler_selector.set_align(Align::Inside | Align::Center);
+    scheduler_selector.set_label_color(Color::White);
+    scheduler_selector.set_frame(FrameType::FlatBox);
+    scheduler_selector.set_color(BG_COLOR);
+    scheduler_selector.set_selection_color(HIGHLIGHT_COLOR);
+    for scheduler_name in SCHEDULERS.iter() {
+        let i = scheduler_selector.add_choice(scheduler_name);
+        let mut entry = scheduler_selector.at(i).unwrap();
+        entry.set_label_color(Color::White);
+    }
+    scheduler_selector.set_callback(move |selector| {
+        let value = selector.value();
+        selector.set_value(-1);
+        SchedulerWidget::show(
+            value as usize,
+            scheduler.clone(),
+            *lr.borrow(),
+            *epochs.borrow(),
+            selector.clone(),
+        );
+    });
+    (scheduler_border, scheduler_selector)
 }
 
 fn lr_entry(lr: Rc<RefCell<f64>>, loss_border: &Frame, p_h: i32) -> (Frame, FloatInput) {
@@ -450,7 +954,7 @@ fn loss_entry(
 }
 
 fn optimizer_entry(
-    optimizer: Rc<RefCell<Option<String>>>,
+    optimizer: Rc<RefCell<Option<OptimizerType>>>,
     device_border: &Frame,
     p_h: i32,
 ) -> (Frame, Choice) {
@@ -485,9 +989,7 @@ fn optimizer_entry(
     optimizer_selector.set_callback(move |selector| {
         let value = selector.value();
         selector.set_value(-1);
-        let name = selector.at(value).unwrap().label().unwrap().to_string();
-        selector.set_label(name.as_str());
-        optimizer.replace(Some(name));
+        OptimizerWidget::show(value as usize, optimizer.clone(), selector.clone());
     });
     (optimizer_border, optimizer_selector)
 }
@@ -611,32 +1113,9 @@ fn save_path_entry(save: Rc<RefCell<Option<PathBuf>>>, p_w: i32, p_h: i32) -> (F
     save_path_selector.set_color(BG_COLOR);
     save_path_selector.set_selection_color(HIGHLIGHT_COLOR);
     save_path_selector.set_callback(move |selector| {
-        let mut dialog = NativeFileChooser::new(FileDialogType::BrowseSaveFile);
-        dialog.show();
-        let filename = dialog.filename();
-        if filename.ne(&PathBuf::new()) {
-            if let Some(ext) = filename.extension() {
-                if ext.ne(OsStr::new("pt")) {
-                    CustomDialog::show(
-                        300,
-                        40,
-                        "Error",
-                        "Extension must be either empty or .pt",
-                        BG_COLOR,
-                        Color::Red,
-                    );
-                } else {
-                    let file = filename.with_extension("pt");
-                    selector.set_label(file.file_name().unwrap().to_str().unwrap());
-                    save.replace(Some(file));
-                }
-            } else {
-                let file = filename.with_extension("pt");
-                selector.set_label(file.file_name().unwrap().to_str().unwrap());
-                save.replace(Some(file));
-            };
-        } else {
-            CustomDialog::show(200, 40, "Error", "No file selected", BG_COLOR, Color::Red);
+        if let Some(file) = save_file_dialog(&SAVE_EXTENSIONS) {
+            selector.set_label(file.file_name().unwrap().to_str().unwrap());
+            save.replace(Some(file));
         }
     });
     (border, save_path_selector)
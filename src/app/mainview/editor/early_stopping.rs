@@ -0,0 +1,53 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fltk::button::Button;
+use fltk::enums::{Color, FrameType};
+use fltk::prelude::{ButtonExt, GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::window::Window;
+
+use crate::training::early_stopping::{EarlyStopping, MODES};
+use crate::training::metrics::METRICS;
+use crate::utils::consts::{BG_COLOR, FORM_WINDOW_WIDTH};
+use crate::utils::form;
+
+pub(crate) struct EarlyStoppingWidget {}
+
+impl EarlyStoppingWidget {
+    // the entry button shows the summary of the values once the popup closes
+    pub(crate) fn show(config: Rc<RefCell<EarlyStopping>>, mut entry: Button) {
+        let current = config.borrow().clone();
+        let mut window = Window::default()
+            .with_size(FORM_WINDOW_WIDTH, form::height(6))
+            .with_label("Early Stopping")
+            .center_screen();
+        window.set_label_color(Color::White);
+        window.set_color(BG_COLOR);
+        window.set_frame(FrameType::FlatBox);
+        let enabled = form::toggle_row(0, "Enabled: ", current.enabled);
+        let monitor = form::choice_row(1, "Monitor: ", &METRICS, &current.monitor);
+        let mode = form::choice_row(2, "Mode: ", &MODES, &current.mode);
+        let patience = form::int_row(3, "Patience (epochs): ", current.patience as i64);
+        let min_delta = form::float_row(4, "Min delta: ", current.min_delta);
+        let restore_best = form::toggle_row(5, "Restore best weights: ", current.restore_best);
+        window.end();
+        window.show();
+        window.set_callback(move |window| {
+            window.hide();
+            if let (Some(patience), Some(min_delta)) = (
+                form::parse::<usize>(&patience.value(), "Patience"),
+                form::parse::<f64>(&min_delta.value(), "Min delta"),
+            ) {
+                config.replace(EarlyStopping {
+                    enabled: enabled.is_set(),
+                    monitor: monitor.label(),
+                    mode: mode.label(),
+                    patience: patience.max(1),
+                    min_delta: min_delta.abs(),
+                    restore_best: restore_best.is_set(),
+                });
+            }
+            entry.set_label(&config.borrow().summary());
+        });
+    }
+}
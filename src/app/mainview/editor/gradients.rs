@@ -0,0 +1,75 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fltk::button::Button;
+use fltk::enums::{Color, FrameType};
+use fltk::frame::Frame;
+use fltk::prelude::{GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::window::Window;
+
+use crate::training::gradients::{Clipping, GradientConfig, CLIPPING_MODES};
+use crate::utils::consts::{BG_COLOR, FORM_WINDOW_WIDTH};
+use crate::utils::form;
+
+pub(crate) struct GradientWidget {}
+
+impl GradientWidget {
+    // the entry shows the summary and the batch size label the effective
+    // batch size once the popup closes
+    pub(crate) fn show(
+        config: Rc<RefCell<GradientConfig>>,
+        batch_size: Rc<RefCell<i64>>,
+        mut entry: Button,
+        mut batch_text: Frame,
+    ) {
+        let current = *config.borrow();
+        let mut window = Window::default()
+            .with_size(FORM_WINDOW_WIDTH, form::height(3))
+            .with_label("Gradients")
+            .center_screen();
+        window.set_label_color(Color::White);
+        window.set_color(BG_COLOR);
+        window.set_frame(FrameType::FlatBox);
+        let mode = form::choice_row(
+            0,
+            "Clipping: ",
+            &CLIPPING_MODES,
+            CLIPPING_MODES[current.clipping.index()],
+        );
+        let threshold = form::float_row(1, "Max norm / value: ", current.clipping.threshold());
+        let accumulation = form::int_row(2, "Accumulate batches: ", current.accumulation as i64);
+        window.end();
+        window.show();
+        window.set_callback(move |window| {
+            window.hide();
+            if let (Some(threshold), Some(accumulation)) = (
+                form::parse::<f64>(&threshold.value(), "Max norm / value"),
+                form::parse::<usize>(&accumulation.value(), "Accumulate batches"),
+            ) {
+                let threshold = threshold.abs();
+                config.replace(GradientConfig {
+                    clipping: match CLIPPING_MODES.iter().position(|m| *m == mode.label()) {
+                        Some(1) => Clipping::Norm(threshold),
+                        Some(2) => Clipping::Value(threshold),
+                        _ => Clipping::None,
+                    },
+                    accumulation: accumulation.max(1),
+                });
+            }
+            entry.set_label(&config.borrow().summary());
+            batch_text.set_label(&batch_label(&config.borrow(), *batch_size.borrow()));
+        });
+    }
+}
+
+// the batch size entry names the effective batch size when accumulating
+pub(crate) fn batch_label(config: &GradientConfig, batch_size: i64) -> String {
+    if config.accumulation > 1 {
+        format!(
+            "Batch Size (eff. {}): ",
+            config.effective_batch_size(batch_size)
+        )
+    } else {
+        "Batch Size: ".to_string()
+    }
+}
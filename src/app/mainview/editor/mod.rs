@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::rc::Rc;
 
 use fltk::prelude::{GroupExt, WidgetBase, WidgetExt};
@@ -7,11 +8,21 @@ use fltk::window::{DoubleWindow, Window};
 use crate::app::mainview::editor::component_list::ComponentList;
 use crate::app::mainview::editor::configs::ConfingList;
 use crate::app::mainview::editor::playground::Playground;
+use crate::project::graph::Graph;
+use crate::project::{parse_device, Project};
+use crate::training::checkpoint::CheckpointConfig;
+use crate::training::metrics::ValidationConfig;
+use crate::training::tensorboard::TensorBoardConfig;
+use crate::training::Job;
 use crate::utils::consts::{COMPONENT_LIST_RATIO, CONFIG_LIST_RATIO};
 
 mod component_list;
 mod configs;
+mod early_stopping;
+mod gradients;
 mod playground;
+mod scheduler;
+mod summary;
 
 pub(crate) struct EditorView {
     window: DoubleWindow,
@@ -49,4 +60,59 @@ impl EditorView {
             conf_list,
         }
     }
+    pub(crate) fn project(&self) -> Project {
+        Project::new(self.graph.borrow().graph(), self.conf_list.settings())
+    }
+    pub(crate) fn load_project(&mut self, project: &Project) -> Result<(), String> {
+        // validated before anything is replaced
+        if let Some(device) = project.settings.device.as_deref() {
+            parse_device(device)?;
+        }
+        self.graph.borrow_mut().load_graph(&project.graph)?;
+        self.conf_list.load_settings(&project.settings)
+    }
+    pub(crate) fn show_summary(&self) {
+        self.graph.borrow().show_summary();
+    }
+    pub(crate) fn load_weights(&self) {
+        self.graph.borrow().load_weights();
+    }
+    pub(crate) fn load_graph(&mut self, graph: &Graph) -> Result<(), String> {
+        self.graph.borrow_mut().load_graph(graph)
+    }
+    pub(crate) fn training_job(
+        &self,
+        dataset: PathBuf,
+        checkpoints: CheckpointConfig,
+        validation: ValidationConfig,
+        tensorboard: TensorBoardConfig,
+    ) -> Result<Job, String> {
+        self.graph
+            .borrow()
+            .training_job(dataset, checkpoints, validation, tensorboard)
+    }
+    pub(crate) fn reload_weights(&self) -> Result<(), String> {
+        self.graph.borrow().reload_weights()
+    }
+    pub(crate) fn export_dot(&self) {
+        self.graph.borrow().export_dot();
+    }
+    pub(crate) fn export_diagram_svg(&self) {
+        self.graph.borrow().export_diagram_svg();
+    }
+    pub(crate) fn export_diagram_png(&self) {
+        self.graph.borrow().export_diagram_png();
+    }
+    pub(crate) fn export_torchscript(&self) {
+        self.graph.borrow().export_torchscript();
+    }
+    pub(crate) fn export_onnx(&self) {
+        self.graph.borrow().export_onnx();
+    }
+    pub(crate) fn export_rust_code(&self) {
+        self.graph.borrow().export_rust_code();
+    }
+    pub(crate) fn export_python_code(&self) {
+        self.graph.borrow().export_python_code();
+    }
 }
@@ -1,5 +1,6 @@
 use std::cell::RefCell;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use fltk::app::MouseButton;
@@ -11,18 +12,31 @@ use fltk::group::Group;
 use fltk::image::{PngImage, SvgImage};
 use fltk::prelude::{GroupExt, ImageExt, WidgetBase, WidgetExt};
 use fltk::window::Window;
-use tch::Device;
 
+use crate::app::mainview::editor::summary::SummaryWidget;
 use crate::components::NNComponent;
+use crate::model::summary::Summary;
+use crate::model::weights::WEIGHT_EXTENSIONS;
+use crate::model::{onnx, python_code, rust_code, torchscript, weights, Hyperparameters, Model};
+use crate::project::diagram::PNG_SCALE_RANGE;
+use crate::project::graph::{Graph, Node};
+use crate::project::{diagram, dot, snapshot, Project, Settings};
+use crate::training::checkpoint::CheckpointConfig;
+use crate::training::metrics::ValidationConfig;
+use crate::training::tensorboard::TensorBoardConfig;
+use crate::training::Job;
 use crate::utils::consts::{BASE_COMPONENT_HEIGHT, BASE_COMPONENT_WIDTH, BG_COLOR};
-use crate::utils::loss_fn::LossFunction;
-use crate::utils::CustomDialog;
+use crate::utils::{open_file_dialog, save_file_dialog, CustomDialog};
 
 pub(crate) struct Playground {
     draw_area: Rc<RefCell<Window>>,
     components: Rc<RefCell<Vec<NNComponent>>>,
     first: Rc<RefCell<Option<usize>>>,
-    first_component: Rc<RefCell<Option<Frame>>>,
+    groups: Vec<Group>,
+    model: Option<Model>,
+    // the weights file loaded into the model, runs start from a new
+    // initialization otherwise
+    pretrained: RefCell<Option<PathBuf>>,
 }
 
 impl Playground {
@@ -35,7 +49,9 @@ impl Playground {
             draw_area: Rc::new(RefCell::new(draw_area)),
             components: Rc::new(RefCell::new(vec![])),
             first: Rc::new(RefCell::new(None)),
-            first_component: Rc::new(RefCell::new(None)),
+            groups: vec![],
+            model: None,
+            pretrained: RefCell::new(None),
         }
     }
 
@@ -43,7 +59,6 @@ impl Playground {
         let i = self.components.borrow().len();
         let draw_area = self.draw_area.clone();
         let set_first_ref = self.first.clone();
-        let first_component = self.first_component.clone();
         let elems = self.components.clone();
         let label = *nn_comp;
         let mut g = Group::default()
@@ -64,280 +79,487 @@ impl Playground {
         inner_component.set_label_color(Color::White);
         inner_component.set_frame(FrameType::FlatBox);
         inner_component.set_color(BG_COLOR);
-        // configured sign
-        let mut img = SvgImage::load("src/assets/cross.svg").unwrap();
-        inner_component.draw(move |f| {
-            img.scale(15, 15, true, true);
-            img.draw(
-                f.x() + f.w() - img.w(),
-                f.y() + f.h() - img.h(),
-                img.w(),
-                img.h(),
-            );
-        });
+        // first and configured signs
+        let status_first = self.first.clone();
+        let status_elems = self.components.clone();
+        inner_component.draw(move |f| draw_status(f, i, &status_first, &status_elems));
         g.end();
         draw_area.borrow_mut().add(&g);
         draw_area.borrow_mut().redraw();
+        self.groups.push(g.clone());
         let mut set = false;
         let mut prev = (-1, -1);
-        let inner_component_handler = Rc::new(RefCell::new(Some(inner_component)));
-        let mv_inner_component = inner_component_handler.clone();
-        inner_component_handler
-            .borrow_mut()
-            .as_mut()
-            .unwrap()
-            .handle(move |_, event| match event {
-                Event::Push => {
-                    match fltk::app::event_mouse_button() {
-                        MouseButton::Left => {
-                            set = true;
-                            prev = fltk::app::event_coords();
-                        }
-                        MouseButton::Right => {
-                            let coords = fltk::app::event_coords();
-                            let mut group = Group::default()
-                                .with_pos(coords.0, coords.1)
-                                .with_size(75, 61);
-                            let mut configuration_window = Frame::default()
-                                .with_size(group.w(), group.h())
-                                .center_of(&group);
-                            configuration_window.set_color(Color::White);
-                            configuration_window.set_frame(FrameType::FlatBox);
-                            let mut set_first_frame = Frame::default()
-                                .with_pos(
-                                    configuration_window.x() + 1,
-                                    configuration_window.y() + 1,
-                                )
-                                .with_size(
-                                    configuration_window.w() - 2,
-                                    configuration_window.h() / 2 - 1,
-                                )
-                                .with_label("Set First");
-                            set_first_frame.set_frame(FrameType::FlatBox);
-                            set_first_frame.set_label_color(Color::White);
-                            set_first_frame.set_align(Align::Center);
-                            set_first_frame.set_color(BG_COLOR);
-                            let mut config_frame = Frame::default()
-                                .with_pos(
-                                    set_first_frame.x(),
-                                    set_first_frame.y() + set_first_frame.h() + 1,
-                                )
-                                .with_size(set_first_frame.w(), set_first_frame.h())
-                                .with_label("Configure");
-                            config_frame.set_frame(FrameType::FlatBox);
-                            config_frame.set_label_color(Color::White);
-                            config_frame.set_align(Align::Center);
-                            config_frame.set_color(BG_COLOR);
-                            group.end();
-                            let mv_inner_component = mv_inner_component.clone();
-                            let mv_inner_config = mv_inner_component.clone();
-                            let mv_first_component = first_component.clone();
-                            let mv_elems = elems.clone();
-                            let config_elems = mv_elems.clone();
-                            let c = draw_area.clone();
-                            let set_first_ref = set_first_ref.clone();
-                            let set_first_ref_config = set_first_ref.clone();
-                            set_first_frame.handle(move |_, evt| match evt {
-                                Event::Push => {
-                                    let inner_elem = mv_elems.clone();
-                                    let prev_i = *set_first_ref.borrow();
-                                    if prev_i.is_none() || prev_i.is_some() && prev_i.unwrap() != i
-                                    {
-                                        let configured = if let Some(prev_i) =
-                                            set_first_ref.borrow_mut().replace(i)
-                                        {
-                                            let e = mv_elems.borrow().get(prev_i).cloned().unwrap();
-                                            match e {
-                                                NNComponent::Layer { configured, .. } => configured,
-                                                NNComponent::ActivationFunction { .. } => true,
-                                            }
-                                        } else {
-                                            false
-                                        };
-                                        mv_inner_component.borrow_mut().as_mut().unwrap().draw(
-                                            move |f| {
-                                                let mut first =
-                                                    PngImage::load("src/assets/star.png").unwrap();
-                                                first.scale(15, 15, true, true);
-                                                first.draw(f.x(), f.y(), first.w(), first.h());
-                                                if configured {
-                                                    let mut first =
-                                                        SvgImage::load("src/assets/tick.svg")
-                                                            .unwrap();
-                                                    first.scale(15, 15, true, true);
-                                                    first.draw(
-                                                        f.x() + f.w() - first.w(),
-                                                        f.y() + f.h() - first.h(),
-                                                        first.w(),
-                                                        first.h(),
-                                                    );
-                                                } else {
-                                                    let mut first =
-                                                        SvgImage::load("src/assets/cross.svg")
-                                                            .unwrap();
-                                                    first.scale(15, 15, true, true);
-                                                    first.draw(
-                                                        f.x() + f.w() - first.w(),
-                                                        f.y() + f.h() - first.h(),
-                                                        first.w(),
-                                                        first.h(),
-                                                    );
-                                                }
-                                            },
-                                        );
-                                        if let Some(f) = mv_first_component.borrow_mut().as_mut() {
-                                            f.draw(move |f| {
-                                                let e =
-                                                    inner_elem.borrow().get(i).cloned().unwrap();
-                                                let configured = match e {
-                                                    NNComponent::Layer { configured, .. } => {
-                                                        configured
-                                                    }
-                                                    NNComponent::ActivationFunction { .. } => true,
-                                                };
-                                                if configured {
-                                                    let mut first =
-                                                        SvgImage::load("src/assets/tick.svg")
-                                                            .unwrap();
-                                                    first.scale(15, 15, true, true);
-                                                    first.draw(
-                                                        f.x() + f.w() - first.w(),
-                                                        f.y() + f.h() - first.h(),
-                                                        first.w(),
-                                                        first.h(),
-                                                    );
-                                                } else {
-                                                    let mut first =
-                                                        SvgImage::load("src/assets/cross.svg")
-                                                            .unwrap();
-                                                    first.scale(15, 15, true, true);
-                                                    first.draw(
-                                                        f.x() + f.w() - first.w(),
-                                                        f.y() + f.h() - first.h(),
-                                                        first.w(),
-                                                        first.h(),
-                                                    );
-                                                }
-                                            });
-                                        }
-                                        mv_first_component
-                                            .replace(mv_inner_component.borrow().clone());
-                                        c.borrow_mut().redraw();
-                                    }
-                                    true
-                                }
-                                _ => false,
-                            });
-                            config_frame.handle(move |_, evt| match evt {
-                                Event::Push => {
-                                    if let NNComponent::Layer { configured, .. } =
-                                        config_elems.borrow_mut().get_mut(i).unwrap()
-                                    {
-                                        *configured = true;
-                                    }
-                                    let first_ref = set_first_ref_config.clone();
-                                    let config_elems = config_elems.clone();
-                                    mv_inner_config
-                                        .borrow_mut()
-                                        .as_mut()
-                                        .unwrap()
-                                        .draw(move |f| {
-                                            let first_ref = first_ref.borrow();
-                                            if first_ref.is_some() && first_ref.unwrap() == i {
-                                                let mut first =
-                                                    PngImage::load("src/assets/star.png").unwrap();
-                                                first.scale(15, 15, true, true);
-                                                first.draw(f.x(), f.y(), first.w(), first.h());
-                                            }
-                                            let e = config_elems.borrow().get(i).cloned().unwrap();
-                                            let configured = match e {
-                                                NNComponent::Layer { configured, .. } => configured,
-                                                NNComponent::ActivationFunction { .. } => true,
-                                            };
-                                            if configured {
-                                                let mut first =
-                                                    SvgImage::load("src/assets/tick.svg").unwrap();
-                                                first.scale(15, 15, true, true);
-                                                first.draw(
-                                                    f.x() + f.w() - first.w(),
-                                                    f.y() + f.h() - first.h(),
-                                                    first.w(),
-                                                    first.h(),
-                                                );
-                                            } else {
-                                                let mut first =
-                                                    SvgImage::load("src/assets/cross.svg").unwrap();
-                                                first.scale(15, 15, true, true);
-                                                first.draw(
-                                                    f.x() + f.w() - first.w(),
-                                                    f.y() + f.h() - first.h(),
-                                                    first.w(),
-                                                    first.h(),
-                                                );
-                                            }
-                                        });
-                                    true
-                                }
-                                _ => false,
-                            });
-                            let c = draw_area.clone();
-                            group.handle(move |group, event| match event {
-                                Event::Leave => {
-                                    c.borrow_mut().remove(group);
-                                    c.borrow_mut().redraw();
-                                    true
+        inner_component.handle(move |_, event| match event {
+            Event::Push => {
+                match fltk::app::event_mouse_button() {
+                    MouseButton::Left => {
+                        set = true;
+                        prev = fltk::app::event_coords();
+                    }
+                    MouseButton::Right => {
+                        let coords = fltk::app::event_coords();
+                        let mut group = Group::default()
+                            .with_pos(coords.0, coords.1)
+                            .with_size(75, 61);
+                        let mut configuration_window = Frame::default()
+                            .with_size(group.w(), group.h())
+                            .center_of(&group);
+                        configuration_window.set_color(Color::White);
+                        configuration_window.set_frame(FrameType::FlatBox);
+                        let mut set_first_frame = Frame::default()
+                            .with_pos(configuration_window.x() + 1, configuration_window.y() + 1)
+                            .with_size(
+                                configuration_window.w() - 2,
+                                configuration_window.h() / 2 - 1,
+                            )
+                            .with_label("Set First");
+                        set_first_frame.set_frame(FrameType::FlatBox);
+                        set_first_frame.set_label_color(Color::White);
+                        set_first_frame.set_align(Align::Center);
+                        set_first_frame.set_color(BG_COLOR);
+                        let mut config_frame = Frame::default()
+                            .with_pos(
+                                set_first_frame.x(),
+                                set_first_frame.y() + set_first_frame.h() + 1,
+                            )
+                            .with_size(set_first_frame.w(), set_first_frame.h())
+                            .with_label("Configure");
+                        config_frame.set_frame(FrameType::FlatBox);
+                        config_frame.set_label_color(Color::White);
+                        config_frame.set_align(Align::Center);
+                        config_frame.set_color(BG_COLOR);
+                        group.end();
+                        let set_first_ref = set_first_ref.clone();
+                        let c = draw_area.clone();
+                        set_first_frame.handle(move |_, evt| match evt {
+                            Event::Push => {
+                                // the previous first loses its star on redraw
+                                set_first_ref.replace(Some(i));
+                                c.borrow_mut().redraw();
+                                true
+                            }
+                            _ => false,
+                        });
+                        let config_elems = elems.clone();
+                        let c = draw_area.clone();
+                        config_frame.handle(move |_, evt| match evt {
+                            Event::Push => {
+                                if let NNComponent::Layer { configured, .. } =
+                                    config_elems.borrow_mut().get_mut(i).unwrap()
+                                {
+                                    *configured = true;
                                 }
-                                _ => false,
-                            });
-                            draw_area.borrow_mut().add(&group);
-                            draw_area.borrow_mut().redraw();
-                        }
-                        _ => {}
+                                c.borrow_mut().redraw();
+                                true
+                            }
+                            _ => false,
+                        });
+                        let c = draw_area.clone();
+                        group.handle(move |group, event| match event {
+                            Event::Leave => {
+                                c.borrow_mut().remove(group);
+                                c.borrow_mut().redraw();
+                                true
+                            }
+                            _ => false,
+                        });
+                        draw_area.borrow_mut().add(&group);
+                        draw_area.borrow_mut().redraw();
                     }
-                    true
+                    _ => {}
                 }
-                Event::Drag if set => {
-                    let new_coords = fltk::app::event_coords();
-                    let (new_x, new_y) = (
-                        component.x() + (new_coords.0 - prev.0),
-                        component.y() + (new_coords.1 - prev.1),
-                    );
-                    prev = new_coords;
-                    g.set_pos(new_x, new_y);
-                    draw_area.borrow_mut().redraw();
-                    true
-                }
-                _ => false,
-            });
+                true
+            }
+            Event::Drag if set => {
+                let new_coords = fltk::app::event_coords();
+                let (new_x, new_y) = (
+                    component.x() + (new_coords.0 - prev.0),
+                    component.y() + (new_coords.1 - prev.1),
+                );
+                prev = new_coords;
+                g.set_pos(new_x, new_y);
+                draw_area.borrow_mut().redraw();
+                true
+            }
+            _ => false,
+        });
         self.components.borrow_mut().push(nn_comp);
     }
 
-    pub(crate) fn build_model(
+    pub(crate) fn clear(&mut self) {
+        self.draw_area.borrow_mut().clear();
+        self.draw_area.borrow_mut().redraw();
+        self.components.borrow_mut().clear();
+        self.first.replace(None);
+        self.groups.clear();
+        self.model = None;
+    }
+
+    pub(crate) fn graph(&self) -> Graph {
+        Graph {
+            nodes: self
+                .components
+                .borrow()
+                .iter()
+                .zip(self.groups.iter())
+                .map(|(component, g)| Node::from_component(component, g.x(), g.y()))
+                .collect(),
+            first: *self.first.borrow(),
+        }
+    }
+
+    // replaces the playground content, nothing is changed if the graph is invalid
+    pub(crate) fn load_graph(&mut self, graph: &Graph) -> Result<(), String> {
+        let components = graph
+            .nodes
+            .iter()
+            .map(Node::to_component)
+            .collect::<Result<Vec<_>, _>>()?;
+        let n = components.len();
+        let out_of_range = |i: &usize| *i >= n;
+        if graph.first.iter().any(out_of_range)
+            || components
+                .iter()
+                .filter_map(NNComponent::next)
+                .any(|i| out_of_range(&i))
+        {
+            return Err("Invalid node reference".to_string());
+        }
+        self.clear();
+        for (component, node) in components.into_iter().zip(graph.nodes.iter()) {
+            self.add_component(component);
+            self.groups.last_mut().unwrap().set_pos(node.x, node.y);
+        }
+        self.first.replace(graph.first);
+        self.draw_area.borrow_mut().redraw();
+        Ok(())
+    }
+
+    pub(crate) fn build_model(&mut self, hyperparameters: Hyperparameters, input_shape: Vec<i64>) {
+        let save_path = hyperparameters.save_path.clone();
+        let project = Project::new(
+            self.graph(),
+            Settings::from_hyperparameters(&hyperparameters, &input_shape),
+        );
+        let model = Model::build(
+            &self.components.borrow(),
+            *self.first.borrow(),
+            input_shape,
+            hyperparameters,
+        )
+        .and_then(|model| {
+            weights::save(&model, &save_path)?;
+            Ok(model)
+        });
+        self.pretrained.replace(None);
+        match model {
+            Ok(model) => {
+                self.model = Some(model);
+                match snapshot::take(&project, &save_path) {
+                    Ok(_) => CustomDialog::show(
+                        220,
+                        40,
+                        "Success",
+                        "Model built successfully",
+                        BG_COLOR,
+                        Color::Green,
+                    ),
+                    Err(e) => CustomDialog::show(
+                        400,
+                        60,
+                        "Error",
+                        &format!("Model built, but the snapshot failed: {}", e),
+                        BG_COLOR,
+                        Color::Red,
+                    ),
+                }
+            }
+            Err(e) => {
+                self.model = None;
+                CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+            }
+        }
+    }
+
+    pub(crate) fn show_summary(&self) {
+        match self.model.as_ref() {
+            Some(model) => SummaryWidget::show(Summary::new(model)),
+            None => CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "Build the model first",
+                BG_COLOR,
+                Color::Red,
+            ),
+        }
+    }
+
+    pub(crate) fn load_weights(&self) {
+        let Some(model) = self.model.as_ref() else {
+            CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "Build the model first",
+                BG_COLOR,
+                Color::Red,
+            );
+            return;
+        };
+        let Some(path) = open_file_dialog(&WEIGHT_EXTENSIONS) else {
+            return;
+        };
+        let tensors = match weights::read(&path) {
+            Ok(tensors) => tensors,
+            Err(e) => {
+                CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+                return;
+            }
+        };
+        let report = match weights::compare(model, &path, &tensors) {
+            Ok(report) => report,
+            Err(e) => {
+                CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+                return;
+            }
+        };
+        let strict = match fltk::dialog::choice2_default(
+            &format!("{}\nLoad the weights?", report),
+            "Cancel",
+            "Strict",
+            "Partial",
+        ) {
+            Some(1) => true,
+            Some(2) => false,
+            _ => return,
+        };
+        match weights::load(model, &path, &tensors, strict) {
+            Ok(report) => {
+                self.pretrained.replace(Some(path));
+                CustomDialog::show(
+                    260,
+                    40,
+                    "Success",
+                    &format!("Loaded {} tensors", report.loaded.len()),
+                    BG_COLOR,
+                    Color::Green,
+                )
+            }
+            Err(e) => CustomDialog::show(400, 120, "Error", &e, BG_COLOR, Color::Red),
+        }
+    }
+
+    // the built model with its hyperparameters, trained from the seed or on a
+    // copy of the weights that were loaded into it
+    pub(crate) fn training_job(
+        &self,
+        dataset: PathBuf,
+        checkpoints: CheckpointConfig,
+        validation: ValidationConfig,
+        tensorboard: TensorBoardConfig,
+    ) -> Result<Job, String> {
+        let model = self.model.as_ref().ok_or("Build the model first")?;
+        Ok(Job {
+            graph: self.graph(),
+            hyperparameters: model.hyperparameters.clone(),
+            input_shape: model.input_shape.clone(),
+            weights: self.pretrained.borrow().as_ref().map(|_| {
+                model
+                    .vs
+                    .variables()
+                    .into_iter()
+                    .map(|(name, var)| (name, var.detach().copy()))
+                    .collect()
+            }),
+            pretrained: self.pretrained.borrow().clone(),
+            optimizer: None,
+            dataset,
+            checkpoints,
+            validation,
+            tensorboard,
+            resume: None,
+        })
+    }
+
+    // picks up the weights a training run saved, unless they belong to
+    // another model, e.g. of a resumed checkpoint
+    pub(crate) fn reload_weights(&self) -> Result<(), String> {
+        let Some(model) = self.model.as_ref() else {
+            return Ok(());
+        };
+        let path = &model.hyperparameters.save_path;
+        let tensors = weights::read(path)?;
+        if weights::compare(model, path, &tensors)?.is_exact() {
+            weights::load(model, path, &tensors, true)?;
+            // the trained weights are shown, the next run starts from the seed
+            self.pretrained.replace(None);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn export_diagram_svg(&self) {
+        let shapes = self.output_shapes();
+        self.export_graph("svg", "Diagram exported", move |graph, path| {
+            diagram::export_svg(graph, &shapes, path)
+        });
+    }
+
+    pub(crate) fn export_diagram_png(&self) {
+        if self.components.borrow().is_empty() {
+            CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "The playground is empty",
+                BG_COLOR,
+                Color::Red,
+            );
+            return;
+        }
+        let Some(scale) = fltk::dialog::input_default("Scale:", "2") else {
+            return;
+        };
+        let (min, max) = PNG_SCALE_RANGE;
+        let scale = match scale.trim().parse::<f64>() {
+            Ok(scale) if (min..=max).contains(&scale) => scale,
+            _ => {
+                CustomDialog::show(
+                    300,
+                    40,
+                    "Error",
+                    &format!("The scale must be between {} and {}", min, max),
+                    BG_COLOR,
+                    Color::Red,
+                );
+                return;
+            }
+        };
+        let shapes = self.output_shapes();
+        self.export_graph("png", "Diagram exported", move |graph, path| {
+            diagram::export_png(graph, &shapes, scale, path)
+        });
+    }
+
+    // the output shape of every node of the built model, none when it was
+    // built from another graph
+    fn output_shapes(&self) -> HashMap<usize, Vec<i64>> {
+        let Some(model) = self.model.as_ref() else {
+            return HashMap::new();
+        };
+        let components = self.components.borrow();
+        let same = model.nodes.iter().all(|(i, component)| {
+            components.get(*i).is_some_and(|c| {
+                c.to_string() == component.to_string()
+                    && c.config_description() == component.config_description()
+            })
+        });
+        if !same {
+            return HashMap::new();
+        }
+        model
+            .nodes
+            .iter()
+            .zip(model.output_shapes.iter())
+            .map(|((i, _), shape)| (*i, shape.clone()))
+            .collect()
+    }
+
+    pub(crate) fn export_dot(&self) {
+        self.export_graph("dot", "Graph exported", dot::save);
+    }
+
+    pub(crate) fn export_torchscript(&self) {
+        self.export("pt", "TorchScript module exported", torchscript::export);
+    }
+
+    pub(crate) fn export_onnx(&self) {
+        self.export("onnx", "ONNX model exported", onnx::export);
+    }
+
+    pub(crate) fn export_rust_code(&self) {
+        self.export("rs", "Rust module generated", rust_code::export);
+    }
+
+    pub(crate) fn export_python_code(&self) {
+        self.export("py", "PyTorch module generated", python_code::export);
+    }
+
+    // exports of the playground graph, they do not need a built model
+    fn export_graph(
         &self,
-        save_path: PathBuf,
-        device: Device,
-        optimizer: String,
-        loss_fn: LossFunction,
-        lr: f64,
-        batch_size: i64,
-        epochs: usize,
+        ext: &str,
+        success: &str,
+        f: impl Fn(&Graph, &Path) -> Result<(), String>,
     ) {
-        CustomDialog::show(
-            220,
-            40,
-            "Success",
-            "Model built successfully",
-            BG_COLOR,
-            Color::Green,
-        );
-        eprintln!("Building model");
-        eprintln!("Save path: {:?}", save_path);
-        eprintln!("Device: {:?}", device);
-        eprintln!("Optimizer: {}", optimizer);
-        eprintln!("Loss function: {:?}", loss_fn);
-        eprintln!("Learning rate: {}", lr);
-        eprintln!("Batch size: {}", batch_size);
-        eprintln!("Epochs: {}", epochs);
-        eprintln!("Components: {:#?}", self.components);
+        if self.components.borrow().is_empty() {
+            CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "The playground is empty",
+                BG_COLOR,
+                Color::Red,
+            );
+            return;
+        }
+        let Some(path) = save_file_dialog(&[ext]) else {
+            return;
+        };
+        match f(&self.graph(), &path) {
+            Ok(()) => CustomDialog::show(200, 40, "Success", success, BG_COLOR, Color::Green),
+            Err(e) => CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red),
+        }
+    }
+
+    fn export(&self, ext: &str, success: &str, f: impl Fn(&Model, &Path) -> Result<(), String>) {
+        let Some(model) = self.model.as_ref() else {
+            CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "Build the model first",
+                BG_COLOR,
+                Color::Red,
+            );
+            return;
+        };
+        let Some(path) = save_file_dialog(&[ext]) else {
+            return;
+        };
+        match f(model, &path) {
+            Ok(()) => CustomDialog::show(260, 40, "Success", success, BG_COLOR, Color::Green),
+            Err(e) => CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red),
+        }
+    }
+}
+
+fn draw_status(
+    f: &mut Frame,
+    i: usize,
+    first: &Rc<RefCell<Option<usize>>>,
+    components: &Rc<RefCell<Vec<NNComponent>>>,
+) {
+    if *first.borrow() == Some(i) {
+        let mut star = PngImage::load("src/assets/star.png").unwrap();
+        star.scale(15, 15, true, true);
+        star.draw(f.x(), f.y(), star.w(), star.h());
     }
+    let configured = match components.borrow().get(i) {
+        Some(NNComponent::Layer { configured, .. }) => *configured,
+        _ => true,
+    };
+    let mut sign = SvgImage::load(if configured {
+        "src/assets/tick.svg"
+    } else {
+        "src/assets/cross.svg"
+    })
+    .unwrap();
+    sign.scale(15, 15, true, true);
+    sign.draw(
+        f.x() + f.w() - sign.w(),
+        f.y() + f.h() - sign.h(),
+        sign.w(),
+        sign.h(),
+    );
 }
@@ -0,0 +1,232 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fltk::button::Button;
+use fltk::enums::{Color, FrameType};
+use fltk::menu::Choice;
+use fltk::prelude::{GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::window::Window;
+
+use crate::app::mainview::training::chart::Chart;
+use crate::training::scheduler::Scheduler;
+use crate::utils::consts::{
+    BG_COLOR, FORM_ROW_HEIGHT, FORM_WINDOW_WIDTH, SCHEDULER_PREVIEW_HEIGHT,
+};
+use crate::utils::form;
+
+// reads the values of the popup, none when one does not parse
+type Reader = Rc<dyn Fn() -> Option<Scheduler>>;
+
+pub(crate) struct SchedulerWidget {}
+
+impl SchedulerWidget {
+    // the current values are edited when the same scheduler is selected again,
+    // the selector shows the name once the popup closes
+    pub(crate) fn show(
+        index: usize,
+        scheduler: Rc<RefCell<Scheduler>>,
+        lr: f64,
+        epochs: usize,
+        mut entry: Choice,
+    ) {
+        let current = match &*scheduler.borrow() {
+            s if s.index() == index => s.clone(),
+            _ => Scheduler::with_defaults(index),
+        };
+        // resized to the rows of the scheduler, as the loss popup does
+        let mut window = Window::default()
+            .with_size(
+                FORM_WINDOW_WIDTH,
+                form::height(5) + SCHEDULER_PREVIEW_HEIGHT,
+            )
+            .with_label(current.name())
+            .center_screen();
+        window.set_label_color(Color::White);
+        window.set_color(BG_COLOR);
+        window.set_frame(FrameType::FlatBox);
+        let (rows, read) = Self::parameters(&current);
+
+        let mut preview_btn = Button::default()
+            .with_pos(2, form::height(rows) + 2)
+            .with_size(FORM_WINDOW_WIDTH - 4, FORM_ROW_HEIGHT - 4)
+            .with_label("Preview");
+        preview_btn.set_color(BG_COLOR);
+        preview_btn.set_label_color(Color::White);
+        preview_btn.set_frame(FrameType::FlatBox);
+        let mut chart = Chart::new(
+            0,
+            form::height(rows + 1),
+            FORM_WINDOW_WIDTH,
+            SCHEDULER_PREVIEW_HEIGHT,
+            "Learning rate",
+            "epoch",
+        );
+        plot(&mut chart, &current, lr, epochs);
+        let preview_read = read.clone();
+        preview_btn.set_callback(move |_| {
+            if let Some(preview) = preview_read() {
+                plot(&mut chart, &preview, lr, epochs);
+            }
+        });
+
+        window.set_size(
+            FORM_WINDOW_WIDTH,
+            form::height(rows + 1) + SCHEDULER_PREVIEW_HEIGHT,
+        );
+        window.end();
+        window.show();
+        window.set_callback(move |window| {
+            window.hide();
+            if let Some(value) = read() {
+                scheduler.replace(value);
+            }
+            entry.set_label(scheduler.borrow().name());
+        });
+    }
+
+    // the rows of the scheduler and how to read them back
+    fn parameters(current: &Scheduler) -> (i32, Reader) {
+        match current.clone() {
+            Scheduler::Constant => (0, Rc::new(|| Some(Scheduler::Constant))),
+            Scheduler::Step { step_size, gamma } => {
+                let step_size = form::int_row(0, "Step size (epochs): ", step_size as i64);
+                let gamma = form::float_row(1, "Gamma: ", gamma);
+                (
+                    2,
+                    Rc::new(move || {
+                        Some(Scheduler::Step {
+                            step_size: form::parse(&step_size.value(), "Step size")?,
+                            gamma: form::parse(&gamma.value(), "Gamma")?,
+                        })
+                    }),
+                )
+            }
+            Scheduler::MultiStep { milestones, gamma } => {
+                let milestones = form::text_row(
+                    0,
+                    "Milestones (epochs): ",
+                    &milestones
+                        .iter()
+                        .map(|m| m.to_string())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+                let gamma = form::float_row(1, "Gamma: ", gamma);
+                (
+                    2,
+                    Rc::new(move || {
+                        let mut milestones = milestones
+                            .value()
+                            .split(',')
+                            .filter(|m| !m.trim().is_empty())
+                            .map(|m| form::parse(m, "Milestones"))
+                            .collect::<Option<Vec<usize>>>()?;
+                        milestones.sort_unstable();
+                        Some(Scheduler::MultiStep {
+                            milestones,
+                            gamma: form::parse(&gamma.value(), "Gamma")?,
+                        })
+                    }),
+                )
+            }
+            Scheduler::Exponential { gamma } => {
+                let gamma = form::float_row(0, "Gamma: ", gamma);
+                (
+                    1,
+                    Rc::new(move || {
+                        Some(Scheduler::Exponential {
+                            gamma: form::parse(&gamma.value(), "Gamma")?,
+                        })
+                    }),
+                )
+            }
+            Scheduler::CosineWarmRestarts {
+                t_0,
+                t_mult,
+                eta_min,
+            } => {
+                let t_0 = form::int_row(0, "First cycle (epochs): ", t_0 as i64);
+                let t_mult = form::int_row(1, "Cycle multiplier: ", t_mult as i64);
+                let eta_min = form::float_row(2, "Minimum lr: ", eta_min);
+                (
+                    3,
+                    Rc::new(move || {
+                        Some(Scheduler::CosineWarmRestarts {
+                            t_0: form::parse(&t_0.value(), "First cycle")?,
+                            t_mult: form::parse(&t_mult.value(), "Cycle multiplier")?,
+                            eta_min: form::parse(&eta_min.value(), "Minimum lr")?,
+                        })
+                    }),
+                )
+            }
+            Scheduler::LinearWarmup {
+                warmup_epochs,
+                start_factor,
+            } => {
+                let warmup_epochs = form::int_row(0, "Warmup (epochs): ", warmup_epochs as i64);
+                let start_factor = form::float_row(1, "Start factor: ", start_factor);
+                (
+                    2,
+                    Rc::new(move || {
+                        Some(Scheduler::LinearWarmup {
+                            warmup_epochs: form::parse(&warmup_epochs.value(), "Warmup")?,
+                            start_factor: form::parse(&start_factor.value(), "Start factor")?,
+                        })
+                    }),
+                )
+            }
+            Scheduler::OneCycle {
+                pct_start,
+                div_factor,
+                final_div_factor,
+            } => {
+                let pct_start = form::float_row(0, "Warmup fraction: ", pct_start);
+                let div_factor = form::float_row(1, "Initial divisor: ", div_factor);
+                let final_div_factor = form::float_row(2, "Final divisor: ", final_div_factor);
+                (
+                    3,
+                    Rc::new(move || {
+                        Some(Scheduler::OneCycle {
+                            pct_start: form::parse::<f64>(&pct_start.value(), "Warmup fraction")?
+                                .clamp(0.01, 0.99),
+                            div_factor: form::parse(&div_factor.value(), "Initial divisor")?,
+                            final_div_factor: form::parse(
+                                &final_div_factor.value(),
+                                "Final divisor",
+                            )?,
+                        })
+                    }),
+                )
+            }
+            Scheduler::ReduceOnPlateau {
+                factor,
+                patience,
+                threshold,
+                min_lr,
+            } => {
+                let factor = form::float_row(0, "Factor: ", factor);
+                let patience = form::int_row(1, "Patience (epochs): ", patience as i64);
+                let threshold = form::float_row(2, "Threshold: ", threshold);
+                let min_lr = form::float_row(3, "Minimum lr: ", min_lr);
+                (
+                    4,
+                    Rc::new(move || {
+                        Some(Scheduler::ReduceOnPlateau {
+                            factor: form::parse(&factor.value(), "Factor")?,
+                            patience: form::parse(&patience.value(), "Patience")?,
+                            threshold: form::parse(&threshold.value(), "Threshold")?,
+                            min_lr: form::parse(&min_lr.value(), "Minimum lr")?,
+                        })
+                    }),
+                )
+            }
+        }
+    }
+}
+
+fn plot(chart: &mut Chart, scheduler: &Scheduler, lr: f64, epochs: usize) {
+    chart.clear();
+    for (epoch, value) in scheduler.preview(lr, epochs) {
+        chart.push("lr", epoch, value);
+    }
+}
@@ -0,0 +1,78 @@
+use std::rc::Rc;
+
+use fltk::button::Button;
+use fltk::enums::{Color, Font, FrameType};
+use fltk::frame::Frame;
+use fltk::prelude::{DisplayExt, GroupExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::text::{TextBuffer, TextDisplay};
+use fltk::window::Window;
+
+use crate::model::summary::Summary;
+use crate::utils::consts::{
+    BG_COLOR, HIGHLIGHT_COLOR, SUMMARY_WINDOW_HEIGHT, SUMMARY_WINDOW_WIDTH,
+};
+use crate::utils::{save_file_dialog, CustomDialog};
+
+pub(crate) struct SummaryWidget {}
+
+impl SummaryWidget {
+    pub(crate) fn show(summary: Summary) {
+        let mut window = Window::default()
+            .with_size(SUMMARY_WINDOW_WIDTH, SUMMARY_WINDOW_HEIGHT)
+            .with_label("Summary")
+            .center_screen();
+        window.set_color(Color::White);
+        let button_h = SUMMARY_WINDOW_HEIGHT / 10;
+
+        let mut buffer = TextBuffer::default();
+        buffer.set_text(&summary.to_text());
+        let mut display = TextDisplay::default().with_pos(2, 2).with_size(
+            SUMMARY_WINDOW_WIDTH - 4,
+            SUMMARY_WINDOW_HEIGHT - button_h - 4,
+        );
+        display.set_buffer(buffer);
+        display.set_frame(FrameType::FlatBox);
+        display.set_color(BG_COLOR);
+        display.set_text_color(Color::White);
+        display.set_text_font(Font::Courier);
+        display.set_selection_color(HIGHLIGHT_COLOR);
+
+        let mut buttons_bg = Frame::default()
+            .with_pos(0, display.y() + display.h())
+            .with_size(SUMMARY_WINDOW_WIDTH, button_h + 2);
+        buttons_bg.set_color(Color::White);
+        buttons_bg.set_frame(FrameType::FlatBox);
+        let summary = Rc::new(summary);
+        for (i, (label, ext)) in [("Export Markdown", "md"), ("Export CSV", "csv")]
+            .into_iter()
+            .enumerate()
+        {
+            let mut button = Button::default()
+                .with_pos(2 + i as i32 * SUMMARY_WINDOW_WIDTH / 2, buttons_bg.y() + 2)
+                .with_size(SUMMARY_WINDOW_WIDTH / 2 - 4, button_h - 2)
+                .with_label(label);
+            button.set_color(BG_COLOR);
+            button.set_frame(FrameType::FlatBox);
+            button.set_label_color(Color::White);
+            let summary = summary.clone();
+            button.set_callback(move |_| {
+                let Some(path) = save_file_dialog(&[ext]) else {
+                    return;
+                };
+                match summary.save(&path) {
+                    Ok(()) => CustomDialog::show(
+                        200,
+                        40,
+                        "Success",
+                        "Summary exported",
+                        BG_COLOR,
+                        Color::Green,
+                    ),
+                    Err(e) => CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red),
+                }
+            });
+        }
+        window.end();
+        window.show();
+    }
+}
@@ -1,18 +1,31 @@
+use std::path::Path;
+
 use fltk::app::Sender;
+use fltk::enums::Color;
 use fltk::prelude::{GroupExt, WidgetBase, WidgetExt};
 use fltk::window::Window;
 
 use crate::app::mainview::editor::EditorView;
+use crate::app::mainview::training::sweep::SweepView;
 use crate::app::mainview::training::TrainingView;
+use crate::project::graph::Graph;
+use crate::project::Project;
+use crate::training::sweep::{Observation, Sweep, SweepConfig, Trial};
+use crate::training::Progress;
+use crate::utils::consts::BG_COLOR;
 use crate::utils::enums::{AppEvent, AppMode};
+use crate::utils::CustomDialog;
 
 mod editor;
-mod training;
+pub(crate) mod training;
 
 pub(crate) struct MainView {
     window: Window,
     editor_view: EditorView,
     training_view: TrainingView,
+    evt_sender: Sender<AppEvent>,
+    sweep: Option<Sweep>,
+    sweep_view: Option<SweepView>,
 }
 
 fltk::widget_extends!(MainView, Window, window);
@@ -27,14 +40,216 @@ impl MainView {
     ) -> Self {
         let window = Window::default().with_pos(p_x, p_y).with_size(p_w, p_h);
 
-        let training_view = TrainingView::new(evt_sender, p_w, p_h);
+        let training_view = TrainingView::new(evt_sender.clone(), p_w, p_h);
         let editor_view = EditorView::new(p_w, p_h);
         window.end();
         Self {
             window,
             editor_view,
             training_view,
+            evt_sender,
+            sweep: None,
+            sweep_view: None,
+        }
+    }
+    pub(crate) fn project(&self) -> Project {
+        self.editor_view.project()
+    }
+    pub(crate) fn load_project(&mut self, project: &Project) -> Result<(), String> {
+        self.editor_view.load_project(project)
+    }
+    pub(crate) fn show_summary(&self) {
+        self.editor_view.show_summary();
+    }
+    pub(crate) fn load_weights(&self) {
+        self.editor_view.load_weights();
+    }
+    pub(crate) fn load_graph(&mut self, graph: &Graph) -> Result<(), String> {
+        self.editor_view.load_graph(graph)
+    }
+    pub(crate) fn start_training(&mut self) {
+        if self.training_view.running() {
+            return;
+        }
+        let Some(dataset) = self.training_view.dataset() else {
+            CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "Select a dataset first",
+                BG_COLOR,
+                Color::Red,
+            );
+            return;
+        };
+        let checkpoints = self.training_view.checkpoints();
+        let validation = self.training_view.validation();
+        let tensorboard = self.training_view.tensorboard();
+        match self
+            .editor_view
+            .training_job(dataset, checkpoints, validation, tensorboard)
+        {
+            Ok(job) => self.training_view.start(job),
+            Err(e) => CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red),
+        }
+    }
+    pub(crate) fn resume_training(&mut self, checkpoint: &Path) {
+        if self.training_view.running() {
+            return;
+        }
+        if let Err(e) = self.training_view.resume(checkpoint) {
+            CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+        }
+    }
+    pub(crate) fn training_progress(&mut self, progress: Progress) {
+        // the trials train from their own graph, the editor keeps its weights
+        let sweeping = self.sweep.as_ref().is_some_and(|s| s.running());
+        if let Progress::Finished(_) = progress {
+            if !sweeping {
+                if let Err(e) = self.editor_view.reload_weights() {
+                    CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+                }
+            }
+        }
+        let refresh = matches!(
+            progress,
+            Progress::Epoch { .. } | Progress::Validation { .. }
+        );
+        let observation = match self.sweep.as_mut() {
+            Some(sweep) if sweeping => sweep.observe(&progress),
+            _ => Observation::Running,
+        };
+        self.training_view.update(progress);
+        match observation {
+            Observation::Ended => self.next_trial(),
+            Observation::Prune(epoch) => {
+                self.training_view
+                    .log(&format!("Trial pruned after epoch {}\n", epoch));
+                self.training_view.stop();
+                self.update_sweep_view();
+            }
+            Observation::Running if sweeping && refresh => self.update_sweep_view(),
+            Observation::Running => {}
+        }
+    }
+    pub(crate) fn show_sweep(&mut self) {
+        match self.sweep_view.as_mut() {
+            Some(view) if view.shown() => view.show(),
+            _ => {
+                let graph = self.editor_view.project().graph;
+                self.sweep_view = Some(SweepView::new(self.evt_sender.clone(), &graph));
+            }
         }
+        self.update_sweep_view();
+    }
+    pub(crate) fn start_sweep(&mut self, config: &SweepConfig) {
+        if self.training_view.running() {
+            CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "Wait for the training to end",
+                BG_COLOR,
+                Color::Red,
+            );
+            return;
+        }
+        let Some(dataset) = self.training_view.dataset() else {
+            CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "Select a dataset first",
+                BG_COLOR,
+                Color::Red,
+            );
+            return;
+        };
+        let project = self.editor_view.project();
+        match Sweep::new(
+            config,
+            &project.graph,
+            &project.settings,
+            dataset,
+            self.training_view.checkpoints(),
+            self.training_view.validation(),
+            self.training_view.tensorboard(),
+        ) {
+            Ok(sweep) => {
+                self.sweep = Some(sweep);
+                if let Some(view) = self.sweep_view.as_mut() {
+                    view.reset();
+                }
+                self.next_trial();
+            }
+            Err(e) => CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red),
+        }
+    }
+    pub(crate) fn stop_sweep(&mut self) {
+        if let Some(sweep) = self.sweep.as_mut() {
+            if sweep.running() {
+                sweep.stop();
+                self.training_view.stop();
+            }
+        }
+    }
+    pub(crate) fn trial_project(&self, trial: usize) -> Option<Project> {
+        let trial = self.sweep.as_ref()?.trials.get(trial)?;
+        Some(Project::new(trial.graph.clone(), trial.settings.clone()))
+    }
+    // starts the next pending trial, the ones that cannot be built fail
+    fn next_trial(&mut self) {
+        if let Some(sweep) = self.sweep.as_mut() {
+            while let Some(trial) = sweep.next() {
+                match sweep.job(trial) {
+                    Ok(job) => {
+                        self.training_view.start(job);
+                        let Trial {
+                            params, settings, ..
+                        } = &sweep.trials[trial];
+                        self.training_view.log(&format!(
+                            "Trial {}/{}: lr {:.2e}, batch {}, {} epochs, {} {}\n",
+                            trial + 1,
+                            sweep.trials.len(),
+                            params.lr,
+                            params.batch_size,
+                            params.epochs,
+                            params.optimizer_name(settings),
+                            params.layers_description()
+                        ));
+                        break;
+                    }
+                    Err(e) => sweep.fail(trial, e),
+                }
+            }
+        }
+        self.update_sweep_view();
+    }
+    fn update_sweep_view(&mut self) {
+        if let (Some(sweep), Some(view)) = (self.sweep.as_ref(), self.sweep_view.as_mut()) {
+            view.update(sweep);
+        }
+    }
+    pub(crate) fn export_dot(&self) {
+        self.editor_view.export_dot();
+    }
+    pub(crate) fn export_diagram_svg(&self) {
+        self.editor_view.export_diagram_svg();
+    }
+    pub(crate) fn export_diagram_png(&self) {
+        self.editor_view.export_diagram_png();
+    }
+    pub(crate) fn export_torchscript(&self) {
+        self.editor_view.export_torchscript();
+    }
+    pub(crate) fn export_onnx(&self) {
+        self.editor_view.export_onnx();
+    }
+    pub(crate) fn export_rust_code(&self) {
+        self.editor_view.export_rust_code();
+    }
+    pub(crate) fn export_python_code(&self) {
+        self.editor_view.export_python_code();
     }
     pub(crate) fn redraw_mode(&mut self, mode: AppMode) {
         match mode {
@@ -0,0 +1,311 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fltk::draw::LineStyle;
+use fltk::enums::{Align, Color, Event, Font, FrameType};
+use fltk::frame::Frame;
+use fltk::prelude::{WidgetBase, WidgetExt};
+
+use crate::utils::consts::{BG_COLOR, CHART_COLORS, HIGHLIGHT_COLOR, MENU_BAR_COLOR};
+
+// space for the title and legend, the y labels and the x labels
+const TOP: i32 = 24;
+const LEFT: i32 = 64;
+const BOTTOM: i32 = 22;
+const RIGHT: i32 = 12;
+const GRID_LINES: i32 = 5;
+const FONT_SIZE: i32 = 11;
+
+struct Series {
+    name: String,
+    color: Color,
+    // sorted by x
+    points: Vec<(f64, f64)>,
+}
+
+struct ChartState {
+    title: String,
+    x_label: String,
+    series: Vec<Series>,
+    log_scale: bool,
+    // exponential moving average weight, 0 draws the raw values
+    smoothing: f64,
+    hover: Option<i32>,
+}
+
+// line chart of named series, drawn with the fltk primitives
+#[derive(Clone)]
+pub(crate) struct Chart {
+    frame: Frame,
+    state: Rc<RefCell<ChartState>>,
+}
+
+fltk::widget_extends!(Chart, Frame, frame);
+
+impl Chart {
+    pub(crate) fn new(x: i32, y: i32, w: i32, h: i32, title: &str, x_label: &str) -> Self {
+        let mut frame = Frame::new(x, y, w, h, None);
+        frame.set_frame(FrameType::FlatBox);
+        frame.set_color(BG_COLOR);
+        let state = Rc::new(RefCell::new(ChartState {
+            title: title.to_string(),
+            x_label: x_label.to_string(),
+            series: vec![],
+            log_scale: false,
+            smoothing: 0.0,
+            hover: None,
+        }));
+
+        let draw_state = state.clone();
+        frame.draw(move |f| draw(f, &draw_state.borrow()));
+        let handle_state = state.clone();
+        frame.handle(move |f, event| match event {
+            // entering must be accepted to receive the move events
+            Event::Enter | Event::Move => {
+                handle_state.borrow_mut().hover = Some(fltk::app::event_x());
+                f.redraw();
+                true
+            }
+            Event::Leave => {
+                handle_state.borrow_mut().hover = None;
+                f.redraw();
+                true
+            }
+            _ => false,
+        });
+        Self { frame, state }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.state.borrow_mut().series.clear();
+        self.frame.redraw();
+    }
+
+    // points are expected in increasing x order
+    pub(crate) fn push(&mut self, series: &str, x: f64, y: f64) {
+        let mut state = self.state.borrow_mut();
+        let i = match state.series.iter().position(|s| s.name == series) {
+            Some(i) => i,
+            None => {
+                let color = CHART_COLORS[state.series.len() % CHART_COLORS.len()];
+                state.series.push(Series {
+                    name: series.to_string(),
+                    color: Color::from_hex(color),
+                    points: vec![],
+                });
+                state.series.len() - 1
+            }
+        };
+        state.series[i].points.push((x, y));
+        drop(state);
+        self.frame.redraw();
+    }
+
+    pub(crate) fn set_log_scale(&mut self, log_scale: bool) {
+        self.state.borrow_mut().log_scale = log_scale;
+        self.frame.redraw();
+    }
+
+    pub(crate) fn set_smoothing(&mut self, smoothing: f64) {
+        self.state.borrow_mut().smoothing = smoothing.clamp(0.0, 0.99);
+        self.frame.redraw();
+    }
+}
+
+// debiased exponential moving average, as tensorboard does it
+fn smooth(points: &[(f64, f64)], weight: f64) -> Vec<(f64, f64)> {
+    let mut last = 0.0;
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, (x, y))| {
+            last = last * weight + (1.0 - weight) * y;
+            (*x, last / (1.0 - weight.powi(i as i32 + 1)))
+        })
+        .collect()
+}
+
+fn label(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e6 {
+        format!("{}", value)
+    } else if value != 0.0 && (value.abs() >= 1e4 || value.abs() < 1e-3) {
+        format!("{:.2e}", value)
+    } else {
+        format!("{:.4}", value)
+    }
+}
+
+fn draw(f: &mut Frame, state: &ChartState) {
+    let (x, y, w, h) = (f.x(), f.y(), f.w(), f.h());
+    fltk::draw::draw_rect_fill(x, y, w, h, BG_COLOR);
+    fltk::draw::set_font(Font::Helvetica, FONT_SIZE);
+    fltk::draw::set_draw_color(Color::White);
+    fltk::draw::draw_text2(&state.title, x + 6, y, w / 2, TOP, Align::Left);
+
+    // the plotted values, log scaled values are their log10
+    let scale = |v: f64| if state.log_scale { v.log10() } else { v };
+    let lines = state
+        .series
+        .iter()
+        .map(|s| {
+            let raw = s
+                .points
+                .iter()
+                .filter(|(_, v)| v.is_finite() && (!state.log_scale || *v > 0.0))
+                .map(|(x, v)| (*x, scale(*v)))
+                .collect::<Vec<_>>();
+            let smoothed = if state.smoothing > 0.0 {
+                Some(smooth(&raw, state.smoothing))
+            } else {
+                None
+            };
+            (s, raw, smoothed)
+        })
+        .collect::<Vec<_>>();
+
+    // legend, right aligned in the title row
+    let mut legend_x = x + w - RIGHT;
+    for (s, _, _) in lines.iter().rev() {
+        let (tw, _) = fltk::draw::measure(&s.name, false);
+        legend_x -= tw + 18;
+        fltk::draw::draw_rect_fill(legend_x, y + TOP / 2 - 4, 10, 8, s.color);
+        fltk::draw::set_draw_color(Color::White);
+        fltk::draw::draw_text2(&s.name, legend_x + 14, y, tw, TOP, Align::Left);
+    }
+
+    let points = lines.iter().flat_map(|(_, raw, _)| raw.iter());
+    let (mut x_min, mut x_max, mut y_min, mut y_max) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+    for (px, py) in points {
+        (x_min, x_max) = (x_min.min(*px), x_max.max(*px));
+        (y_min, y_max) = (y_min.min(*py), y_max.max(*py));
+    }
+    let (px, py, pw, ph) = (x + LEFT, y + TOP, w - LEFT - RIGHT, h - TOP - BOTTOM);
+    fltk::draw::draw_rect_fill(px, py, pw, ph, MENU_BAR_COLOR);
+    if x_min > x_max {
+        fltk::draw::set_draw_color(Color::White);
+        fltk::draw::draw_text2("No data", px, py, pw, ph, Align::Center);
+        return;
+    }
+    if x_max - x_min < f64::EPSILON {
+        (x_min, x_max) = (x_min - 1.0, x_max + 1.0);
+    }
+    if y_max - y_min < f64::EPSILON {
+        (y_min, y_max) = (y_min - 0.5, y_max + 0.5);
+    }
+    let to_x = |v: f64| px + ((v - x_min) / (x_max - x_min) * pw as f64) as i32;
+    let to_y = |v: f64| py + ph - ((v - y_min) / (y_max - y_min) * ph as f64) as i32;
+    let unscale = |v: f64| if state.log_scale { 10f64.powf(v) } else { v };
+
+    // grid and axis labels
+    fltk::draw::set_line_style(LineStyle::Dot, 1);
+    for i in 0..=GRID_LINES {
+        let value = y_min + (y_max - y_min) * i as f64 / GRID_LINES as f64;
+        let gy = to_y(value);
+        fltk::draw::set_draw_color(HIGHLIGHT_COLOR);
+        fltk::draw::draw_line(px, gy, px + pw, gy);
+        fltk::draw::set_draw_color(Color::White);
+        fltk::draw::draw_text2(
+            &label(unscale(value)),
+            x,
+            gy - 6,
+            LEFT - 4,
+            12,
+            Align::Right,
+        );
+    }
+    fltk::draw::set_line_style(LineStyle::Solid, 0);
+    fltk::draw::draw_text2(&label(x_min), px, py + ph, pw / 2, BOTTOM, Align::Left);
+    fltk::draw::draw_text2(
+        &label(x_max),
+        px + pw / 2,
+        py + ph,
+        pw / 2,
+        BOTTOM,
+        Align::Right,
+    );
+    fltk::draw::draw_text2(&state.x_label, px, py + ph, pw, BOTTOM, Align::Center);
+
+    // raw values are dimmed when smoothed ones are drawn over them
+    fltk::draw::push_clip(px, py, pw, ph);
+    let polyline = |values: &[(f64, f64)], color: Color, width: i32| {
+        fltk::draw::set_draw_color(color);
+        fltk::draw::set_line_style(LineStyle::Solid, width);
+        for segment in values.windows(2) {
+            let ((x1, y1), (x2, y2)) = (segment[0], segment[1]);
+            fltk::draw::draw_line(to_x(x1), to_y(y1), to_x(x2), to_y(y2));
+        }
+        if let [(vx, vy)] = values {
+            fltk::draw::draw_rect_fill(to_x(*vx) - 2, to_y(*vy) - 2, 4, 4, color);
+        }
+    };
+    for (s, raw, smoothed) in lines.iter() {
+        match smoothed {
+            Some(smoothed) => {
+                polyline(raw, Color::color_average(s.color, MENU_BAR_COLOR, 0.35), 1);
+                polyline(smoothed, s.color, 2);
+            }
+            None => polyline(raw, s.color, 1),
+        }
+    }
+    fltk::draw::set_line_style(LineStyle::Solid, 0);
+    fltk::draw::pop_clip();
+
+    // readout of the points nearest to the pointer
+    let Some(mouse_x) = state.hover.filter(|mx| *mx >= px && *mx <= px + pw) else {
+        return;
+    };
+    let hover_x = x_min + (mouse_x - px) as f64 / pw as f64 * (x_max - x_min);
+    let mut readout = vec![];
+    let mut nearest_x = None;
+    for (s, raw, smoothed) in lines.iter() {
+        let values = smoothed.as_ref().unwrap_or(raw);
+        let Some((vx, vy)) = values
+            .iter()
+            .min_by(|a, b| (a.0 - hover_x).abs().total_cmp(&(b.0 - hover_x).abs()))
+        else {
+            continue;
+        };
+        nearest_x.get_or_insert(*vx);
+        readout.push((format!("{}: {}", s.name, label(unscale(*vy))), s.color));
+        fltk::draw::draw_rect_fill(to_x(*vx) - 3, to_y(*vy) - 3, 6, 6, s.color);
+    }
+    let Some(nearest_x) = nearest_x else {
+        return;
+    };
+    fltk::draw::set_draw_color(Color::White);
+    fltk::draw::draw_line(to_x(nearest_x), py, to_x(nearest_x), py + ph);
+    readout.insert(
+        0,
+        (
+            format!("{} {}", state.x_label, label(nearest_x)),
+            Color::White,
+        ),
+    );
+    let box_w = readout
+        .iter()
+        .map(|(text, _)| fltk::draw::measure(text, false).0)
+        .max()
+        .unwrap_or(0)
+        + 12;
+    let box_h = readout.len() as i32 * 14 + 6;
+    // kept inside the plot, on the side of the pointer with more room
+    let box_x = if mouse_x + 12 + box_w <= px + pw {
+        mouse_x + 12
+    } else {
+        (mouse_x - 12 - box_w).max(px)
+    };
+    fltk::draw::draw_rect_fill(box_x, py + 4, box_w, box_h, BG_COLOR);
+    fltk::draw::set_draw_color(HIGHLIGHT_COLOR);
+    fltk::draw::draw_rect(box_x, py + 4, box_w, box_h);
+    for (i, (text, color)) in readout.iter().enumerate() {
+        fltk::draw::set_draw_color(*color);
+        fltk::draw::draw_text2(
+            text,
+            box_x + 6,
+            py + 7 + i as i32 * 14,
+            box_w,
+            14,
+            Align::Left,
+        );
+    }
+}
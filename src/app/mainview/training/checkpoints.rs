@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fltk::enums::{Color, FrameType};
+use fltk::prelude::{ButtonExt, GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::window::Window;
+
+use crate::training::checkpoint::CheckpointConfig;
+use crate::utils::consts::{BG_COLOR, FORM_WINDOW_WIDTH};
+use crate::utils::form;
+
+pub(crate) struct CheckpointWidget {}
+
+impl CheckpointWidget {
+    pub(crate) fn show(config: Rc<RefCell<CheckpointConfig>>) {
+        let current = *config.borrow();
+        let mut window = Window::default()
+            .with_size(FORM_WINDOW_WIDTH, form::height(3))
+            .with_label("Checkpoints")
+            .center_screen();
+        window.set_label_color(Color::White);
+        window.set_color(BG_COLOR);
+        window.set_frame(FrameType::FlatBox);
+        let every_epochs = form::int_row(0, "Every N epochs: ", current.every_epochs as i64);
+        let every_minutes = form::int_row(1, "Every N minutes: ", current.every_minutes as i64);
+        let keep_best = form::toggle_row(2, "Keep best: ", current.keep_best);
+        window.end();
+        window.show();
+        window.set_callback(move |window| {
+            window.hide();
+            if let (Some(every_epochs), Some(every_minutes)) = (
+                form::parse(&every_epochs.value(), "Every N epochs"),
+                form::parse(&every_minutes.value(), "Every N minutes"),
+            ) {
+                config.replace(CheckpointConfig {
+                    every_epochs,
+                    every_minutes,
+                    keep_best: keep_best.is_set(),
+                });
+            }
+        });
+    }
+}
@@ -1,14 +1,56 @@
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
 use fltk::app::Sender;
-use fltk::button::Button;
-use fltk::prelude::{GroupExt, WidgetBase, WidgetExt};
+use fltk::button::{Button, CheckButton};
+use fltk::enums::{Align, Color, Font, FrameType};
+use fltk::frame::Frame;
+use fltk::prelude::{ButtonExt, DisplayExt, GroupExt, ValuatorExt, WidgetBase, WidgetExt};
+use fltk::text::{TextBuffer, TextDisplay};
+use fltk::valuator::HorNiceSlider;
 use fltk::window::{DoubleWindow, Window};
 
-use crate::utils::consts::BG_COLOR;
+use crate::app::mainview::training::chart::Chart;
+use crate::app::mainview::training::checkpoints::CheckpointWidget;
+use crate::app::mainview::training::tensorboard::TensorBoardWidget;
+use crate::app::mainview::training::validation::ValidationWidget;
+use crate::training::checkpoint::{Checkpoint, CheckpointConfig, CHECKPOINT_EXTENSION};
+use crate::training::control::{Command, Control};
+use crate::training::data::DATASET_EXTENSIONS;
+use crate::training::metrics::{ValidationConfig, VAL_LOSS};
+use crate::training::tensorboard::TensorBoardConfig;
+use crate::training::{Job, Progress};
+use crate::utils::consts::{BG_COLOR, HIGHLIGHT_COLOR, MENU_BAR_COLOR, MENU_BAR_RATIO};
 use crate::utils::enums::AppEvent;
+use crate::utils::{open_file_dialog, CustomDialog};
+
+pub(crate) mod chart;
+mod checkpoints;
+pub(crate) mod sweep;
+mod tensorboard;
+mod validation;
 
 pub(crate) struct TrainingView {
     window: DoubleWindow,
     evt_sender: Sender<AppEvent>,
+    dataset: Rc<RefCell<Option<PathBuf>>>,
+    checkpoints: Rc<RefCell<CheckpointConfig>>,
+    validation: Rc<RefCell<ValidationConfig>>,
+    tensorboard: Rc<RefCell<TensorBoardConfig>>,
+    // set while a run is going on
+    control: Rc<RefCell<Option<Control>>>,
+    // of the current run
+    epochs: usize,
+    steps: usize,
+    train_btn: Button,
+    pause_btn: Button,
+    stop_btn: Button,
+    status: Frame,
+    step_chart: Chart,
+    epoch_chart: Chart,
+    metrics_chart: Chart,
+    log: TextBuffer,
 }
 
 fltk::widget_extends!(TrainingView, Window, window);
@@ -17,9 +59,321 @@ impl TrainingView {
     pub(crate) fn new(evt_sender: Sender<AppEvent>, p_w: i32, p_h: i32) -> Self {
         let mut window = Window::default().with_size(p_w, p_h);
         window.set_color(BG_COLOR);
+        let bar_h = p_h / MENU_BAR_RATIO;
+
+        let mut bar = Frame::default().with_pos(0, 0).with_size(p_w, bar_h + 4);
+        bar.set_frame(FrameType::FlatBox);
+        bar.set_color(MENU_BAR_COLOR);
+
+        let dataset = Rc::new(RefCell::new(None));
+        let mut dataset_btn = Button::default()
+            .with_pos(2, 2)
+            .with_size(p_w / 8, bar_h)
+            .with_label("Select dataset");
+        let mut train_btn = Button::default()
+            .right_of(&dataset_btn, 2)
+            .with_size(p_w / 14, bar_h)
+            .with_label("Train");
+        let mut pause_btn = Button::default()
+            .right_of(&train_btn, 2)
+            .with_size(p_w / 14, bar_h)
+            .with_label("Pause");
+        let mut stop_btn = Button::default()
+            .right_of(&pause_btn, 2)
+            .with_size(p_w / 14, bar_h)
+            .with_label("Stop");
+        let mut checkpoints_btn = Button::default()
+            .right_of(&stop_btn, 2)
+            .with_size(p_w / 14, bar_h)
+            .with_label("Checkpoints");
+        let mut validation_btn = Button::default()
+            .right_of(&checkpoints_btn, 2)
+            .with_size(p_w / 14, bar_h)
+            .with_label("Validation");
+        let mut tensorboard_btn = Button::default()
+            .right_of(&validation_btn, 2)
+            .with_size(p_w / 14, bar_h)
+            .with_label("TensorBoard");
+        let mut resume_btn = Button::default()
+            .right_of(&tensorboard_btn, 2)
+            .with_size(p_w / 14, bar_h)
+            .with_label("Resume");
+        for btn in [
+            &mut dataset_btn,
+            &mut train_btn,
+            &mut pause_btn,
+            &mut stop_btn,
+            &mut checkpoints_btn,
+            &mut validation_btn,
+            &mut tensorboard_btn,
+            &mut resume_btn,
+        ] {
+            btn.set_color(BG_COLOR);
+            btn.set_frame(FrameType::FlatBox);
+            btn.set_label_color(Color::White);
+        }
+        pause_btn.deactivate();
+        stop_btn.deactivate();
+        let selected = dataset.clone();
+        dataset_btn.set_callback(move |btn| {
+            if let Some(path) = open_file_dialog(&DATASET_EXTENSIONS) {
+                btn.set_label(&path.file_name().unwrap().to_string_lossy());
+                selected.replace(Some(path));
+            }
+        });
+        train_btn.set_callback(move |_| evt_sender.send(AppEvent::StartTraining));
+        let checkpoints = Rc::new(RefCell::new(CheckpointConfig::default()));
+        let config = checkpoints.clone();
+        checkpoints_btn.set_callback(move |_| CheckpointWidget::show(config.clone()));
+        let validation = Rc::new(RefCell::new(ValidationConfig::default()));
+        let config = validation.clone();
+        validation_btn.set_callback(move |_| ValidationWidget::show(config.clone()));
+        let tensorboard = Rc::new(RefCell::new(TensorBoardConfig::default()));
+        let config = tensorboard.clone();
+        tensorboard_btn.set_callback(move |_| TensorBoardWidget::show(config.clone()));
+        // continues from the last or any other checkpoint of a run
+        resume_btn.set_callback(move |_| {
+            if let Some(path) = open_file_dialog(&[CHECKPOINT_EXTENSION]) {
+                evt_sender.send(AppEvent::ResumeTraining(path));
+            }
+        });
+        let control: Rc<RefCell<Option<Control>>> = Rc::new(RefCell::new(None));
+        let pause_control = control.clone();
+        pause_btn.set_callback(move |btn| {
+            if let Some(control) = pause_control.borrow().as_ref() {
+                if control.get() == Command::Pause {
+                    control.set(Command::Run);
+                    btn.set_label("Pause");
+                } else {
+                    control.set(Command::Pause);
+                    btn.set_label("Resume");
+                }
+            }
+        });
+        let stop_control = control.clone();
+        stop_btn.set_callback(move |btn| {
+            // the worker saves the weights after the current step
+            if let Some(control) = stop_control.borrow().as_ref() {
+                control.set(Command::Stop);
+                btn.deactivate();
+            }
+        });
+
+        // chart options, on the right of the bar
+        let mut smoothing = HorNiceSlider::default()
+            .with_pos(p_w - p_w / 8 - 2, 2)
+            .with_size(p_w / 8, bar_h)
+            .with_label("Smoothing")
+            .with_align(Align::Left);
+        smoothing.set_bounds(0.0, 0.99);
+        smoothing.set_step(0.01, 1);
+        smoothing.set_color(BG_COLOR);
+        smoothing.set_selection_color(HIGHLIGHT_COLOR);
+        smoothing.set_label_color(Color::White);
+        let mut log_scale = CheckButton::default()
+            .with_pos(smoothing.x() - p_w / 6, 2)
+            .with_size(p_w / 12, bar_h)
+            .with_label("Log scale");
+        log_scale.set_label_color(Color::White);
+
+        let mut status = Frame::default()
+            .right_of(&resume_btn, 8)
+            .with_size(log_scale.x() - resume_btn.x() - resume_btn.w() - 16, bar_h)
+            .with_align(Align::Left | Align::Inside)
+            .with_label("Build a model and select a dataset");
+        status.set_frame(FrameType::FlatBox);
+        status.set_color(MENU_BAR_COLOR);
+        status.set_label_color(Color::White);
+
+        let charts_h = (p_h - bar_h - 8) * 3 / 5;
+        let step_chart = Chart::new(2, bar_h + 6, p_w / 2 - 3, charts_h, "Loss per step", "step");
+        let epoch_chart = Chart::new(
+            p_w / 2 + 1,
+            bar_h + 6,
+            p_w / 2 - 3,
+            charts_h,
+            "Loss per epoch",
+            "epoch",
+        );
+        // the log on the left of the validation metrics
+        let below_y = bar_h + charts_h + 8;
+        let below_h = p_h - bar_h - charts_h - 10;
+        let metrics_chart = Chart::new(
+            p_w / 2 + 1,
+            below_y,
+            p_w / 2 - 3,
+            below_h,
+            "Validation metrics",
+            "epoch",
+        );
+        let charts = [
+            step_chart.clone(),
+            epoch_chart.clone(),
+            metrics_chart.clone(),
+        ];
+        let mut log_charts = charts.clone();
+        log_scale.set_callback(move |btn| {
+            for chart in log_charts.iter_mut() {
+                chart.set_log_scale(btn.is_checked());
+            }
+        });
+        let mut smoothed_charts = charts;
+        smoothing.set_callback(move |slider| {
+            for chart in smoothed_charts.iter_mut() {
+                chart.set_smoothing(slider.value());
+            }
+        });
+
+        let log = TextBuffer::default();
+        let mut display = TextDisplay::default()
+            .with_pos(2, below_y)
+            .with_size(p_w / 2 - 3, below_h);
+        display.set_buffer(log.clone());
+        display.set_frame(FrameType::FlatBox);
+        display.set_color(BG_COLOR);
+        display.set_text_color(Color::White);
+        display.set_text_font(Font::Courier);
+        display.set_selection_color(HIGHLIGHT_COLOR);
 
-        let btn = Button::new(0, 0, 80, 40, "Training").center_of(&window);
         window.end();
-        Self { window, evt_sender }
+        Self {
+            window,
+            evt_sender,
+            dataset,
+            checkpoints,
+            validation,
+            tensorboard,
+            control,
+            epochs: 0,
+            steps: 0,
+            train_btn,
+            pause_btn,
+            stop_btn,
+            status,
+            step_chart,
+            epoch_chart,
+            metrics_chart,
+            log,
+        }
+    }
+    pub(crate) fn dataset(&self) -> Option<PathBuf> {
+        self.dataset.borrow().clone()
+    }
+    pub(crate) fn checkpoints(&self) -> CheckpointConfig {
+        *self.checkpoints.borrow()
+    }
+    pub(crate) fn validation(&self) -> ValidationConfig {
+        self.validation.borrow().clone()
+    }
+    pub(crate) fn tensorboard(&self) -> TensorBoardConfig {
+        self.tensorboard.borrow().clone()
+    }
+    pub(crate) fn running(&self) -> bool {
+        self.control.borrow().is_some()
+    }
+    pub(crate) fn start(&mut self, job: Job) {
+        self.train_btn.deactivate();
+        self.pause_btn.activate();
+        self.stop_btn.activate();
+        self.log.set_text("");
+        self.step_chart.clear();
+        self.epoch_chart.clear();
+        self.metrics_chart.clear();
+        self.set_status("Starting");
+        self.control
+            .replace(Some(crate::training::spawn(job, self.evt_sender)));
+    }
+    // as the stop button, the worker saves the weights after the current step
+    pub(crate) fn stop(&mut self) {
+        if let Some(control) = self.control.borrow().as_ref() {
+            control.set(Command::Stop);
+            self.stop_btn.deactivate();
+        }
+    }
+    pub(crate) fn log(&mut self, message: &str) {
+        self.log.append(message);
+    }
+    pub(crate) fn resume(&mut self, path: &Path) -> Result<(), String> {
+        let checkpoint = Checkpoint::load(path)?;
+        let message = format!(
+            "Resuming {} at epoch {}, step {}\n",
+            path.display(),
+            checkpoint.epoch,
+            checkpoint.step
+        );
+        self.start(checkpoint.job(path)?);
+        self.log.append(&message);
+        Ok(())
+    }
+    pub(crate) fn update(&mut self, progress: Progress) {
+        match progress {
+            Progress::Started {
+                epochs,
+                steps,
+                seed,
+            } => {
+                self.epochs = epochs;
+                self.steps = steps;
+                self.log.append(&format!("Seed {}\n", seed));
+            }
+            Progress::Step {
+                epoch,
+                step,
+                loss,
+                lr,
+            } => {
+                let global_step = (epoch - 1) * self.steps + step;
+                self.step_chart.push("train loss", global_step as f64, loss);
+                self.set_status(&format!(
+                    "Epoch {}/{}  Step {}/{}  Loss {:.6}  LR {:.2e}",
+                    epoch, self.epochs, step, self.steps, loss, lr
+                ));
+            }
+            Progress::Epoch { epoch, loss } => {
+                self.epoch_chart.push("train loss", epoch as f64, loss);
+                self.log.append(&format!(
+                    "Epoch {}/{}: loss {:.6}\n",
+                    epoch, self.epochs, loss
+                ));
+            }
+            Progress::Validation { epoch, metrics } => {
+                let mut line = format!("Epoch {}/{}: ", epoch, self.epochs);
+                for (name, value) in metrics.iter() {
+                    // the loss is drawn next to the training loss
+                    if name == VAL_LOSS {
+                        self.epoch_chart.push("val loss", epoch as f64, *value);
+                    } else {
+                        self.metrics_chart.push(name, epoch as f64, *value);
+                    }
+                    line.push_str(&format!("{} {:.4}  ", name, value));
+                }
+                self.log.append(&format!("{}\n", line.trim_end()));
+            }
+            Progress::Checkpoint(path) => self
+                .log
+                .append(&format!("Checkpoint saved to {}\n", path.display())),
+            Progress::Finished(message) => {
+                self.finish();
+                self.set_status(&message);
+                // the status is cut on narrow windows, the log keeps why the run ended
+                self.log.append(&format!("{}\n", message));
+            }
+            Progress::Failed(e) => {
+                self.finish();
+                self.set_status("Training failed");
+                CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+            }
+        }
+    }
+    fn set_status(&mut self, status: &str) {
+        self.status.set_label(status);
+        self.status.redraw();
+    }
+    fn finish(&mut self) {
+        self.control.replace(None);
+        self.train_btn.activate();
+        self.pause_btn.set_label("Pause");
+        self.pause_btn.deactivate();
+        self.stop_btn.deactivate();
     }
 }
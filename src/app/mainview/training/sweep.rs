@@ -0,0 +1,442 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use fltk::app::Sender;
+use fltk::browser::HoldBrowser;
+use fltk::button::{Button, CheckButton};
+use fltk::enums::{Align, Color, Font, FrameType};
+use fltk::frame::Frame;
+use fltk::input::Input;
+use fltk::menu::Choice;
+use fltk::prelude::{
+    BrowserExt, ButtonExt, GroupExt, InputExt, MenuExt, WidgetBase, WidgetExt, WindowExt,
+};
+use fltk::window::Window;
+
+use crate::model::node_name;
+use crate::project::graph::Graph;
+use crate::training::metrics::{lower_is_better, METRICS};
+use crate::training::sweep::{
+    SearchSpace, Strategy, Sweep, SweepConfig, TrialScheduler, TrialStatus, STRATEGIES,
+    TRIAL_SCHEDULERS,
+};
+use crate::utils::consts::{
+    BG_COLOR, FORM_WINDOW_WIDTH, HIGHLIGHT_COLOR, OPTIMIZERS, SWEEP_WINDOW_WIDTH,
+};
+use crate::utils::enums::AppEvent;
+use crate::utils::{form, CustomDialog};
+
+const ROWS: i32 = 16;
+// the columns the results can be sorted by, before the metrics
+const COLUMNS: [&str; 4] = ["trial", "lr", "batch", "epochs"];
+
+// a trial of the results, with the values it can be sorted by
+struct Row {
+    trial: usize,
+    text: String,
+    keys: BTreeMap<String, f64>,
+}
+
+// the search space of a sweep on the left, the trials and their best metrics
+// on the right, the selected trial can be promoted to the editor
+pub(crate) struct SweepView {
+    window: Window,
+    status: Frame,
+    header: Frame,
+    sort: Choice,
+    descending: CheckButton,
+    rows: Rc<RefCell<Vec<Row>>>,
+    refill: Rc<dyn Fn()>,
+}
+
+impl SweepView {
+    pub(crate) fn new(evt_sender: Sender<AppEvent>, graph: &Graph) -> Self {
+        let height = form::height(ROWS);
+        let mut window = Window::default()
+            .with_size(SWEEP_WINDOW_WIDTH, height)
+            .with_label("Sweep")
+            .center_screen();
+        window.set_color(Color::White);
+
+        let strategy = form::choice_row(0, "Strategy: ", &STRATEGIES, STRATEGIES[0]);
+        let trials = form::int_row(1, "Random trials: ", 10);
+        let seed = form::int_row(2, "Random seed: ", 0);
+        let lr_min = form::float_row(3, "LR min: ", 1e-4);
+        let lr_max = form::float_row(4, "LR max: ", 1e-1);
+        let lr_points = form::int_row(5, "LR points (0 keeps it): ", 3);
+        let batch_sizes = form::text_row(6, "Batch sizes: ", "");
+        let epochs = form::text_row(7, "Epochs: ", "");
+        let optimizers = form::text_row(8, "Optimizers: ", "");
+        // the current sizes, as an example of the syntax
+        let layers = form::text_row(9, "Layer sizes: ", &layer_sizes(graph));
+        let metric = form::choice_row(10, "Rank by: ", &METRICS, METRICS[0]);
+        // the epochs of the search space are ignored with a scheduler
+        let scheduler = form::choice_row(11, "Scheduler: ", &TRIAL_SCHEDULERS, TRIAL_SCHEDULERS[0]);
+        let min_epochs = form::int_row(12, "Min epochs: ", 1);
+        let max_epochs = form::int_row(13, "Max epochs: ", 27);
+        let eta = form::int_row(14, "Reduction factor: ", 3);
+        let mut start_btn = Button::default()
+            .with_pos(2, 15 * form::height(1) + 2)
+            .with_size(FORM_WINDOW_WIDTH / 2 - 4, form::height(1) - 4)
+            .with_label("Start");
+        let mut stop_btn = Button::default()
+            .right_of(&start_btn, 4)
+            .with_size(FORM_WINDOW_WIDTH / 2 - 4, form::height(1) - 4)
+            .with_label("Stop");
+
+        let table_x = FORM_WINDOW_WIDTH + 2;
+        let table_w = SWEEP_WINDOW_WIDTH - table_x - 2;
+        let bar_h = form::height(1) * 3 / 5;
+        let mut status = Frame::default()
+            .with_pos(table_x, 2)
+            .with_size(table_w, bar_h)
+            .with_align(Align::Left | Align::Inside)
+            .with_label("Define the search space and start the sweep");
+        let mut sort = Choice::default()
+            .with_pos(table_x + 60, bar_h + 4)
+            .with_size(table_w / 3, bar_h)
+            .with_label("Sort by: ")
+            .with_align(Align::Left);
+        for name in COLUMNS.iter().chain(METRICS.iter()) {
+            sort.add_choice(name);
+        }
+        sort.set_value(0);
+        let mut descending = CheckButton::default()
+            .right_of(&sort, 8)
+            .with_size(table_w / 5, bar_h)
+            .with_label("Descending");
+        let mut promote_btn = Button::default()
+            .with_pos(SWEEP_WINDOW_WIDTH - table_w / 4 - 2, bar_h + 4)
+            .with_size(table_w / 4, bar_h)
+            .with_label("Promote to editor");
+        let mut header = Frame::default()
+            .with_pos(table_x, 2 * bar_h + 6)
+            .with_size(table_w, bar_h)
+            .with_align(Align::Left | Align::Inside);
+        header.set_label_font(Font::Courier);
+        let mut browser = HoldBrowser::default()
+            .with_pos(table_x, 3 * bar_h + 8)
+            .with_size(table_w, height - 3 * bar_h - 10);
+        browser.set_frame(FrameType::FlatBox);
+        browser.set_color(BG_COLOR);
+        browser.set_text_color(Color::White);
+        browser.set_text_font(Font::Courier);
+        browser.set_selection_color(HIGHLIGHT_COLOR);
+        for btn in [&mut start_btn, &mut stop_btn, &mut promote_btn] {
+            btn.set_color(BG_COLOR);
+            btn.set_frame(FrameType::FlatBox);
+            btn.set_label_color(Color::White);
+        }
+        for frame in [&mut status, &mut header] {
+            frame.set_frame(FrameType::FlatBox);
+            frame.set_color(BG_COLOR);
+            frame.set_label_color(Color::White);
+        }
+        sort.set_frame(FrameType::FlatBox);
+        sort.set_color(BG_COLOR);
+        sort.set_text_color(Color::White);
+        sort.set_selection_color(HIGHLIGHT_COLOR);
+
+        let start_sender = evt_sender.clone();
+        start_btn.set_callback(move |_| {
+            let strategy = if strategy.label() == STRATEGIES[1] {
+                match (
+                    form::parse::<usize>(&trials.value(), "Random trials"),
+                    form::parse::<u64>(&seed.value(), "Random seed"),
+                ) {
+                    (Some(trials), Some(seed)) => Strategy::Random { trials, seed },
+                    _ => return,
+                }
+            } else {
+                Strategy::Grid
+            };
+            let (Some(lr_min), Some(lr_max), Some(lr_points)) = (
+                form::parse::<f64>(&lr_min.value(), "LR min"),
+                form::parse::<f64>(&lr_max.value(), "LR max"),
+                form::parse::<usize>(&lr_points.value(), "LR points"),
+            ) else {
+                return;
+            };
+            if lr_points > 0 && !(lr_min > 0.0 && lr_min <= lr_max) {
+                error("The learning rates must be positive, the minimum below the maximum");
+                return;
+            }
+            let (Some(batch_sizes), Some(epochs), Some(layers)) = (
+                parse_list::<i64>(&batch_sizes, "Batch sizes"),
+                parse_list::<usize>(&epochs, "Epochs"),
+                parse_layers(&layers),
+            ) else {
+                return;
+            };
+            let scheduler = match TRIAL_SCHEDULERS
+                .iter()
+                .position(|name| *name == scheduler.label())
+            {
+                Some(0) | None => TrialScheduler::None,
+                Some(index) => {
+                    let (Some(min_epochs), Some(max_epochs), Some(eta)) = (
+                        form::parse::<usize>(&min_epochs.value(), "Min epochs"),
+                        form::parse::<usize>(&max_epochs.value(), "Max epochs"),
+                        form::parse::<usize>(&eta.value(), "Reduction factor"),
+                    ) else {
+                        return;
+                    };
+                    if min_epochs == 0 || max_epochs <= min_epochs || eta < 2 {
+                        error("Expected 0 < min epochs < max epochs and a factor of at least 2");
+                        return;
+                    }
+                    if index == 1 {
+                        TrialScheduler::SuccessiveHalving {
+                            min_epochs,
+                            max_epochs,
+                            eta,
+                        }
+                    } else {
+                        TrialScheduler::Hyperband {
+                            min_epochs,
+                            max_epochs,
+                            eta,
+                        }
+                    }
+                }
+            };
+            let optimizers = optimizers
+                .value()
+                .split([',', ' '])
+                .filter(|name| !name.is_empty())
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>();
+            if let Some(name) = optimizers
+                .iter()
+                .find(|n| !OPTIMIZERS.contains(&n.as_str()))
+            {
+                error(&format!(
+                    "Unknown optimizer {}, expected one of {}",
+                    name,
+                    OPTIMIZERS.join(", ")
+                ));
+                return;
+            }
+            start_sender.send(AppEvent::StartSweep(Box::new(SweepConfig {
+                strategy,
+                space: SearchSpace {
+                    lr_min,
+                    lr_max,
+                    lr_points,
+                    batch_sizes,
+                    epochs,
+                    optimizers,
+                    layers,
+                },
+                scheduler,
+                metric: metric.label(),
+            })));
+        });
+        let stop_sender = evt_sender.clone();
+        stop_btn.set_callback(move |_| stop_sender.send(AppEvent::StopSweep));
+
+        let rows: Rc<RefCell<Vec<Row>>> = Rc::new(RefCell::new(vec![]));
+        let refill: Rc<dyn Fn()> = {
+            let rows = rows.clone();
+            let sort = sort.clone();
+            let descending = descending.clone();
+            let browser = browser.clone();
+            Rc::new(move || {
+                let mut browser = browser.clone();
+                let key = sort.choice().unwrap_or(COLUMNS[0].to_string());
+                let mut rows = rows.borrow_mut();
+                // the trials without the value last, in either order
+                rows.sort_by(|a, b| match (a.keys.get(&key), b.keys.get(&key)) {
+                    (Some(a), Some(b)) if descending.is_checked() => b.total_cmp(a),
+                    (Some(a), Some(b)) => a.total_cmp(b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                });
+                browser.clear();
+                for row in rows.iter() {
+                    browser.add(&row.text);
+                }
+                browser.redraw();
+            })
+        };
+        let sort_refill = refill.clone();
+        sort.set_callback(move |_| sort_refill());
+        let descending_refill = refill.clone();
+        descending.set_callback(move |_| descending_refill());
+        let selected_rows = rows.clone();
+        promote_btn.set_callback(move |_| {
+            let line = browser.value();
+            match selected_rows.borrow().get((line - 1).max(0) as usize) {
+                Some(row) if line > 0 => evt_sender.send(AppEvent::PromoteTrial(row.trial)),
+                _ => error("Select a trial first"),
+            }
+        });
+        window.end();
+        window.show();
+        Self {
+            window,
+            status,
+            header,
+            sort,
+            descending,
+            rows,
+            refill,
+        }
+    }
+
+    pub(crate) fn shown(&self) -> bool {
+        self.window.shown()
+    }
+
+    pub(crate) fn show(&mut self) {
+        self.window.show();
+    }
+
+    pub(crate) fn update(&mut self, sweep: &Sweep) {
+        let done = sweep
+            .trials
+            .iter()
+            .filter(|t| !matches!(t.status, TrialStatus::Pending | TrialStatus::Running))
+            .count();
+        self.status.set_label(&format!(
+            "{}/{} trials done, ranked by {}{}",
+            done,
+            sweep.trials.len(),
+            sweep.metric,
+            if sweep.running() { "" } else { ", not running" }
+        ));
+        let metrics = METRICS
+            .iter()
+            .filter(|name| sweep.trials.iter().any(|t| t.metrics.contains_key(**name)))
+            .collect::<Vec<_>>();
+        // the bracket of every trial with a hyperband
+        let brackets = matches!(sweep.scheduler, TrialScheduler::Hyperband { .. });
+        let mut header = format!(
+            "{:>5} {:<9} {:>10} {:>6} {:>7} {:<8} {:<24}",
+            "trial", "status", "lr", "batch", "epochs", "opt", "layers"
+        );
+        if brackets {
+            write!(header, " {:>7}", "bracket").unwrap();
+        }
+        for name in metrics.iter() {
+            write!(header, " {:>15}", name).unwrap();
+        }
+        self.header.set_label(&header);
+        let rows = sweep
+            .trials
+            .iter()
+            .enumerate()
+            .map(|(i, trial)| {
+                let params = &trial.params;
+                let mut text = format!(
+                    "{:>5} {:<9} {:>10.3e} {:>6} {:>7} {:<8} {:<24}",
+                    i + 1,
+                    trial.status.name(),
+                    params.lr,
+                    params.batch_size,
+                    format!("{}/{}", trial.epochs, params.epochs),
+                    params.optimizer_name(&trial.settings),
+                    params.layers_description()
+                );
+                if brackets {
+                    write!(text, " {:>7}", trial.bracket + 1).unwrap();
+                }
+                for name in metrics.iter() {
+                    match trial.metrics.get(**name) {
+                        Some(value) => write!(text, " {:>15.6}", value).unwrap(),
+                        None => write!(text, " {:>15}", "-").unwrap(),
+                    }
+                }
+                let mut keys = trial.metrics.clone();
+                keys.insert(COLUMNS[0].to_string(), (i + 1) as f64);
+                keys.insert(COLUMNS[1].to_string(), params.lr);
+                keys.insert(COLUMNS[2].to_string(), params.batch_size as f64);
+                keys.insert(COLUMNS[3].to_string(), trial.epochs as f64);
+                Row {
+                    trial: i,
+                    text,
+                    keys,
+                }
+            })
+            .collect();
+        let first = self.rows.borrow().is_empty();
+        self.rows.replace(rows);
+        // a new sweep is ranked by its metric, the best trial first
+        if first {
+            if let Some(index) = COLUMNS
+                .iter()
+                .chain(METRICS.iter())
+                .position(|name| *name == sweep.metric)
+            {
+                self.sort.set_value(index as i32);
+                self.descending.set_checked(!lower_is_better(&sweep.metric));
+            }
+        }
+        (self.refill)();
+        self.window.redraw();
+    }
+
+    // the next update sorts by the metric of the sweep again
+    pub(crate) fn reset(&mut self) {
+        self.rows.replace(vec![]);
+    }
+}
+
+fn error(message: &str) {
+    CustomDialog::show(400, 60, "Error", message, BG_COLOR, Color::Red);
+}
+
+// comma or space separated values, empty keeps the configured value
+fn parse_list<T: FromStr>(input: &Input, label: &str) -> Option<Vec<T>>
+where
+    T::Err: std::fmt::Display,
+{
+    input
+        .value()
+        .split([',', ' '])
+        .filter(|value| !value.is_empty())
+        .map(|value| form::parse::<T>(value, label))
+        .collect()
+}
+
+// "linear_0: 32 64; linear_2: 16 32"
+fn parse_layers(input: &Input) -> Option<Vec<(String, Vec<i64>)>> {
+    let mut layers = vec![];
+    for entry in input.value().split(';').filter(|e| !e.trim().is_empty()) {
+        let Some((name, sizes)) = entry.split_once(':') else {
+            error(&format!("Expected \"name: sizes\" in {}", entry.trim()));
+            return None;
+        };
+        let sizes = sizes
+            .split([',', ' '])
+            .filter(|size| !size.is_empty())
+            .map(|size| form::parse::<i64>(size, "Layer sizes"))
+            .collect::<Option<Vec<_>>>()?;
+        layers.push((name.trim().to_string(), sizes));
+    }
+    Some(layers)
+}
+
+// the output size of every layer of the graph
+fn layer_sizes(graph: &Graph) -> String {
+    graph
+        .nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, node)| {
+            let layer = node.layer.as_ref()?;
+            let component = node.to_component().ok()?;
+            Some(format!(
+                "{}: {}",
+                node_name(i, &component),
+                layer.action_space
+            ))
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
@@ -0,0 +1,57 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use fltk::enums::{Color, FrameType};
+use fltk::prelude::{ButtonExt, GroupExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::window::Window;
+
+use crate::training::tensorboard::TensorBoardConfig;
+use crate::utils::consts::{BG_COLOR, FORM_WINDOW_WIDTH};
+use crate::utils::{form, open_dir_dialog};
+
+pub(crate) struct TensorBoardWidget {}
+
+impl TensorBoardWidget {
+    pub(crate) fn show(config: Rc<RefCell<TensorBoardConfig>>) {
+        let current = config.borrow().clone();
+        let mut window = Window::default()
+            .with_size(FORM_WINDOW_WIDTH, form::height(3))
+            .with_label("TensorBoard")
+            .center_screen();
+        window.set_label_color(Color::White);
+        window.set_color(BG_COLOR);
+        window.set_frame(FrameType::FlatBox);
+        let enabled = form::toggle_row(0, "Write event files: ", current.logdir.is_some());
+        let mut logdir_btn = form::button_row(
+            1,
+            "Log directory: ",
+            &current
+                .logdir
+                .as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or("Select directory".to_string()),
+        );
+        let histograms = form::toggle_row(2, "Weight histograms: ", current.histograms);
+        let logdir: Rc<RefCell<Option<PathBuf>>> = Rc::new(RefCell::new(current.logdir));
+        let selected = logdir.clone();
+        let mut selected_toggle = enabled.clone();
+        logdir_btn.set_callback(move |btn| {
+            if let Some(path) = open_dir_dialog() {
+                btn.set_label(&path.display().to_string());
+                selected.replace(Some(path));
+                selected_toggle.set(true);
+                selected_toggle.set_label("✔️");
+            }
+        });
+        window.end();
+        window.show();
+        window.set_callback(move |window| {
+            window.hide();
+            config.replace(TensorBoardConfig {
+                logdir: logdir.borrow().clone().filter(|_| enabled.is_set()),
+                histograms: histograms.is_set(),
+            });
+        });
+    }
+}
@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use fltk::enums::{Color, FrameType};
+use fltk::prelude::{ButtonExt, GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::window::Window;
+
+use crate::training::data::DATASET_EXTENSIONS;
+use crate::training::metrics::{Task, ValidationConfig, TASKS};
+use crate::utils::consts::{BG_COLOR, FORM_WINDOW_WIDTH};
+use crate::utils::{form, open_file_dialog};
+
+pub(crate) struct ValidationWidget {}
+
+impl ValidationWidget {
+    pub(crate) fn show(config: Rc<RefCell<ValidationConfig>>) {
+        let current = config.borrow().clone();
+        let mut window = Window::default()
+            .with_size(FORM_WINDOW_WIDTH, form::height(5))
+            .with_label("Validation")
+            .center_screen();
+        window.set_label_color(Color::White);
+        window.set_color(BG_COLOR);
+        window.set_frame(FrameType::FlatBox);
+        let fraction = form::float_row(0, "Held out fraction: ", current.fraction);
+        let separate = form::toggle_row(1, "Separate dataset: ", current.dataset.is_some());
+        let mut dataset_btn = form::button_row(
+            2,
+            "Validation dataset: ",
+            &current
+                .dataset
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or("Select dataset".to_string()),
+        );
+        let task = form::choice_row(
+            3,
+            "Task: ",
+            &TASKS,
+            TASKS[match current.task {
+                Task::Classification => 0,
+                Task::Regression => 1,
+            }],
+        );
+        let top_k = form::int_row(4, "Top k: ", current.top_k);
+        let dataset: Rc<RefCell<Option<PathBuf>>> = Rc::new(RefCell::new(current.dataset));
+        let selected = dataset.clone();
+        let mut selected_toggle = separate.clone();
+        dataset_btn.set_callback(move |btn| {
+            if let Some(path) = open_file_dialog(&DATASET_EXTENSIONS) {
+                btn.set_label(&path.file_name().unwrap().to_string_lossy());
+                selected.replace(Some(path));
+                selected_toggle.set(true);
+                selected_toggle.set_label("✔️");
+            }
+        });
+        window.end();
+        window.show();
+        window.set_callback(move |window| {
+            window.hide();
+            if let (Some(fraction), Some(top_k)) = (
+                form::parse::<f64>(&fraction.value(), "Held out fraction"),
+                form::parse::<i64>(&top_k.value(), "Top k"),
+            ) {
+                config.replace(ValidationConfig {
+                    fraction: fraction.clamp(0.0, 0.9),
+                    dataset: dataset.borrow().clone().filter(|_| separate.is_set()),
+                    task: if task.label() == TASKS[1] {
+                        Task::Regression
+                    } else {
+                        Task::Classification
+                    },
+                    top_k: top_k.max(1),
+                });
+            }
+        });
+    }
+}
@@ -35,8 +35,11 @@ impl AppMenuBar {
 
         window.end();
         SysMenuBar::set_window_menu_style(WindowMenuStyle::TabbingModePreferred);
+        AppMenuBar::file(&mut menu_bar, evt_sender.clone());
         let editor = AppMenuBar::editor(&mut menu_bar, evt_sender.clone());
         let training = AppMenuBar::training(&mut menu_bar, evt_sender.clone());
+        AppMenuBar::model(&mut menu_bar, evt_sender.clone());
+        AppMenuBar::export(&mut menu_bar, evt_sender.clone());
         let settings = AppMenuBar::settings(&mut menu_bar, evt_sender.clone());
         let help = AppMenuBar::help(&mut menu_bar, evt_sender);
         Self {
@@ -48,6 +51,58 @@ impl AppMenuBar {
             help,
         }
     }
+    fn file(menu_bar: &mut SysMenuBar, evt_sender: Sender<AppEvent>) {
+        let new = menu_bar.add_emit(
+            "File/New",
+            Shortcut::Ctrl | 'n',
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::NewProject,
+        );
+        apply_style(&mut menu_bar.at(new).unwrap());
+        let open = menu_bar.add_emit(
+            "File/Open...",
+            Shortcut::Ctrl | 'o',
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::OpenProject,
+        );
+        apply_style(&mut menu_bar.at(open).unwrap());
+        let save = menu_bar.add_emit(
+            "File/Save",
+            Shortcut::Ctrl | 's',
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::SaveProject,
+        );
+        apply_style(&mut menu_bar.at(save).unwrap());
+        // ctrl+shift+s is taken by the settings
+        let save_as = menu_bar.add_emit(
+            "File/Save As...",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::SaveProjectAs,
+        );
+        apply_style(&mut menu_bar.at(save_as).unwrap());
+        let import_dot = menu_bar.add_emit(
+            "File/Import DOT...",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::ImportDot,
+        );
+        apply_style(&mut menu_bar.at(import_dot).unwrap());
+        let load_weights = menu_bar.add_emit(
+            "File/Load Weights...",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender,
+            AppEvent::LoadWeights,
+        );
+        apply_style(&mut menu_bar.at(load_weights).unwrap());
+        apply_style(&mut menu_bar.find_item("File").unwrap());
+    }
     fn editor(menu_bar: &mut SysMenuBar, evt_sender: Sender<AppEvent>) -> i32 {
         let editor = menu_bar.add_emit(
             "Editor",
@@ -70,6 +125,100 @@ impl AppMenuBar {
         apply_style(&mut menu_bar.at(training).unwrap());
         training
     }
+    fn model(menu_bar: &mut SysMenuBar, evt_sender: Sender<AppEvent>) {
+        let summary = menu_bar.add_emit(
+            "Model/Summary",
+            Shortcut::Ctrl | Shortcut::Shift | 'm',
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::Summary,
+        );
+        apply_style(&mut menu_bar.at(summary).unwrap());
+        let snapshots = menu_bar.add_emit(
+            "Model/Snapshots...",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::Snapshots,
+        );
+        apply_style(&mut menu_bar.at(snapshots).unwrap());
+        let runs = menu_bar.add_emit(
+            "Model/Runs...",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::Runs,
+        );
+        apply_style(&mut menu_bar.at(runs).unwrap());
+        let sweep = menu_bar.add_emit(
+            "Model/Sweep...",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender,
+            AppEvent::Sweep,
+        );
+        apply_style(&mut menu_bar.at(sweep).unwrap());
+        apply_style(&mut menu_bar.find_item("Model").unwrap());
+    }
+    fn export(menu_bar: &mut SysMenuBar, evt_sender: Sender<AppEvent>) {
+        let dot = menu_bar.add_emit(
+            "Export/Graphviz (DOT)",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::ExportDot,
+        );
+        apply_style(&mut menu_bar.at(dot).unwrap());
+        let diagram_svg = menu_bar.add_emit(
+            "Export/Diagram (SVG)",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::ExportDiagramSvg,
+        );
+        apply_style(&mut menu_bar.at(diagram_svg).unwrap());
+        let diagram_png = menu_bar.add_emit(
+            "Export/Diagram (PNG)",
+            Shortcut::None,
+            MenuFlag::Normal | MenuFlag::MenuDivider,
+            evt_sender.clone(),
+            AppEvent::ExportDiagramPng,
+        );
+        apply_style(&mut menu_bar.at(diagram_png).unwrap());
+        let torchscript = menu_bar.add_emit(
+            "Export/TorchScript",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::ExportTorchScript,
+        );
+        apply_style(&mut menu_bar.at(torchscript).unwrap());
+        let onnx = menu_bar.add_emit(
+            "Export/ONNX",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::ExportOnnx,
+        );
+        apply_style(&mut menu_bar.at(onnx).unwrap());
+        let rust_code = menu_bar.add_emit(
+            "Export/Rust code",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::ExportRustCode,
+        );
+        apply_style(&mut menu_bar.at(rust_code).unwrap());
+        let python_code = menu_bar.add_emit(
+            "Export/PyTorch code",
+            Shortcut::None,
+            MenuFlag::Normal,
+            evt_sender.clone(),
+            AppEvent::ExportPythonCode,
+        );
+        apply_style(&mut menu_bar.at(python_code).unwrap());
+        apply_style(&mut menu_bar.find_item("Export").unwrap());
+    }
     fn settings(menu_bar: &mut SysMenuBar, evt_sender: Sender<AppEvent>) -> i32 {
         let settings = menu_bar.add_emit(
             "Settings",
@@ -1,22 +1,38 @@
-use fltk::app::{App, Receiver, Scheme};
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use fltk::app::{App, Receiver, Scheme, Sender};
+use fltk::enums::Color;
 use fltk::prelude::{GroupExt, WidgetBase, WidgetExt, WindowExt};
 use fltk::window::Window;
 
 use crate::app::mainview::MainView;
 use crate::app::menubar::AppMenuBar;
-use crate::utils::consts::{MENU_BAR_RATIO, WINDOW_TITLE};
+use crate::app::runs::RunBrowser;
+use crate::app::snapshots::SnapshotBrowser;
+use crate::project::{dot, Project, PROJECT_EXTENSION};
+use crate::utils::consts::{BG_COLOR, MENU_BAR_RATIO, WINDOW_TITLE};
 use crate::utils::enums::{AppEvent, AppMode};
+use crate::utils::{open_file_dialog, save_file_dialog, CustomDialog};
 
 mod mainview;
 mod menubar;
+mod runs;
+mod snapshots;
 
 pub(crate) struct Application {
     pub(crate) mode: AppMode,
     pub(crate) app: App,
     pub(crate) window: Window,
+    pub(crate) evt_sender: Sender<AppEvent>,
     pub(crate) evt_recv: Receiver<AppEvent>,
     pub(crate) menu_bar: AppMenuBar,
     pub(crate) main_view: MainView,
+    // last saved or opened state, the project is dirty when the editor differs
+    project_path: Option<PathBuf>,
+    saved: Project,
+    dirty: Rc<Cell<bool>>,
 }
 
 impl Application {
@@ -25,18 +41,33 @@ impl Application {
         let mut window = Window::new(0, 0, width, height, WINDOW_TITLE).center_screen();
         let (evt_sender, evt_recv) = fltk::app::channel();
         let menu_bar = AppMenuBar::new(evt_sender.clone(), 0, 0, width, height / MENU_BAR_RATIO);
-        let main_view = MainView::new(evt_sender, 0, menu_bar.h(), width, height - menu_bar.h());
+        let main_view = MainView::new(
+            evt_sender.clone(),
+            0,
+            menu_bar.h(),
+            width,
+            height - menu_bar.h(),
+        );
         window.end();
+        let dirty = Rc::new(Cell::new(false));
+        let close_dirty = dirty.clone();
         window.set_callback(move |_| {
-            app.quit();
+            if !close_dirty.get() || confirm_discard() {
+                app.quit();
+            }
         });
+        let saved = main_view.project();
         Application {
             mode: AppMode::Editor,
             app,
             window,
+            evt_sender,
             evt_recv,
             menu_bar,
             main_view,
+            project_path: None,
+            saved,
+            dirty,
         }
     }
     pub(crate) fn run(mut self) {
@@ -64,12 +95,161 @@ impl Application {
                     AppEvent::Help => {
                         eprintln!("Showing help");
                     }
+                    AppEvent::ImportDot => self.import_dot(),
+                    AppEvent::ExportDot => self.main_view.export_dot(),
+                    AppEvent::ExportDiagramSvg => self.main_view.export_diagram_svg(),
+                    AppEvent::ExportDiagramPng => self.main_view.export_diagram_png(),
+                    AppEvent::ExportTorchScript => self.main_view.export_torchscript(),
+                    AppEvent::ExportOnnx => self.main_view.export_onnx(),
+                    AppEvent::ExportRustCode => self.main_view.export_rust_code(),
+                    AppEvent::ExportPythonCode => self.main_view.export_python_code(),
+                    AppEvent::NewProject => self.new_project(),
+                    AppEvent::OpenProject => self.open_project(),
+                    AppEvent::SaveProject => self.save_project(false),
+                    AppEvent::SaveProjectAs => self.save_project(true),
+                    AppEvent::LoadWeights => self.main_view.load_weights(),
+                    AppEvent::Summary => self.main_view.show_summary(),
+                    AppEvent::Snapshots => self.show_snapshots(),
+                    AppEvent::Runs => self.show_runs(),
+                    AppEvent::Sweep => self.main_view.show_sweep(),
+                    AppEvent::RestoreSnapshot(project) => self.restore_snapshot(&project),
+                    AppEvent::StartTraining => self.main_view.start_training(),
+                    AppEvent::ResumeTraining(path) => self.main_view.resume_training(&path),
+                    AppEvent::TrainingProgress(progress) => {
+                        self.main_view.training_progress(progress)
+                    }
+                    AppEvent::StartSweep(config) => self.main_view.start_sweep(&config),
+                    AppEvent::StopSweep => self.main_view.stop_sweep(),
+                    AppEvent::PromoteTrial(trial) => self.promote_trial(trial),
                 }
             }
+            let dirty = self.main_view.project() != self.saved;
+            if dirty != self.dirty.get() {
+                self.dirty.set(dirty);
+                self.update_title();
+            }
+        }
+    }
+    fn new_project(&mut self) {
+        if self.dirty.get() && !confirm_discard() {
+            return;
+        }
+        let project = Project::new(Default::default(), Default::default());
+        if let Err(e) = self.main_view.load_project(&project) {
+            CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+            return;
+        }
+        self.project_path = None;
+        self.set_saved();
+    }
+    fn open_project(&mut self) {
+        if self.dirty.get() && !confirm_discard() {
+            return;
+        }
+        let Some(path) = open_file_dialog(&[PROJECT_EXTENSION]) else {
+            return;
+        };
+        match Project::load(&path).and_then(|project| self.main_view.load_project(&project)) {
+            Ok(()) => {
+                self.project_path = Some(path);
+                self.set_saved();
+            }
+            Err(e) => CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red),
+        }
+    }
+    // snapshots are stored next to the save path of the model
+    fn show_snapshots(&self) {
+        match self.main_view.project().settings.save_path {
+            Some(save_path) => SnapshotBrowser::show(&save_path, self.evt_sender.clone()),
+            None => CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "Select a save path first",
+                BG_COLOR,
+                Color::Red,
+            ),
+        }
+    }
+    // runs are recorded next to the save path of the model
+    fn show_runs(&self) {
+        match self.main_view.project().settings.save_path {
+            Some(save_path) => RunBrowser::show(&save_path),
+            None => CustomDialog::show(
+                200,
+                40,
+                "Error",
+                "Select a save path first",
+                BG_COLOR,
+                Color::Red,
+            ),
+        }
+    }
+    // the restored state is unsaved, the project file is kept
+    fn restore_snapshot(&mut self, project: &Project) {
+        if self.dirty.get() && !confirm_discard() {
+            return;
+        }
+        if let Err(e) = self.main_view.load_project(project) {
+            CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+        }
+    }
+    // the graph and settings of a sweep trial replace those of the editor,
+    // unsaved as a restored snapshot
+    fn promote_trial(&mut self, trial: usize) {
+        if let Some(project) = self.main_view.trial_project(trial) {
+            self.restore_snapshot(&project);
+        }
+    }
+    // replaces the graph of the current project, the settings are kept
+    fn import_dot(&mut self) {
+        if self.dirty.get() && !confirm_discard() {
+            return;
+        }
+        let Some(path) = open_file_dialog(&["dot", "gv"]) else {
+            return;
+        };
+        if let Err(e) = dot::load(&path).and_then(|graph| self.main_view.load_graph(&graph)) {
+            CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+        }
+    }
+    fn save_project(&mut self, save_as: bool) {
+        let path = match self.project_path.clone() {
+            Some(path) if !save_as => path,
+            _ => match save_file_dialog(&[PROJECT_EXTENSION]) {
+                Some(path) => path,
+                None => return,
+            },
+        };
+        match self.main_view.project().save(&path) {
+            Ok(()) => {
+                self.project_path = Some(path);
+                self.set_saved();
+            }
+            Err(e) => CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red),
+        }
+    }
+    fn set_saved(&mut self) {
+        self.saved = self.main_view.project();
+        self.dirty.set(false);
+        self.update_title();
+    }
+    fn update_title(&mut self) {
+        let mut title = WINDOW_TITLE.to_string();
+        if let Some(name) = self.project_path.as_ref().and_then(|p| p.file_name()) {
+            title.push_str(&format!(" - {}", name.to_string_lossy()));
+        }
+        if self.dirty.get() {
+            title.push('*');
         }
+        self.window.set_label(&title);
     }
 }
 
+fn confirm_discard() -> bool {
+    fltk::dialog::choice2_default("Discard unsaved changes?", "No", "Yes", "") == Some(1)
+}
+
 impl Default for Application {
     fn default() -> Self {
         let (w, h) = fltk::app::screen_size();
@@ -0,0 +1,173 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+use std::rc::Rc;
+
+use fltk::browser::MultiBrowser;
+use fltk::enums::{Align, Color, Font, FrameType};
+use fltk::menu::Choice;
+use fltk::prelude::{BrowserExt, DisplayExt, GroupExt, MenuExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::text::{TextBuffer, TextDisplay};
+use fltk::window::Window;
+
+use crate::app::mainview::training::chart::Chart;
+use crate::project::snapshot::format_timestamp;
+use crate::training::metrics::METRICS;
+use crate::training::run::Run;
+use crate::utils::consts::{BG_COLOR, HIGHLIGHT_COLOR, RUNS_WINDOW_HEIGHT, RUNS_WINDOW_WIDTH};
+use crate::utils::CustomDialog;
+
+// lists the runs of a save path, the selected runs have their curves of a
+// metric overlaid and their settings and best metrics side by side
+pub(crate) struct RunBrowser {}
+
+impl RunBrowser {
+    pub(crate) fn show(save_path: &Path) {
+        let runs = match Run::list(save_path) {
+            Ok(runs) if runs.is_empty() => {
+                CustomDialog::show(
+                    300,
+                    40,
+                    "Runs",
+                    "No runs, train the model first",
+                    BG_COLOR,
+                    Color::Red,
+                );
+                return;
+            }
+            Ok(runs) => Rc::new(runs),
+            Err(e) => {
+                CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+                return;
+            }
+        };
+        let mut window = Window::default()
+            .with_size(RUNS_WINDOW_WIDTH, RUNS_WINDOW_HEIGHT)
+            .with_label("Runs")
+            .center_screen();
+        window.set_color(Color::White);
+        let top_h = RUNS_WINDOW_HEIGHT * 3 / 5;
+        let list_w = RUNS_WINDOW_WIDTH * 2 / 5;
+        let choice_h = RUNS_WINDOW_HEIGHT / 20;
+
+        let mut browser = MultiBrowser::default()
+            .with_pos(2, 2)
+            .with_size(list_w - 4, top_h - 4);
+        browser.set_frame(FrameType::FlatBox);
+        browser.set_color(BG_COLOR);
+        browser.set_text_color(Color::White);
+        browser.set_text_font(Font::Courier);
+        browser.set_selection_color(HIGHLIGHT_COLOR);
+        // newest first
+        for run in runs.iter().rev() {
+            browser.add(&run.title());
+        }
+
+        let mut metric = Choice::default()
+            .with_pos(list_w + 80, 2)
+            .with_size(RUNS_WINDOW_WIDTH - list_w - 82, choice_h)
+            .with_label("Metric: ")
+            .with_align(Align::Left);
+        metric.set_frame(FrameType::FlatBox);
+        metric.set_color(BG_COLOR);
+        metric.set_text_color(Color::White);
+        metric.set_selection_color(HIGHLIGHT_COLOR);
+        for name in METRICS {
+            metric.add_choice(name);
+        }
+        metric.set_value(0);
+        let chart = Chart::new(
+            list_w,
+            choice_h + 4,
+            RUNS_WINDOW_WIDTH - list_w - 2,
+            top_h - choice_h - 6,
+            "Metric per epoch",
+            "epoch",
+        );
+
+        let mut display = TextDisplay::default()
+            .with_pos(2, top_h)
+            .with_size(RUNS_WINDOW_WIDTH - 4, RUNS_WINDOW_HEIGHT - top_h - 2);
+        display.set_buffer(TextBuffer::default());
+        display.set_frame(FrameType::FlatBox);
+        display.set_color(BG_COLOR);
+        display.set_text_color(Color::White);
+        display.set_text_font(Font::Courier);
+        display.set_selection_color(HIGHLIGHT_COLOR);
+
+        let mut update = {
+            let browser = browser.clone();
+            let metric = metric.clone();
+            let mut chart = chart.clone();
+            let runs = runs.clone();
+            move || {
+                let selected = (1..=browser.size())
+                    .filter(|line| browser.selected(*line))
+                    .map(|line| (line, &runs[runs.len() - line as usize]))
+                    .collect::<Vec<_>>();
+                let name = metric.choice().unwrap_or(METRICS[0].to_string());
+                chart.clear();
+                for (line, run) in selected.iter() {
+                    // runs are named by their line, they may start within a second
+                    let series = format!("#{} {}", line, format_timestamp(run.started / 1000));
+                    for record in run.epochs.iter() {
+                        if let Some(value) = record.metrics.get(&name) {
+                            chart.push(&series, record.epoch as f64, *value);
+                        }
+                    }
+                }
+                let selected = selected.into_iter().map(|(_, run)| run).collect::<Vec<_>>();
+                display.buffer().unwrap().set_text(&table(&selected));
+            }
+        };
+        let mut browser_update = update.clone();
+        browser.set_callback(move |_| browser_update());
+        metric.set_callback(move |_| update());
+        window.end();
+        window.show();
+    }
+}
+
+// one row per run, the settings then the best value of every metric
+fn table(runs: &[&Run]) -> String {
+    if runs.is_empty() {
+        return "Select runs to compare".to_string();
+    }
+    let bests = runs.iter().map(|run| run.best()).collect::<Vec<_>>();
+    let metrics = bests
+        .iter()
+        .flat_map(|best| best.keys().cloned())
+        .collect::<BTreeSet<_>>();
+    let mut text = format!(
+        "{:<20} {:<8} {:<10} {:<18} {:>6} {:>5} {:>6} {:>20}",
+        "run", "opt", "lr", "scheduler", "batch", "accum", "epochs", "seed"
+    );
+    for name in metrics.iter() {
+        write!(text, " {:>15}", name).unwrap();
+    }
+    text.push('\n');
+    for (run, best) in runs.iter().zip(bests.iter()) {
+        let settings = &run.settings;
+        write!(
+            text,
+            "{:<20} {:<8} {:<10} {:<18} {:>6} {:>5} {:>6} {:>20}",
+            format_timestamp(run.started / 1000),
+            settings.optimizer.as_ref().map_or("-", |o| o.name()),
+            settings.lr,
+            settings.scheduler.name(),
+            settings.batch_size,
+            settings.gradients.accumulation.max(1),
+            settings.epochs,
+            run.seed
+        )
+        .unwrap();
+        for name in metrics.iter() {
+            match best.get(name) {
+                Some(value) => write!(text, " {:>15.6}", value).unwrap(),
+                None => write!(text, " {:>15}", "-").unwrap(),
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
@@ -0,0 +1,152 @@
+use std::path::Path;
+use std::rc::Rc;
+
+use fltk::app::Sender;
+use fltk::browser::MultiBrowser;
+use fltk::button::Button;
+use fltk::enums::{Color, Font, FrameType};
+use fltk::frame::Frame;
+use fltk::prelude::{BrowserExt, DisplayExt, GroupExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::text::{TextBuffer, TextDisplay};
+use fltk::window::Window;
+
+use crate::project::snapshot::{self, Snapshot};
+use crate::utils::consts::{
+    BG_COLOR, HIGHLIGHT_COLOR, SNAPSHOT_WINDOW_HEIGHT, SNAPSHOT_WINDOW_WIDTH,
+};
+use crate::utils::enums::AppEvent;
+use crate::utils::CustomDialog;
+
+// lists the snapshots of a save path, one selected snapshot can be restored,
+// two selected snapshots can be compared
+pub(crate) struct SnapshotBrowser {}
+
+impl SnapshotBrowser {
+    pub(crate) fn show(save_path: &Path, evt_sender: Sender<AppEvent>) {
+        let snapshots = match snapshot::list(save_path) {
+            Ok(snapshots) if snapshots.is_empty() => {
+                CustomDialog::show(
+                    300,
+                    40,
+                    "Snapshots",
+                    "No snapshots, build the model first",
+                    BG_COLOR,
+                    Color::Red,
+                );
+                return;
+            }
+            Ok(snapshots) => Rc::new(snapshots),
+            Err(e) => {
+                CustomDialog::show(400, 60, "Error", &e, BG_COLOR, Color::Red);
+                return;
+            }
+        };
+        let mut window = Window::default()
+            .with_size(SNAPSHOT_WINDOW_WIDTH, SNAPSHOT_WINDOW_HEIGHT)
+            .with_label("Snapshots")
+            .center_screen();
+        window.set_color(Color::White);
+        let button_h = SNAPSHOT_WINDOW_HEIGHT / 12;
+        let list_h = (SNAPSHOT_WINDOW_HEIGHT - button_h) / 2;
+
+        let mut browser = MultiBrowser::default()
+            .with_pos(2, 2)
+            .with_size(SNAPSHOT_WINDOW_WIDTH - 4, list_h - 4);
+        browser.set_frame(FrameType::FlatBox);
+        browser.set_color(BG_COLOR);
+        browser.set_text_color(Color::White);
+        browser.set_text_font(Font::Courier);
+        browser.set_selection_color(HIGHLIGHT_COLOR);
+        // newest first
+        for snapshot in snapshots.iter().rev() {
+            browser.add(&snapshot.title());
+        }
+
+        let mut buttons_bg = Frame::default()
+            .with_pos(0, list_h - 2)
+            .with_size(SNAPSHOT_WINDOW_WIDTH, button_h + 2);
+        buttons_bg.set_color(Color::White);
+        buttons_bg.set_frame(FrameType::FlatBox);
+
+        let mut display = TextDisplay::default()
+            .with_pos(2, list_h + button_h + 2)
+            .with_size(
+                SNAPSHOT_WINDOW_WIDTH - 4,
+                SNAPSHOT_WINDOW_HEIGHT - list_h - button_h - 4,
+            );
+        display.set_buffer(TextBuffer::default());
+        display.set_frame(FrameType::FlatBox);
+        display.set_color(BG_COLOR);
+        display.set_text_color(Color::White);
+        display.set_text_font(Font::Courier);
+        display.set_selection_color(HIGHLIGHT_COLOR);
+
+        let selected = {
+            let browser = browser.clone();
+            let snapshots = snapshots.clone();
+            move || -> Vec<Snapshot> {
+                (1..=browser.size())
+                    .filter(|line| browser.selected(*line))
+                    .map(|line| snapshots[snapshots.len() - line as usize].clone())
+                    .collect()
+            }
+        };
+
+        let mut restore = Button::default()
+            .with_pos(2, buttons_bg.y() + 2)
+            .with_size(SNAPSHOT_WINDOW_WIDTH / 2 - 4, button_h - 2)
+            .with_label("Restore");
+        let mut diff = Button::default()
+            .with_pos(SNAPSHOT_WINDOW_WIDTH / 2 + 2, buttons_bg.y() + 2)
+            .with_size(SNAPSHOT_WINDOW_WIDTH / 2 - 4, button_h - 2)
+            .with_label("Diff");
+        for button in [&mut restore, &mut diff] {
+            button.set_color(BG_COLOR);
+            button.set_frame(FrameType::FlatBox);
+            button.set_label_color(Color::White);
+        }
+
+        let restore_selected = selected.clone();
+        let mut restore_window = window.clone();
+        restore.set_callback(move |_| match restore_selected().as_slice() {
+            [snapshot] => {
+                evt_sender.send(AppEvent::RestoreSnapshot(Box::new(
+                    snapshot.project.clone(),
+                )));
+                restore_window.hide();
+            }
+            _ => CustomDialog::show(
+                260,
+                40,
+                "Error",
+                "Select one snapshot to restore",
+                BG_COLOR,
+                Color::Red,
+            ),
+        });
+
+        diff.set_callback(move |_| match selected().as_slice() {
+            // the list is newest first, so the older snapshot comes last
+            [new, old] => {
+                let changes = snapshot::diff(&old.project, &new.project);
+                let mut text = format!("{}\n{}\n\n", old.title(), new.title());
+                if changes.is_empty() {
+                    text.push_str("No changes");
+                } else {
+                    text.push_str(&changes.join("\n"));
+                }
+                display.buffer().unwrap().set_text(&text);
+            }
+            _ => CustomDialog::show(
+                260,
+                40,
+                "Error",
+                "Select two snapshots to compare",
+                BG_COLOR,
+                Color::Red,
+            ),
+        });
+        window.end();
+        window.show();
+    }
+}
@@ -2,6 +2,7 @@ use std::ops::Deref;
 
 use crate::components::activation_functions::ActivationFunctionType;
 use crate::components::layers::LayerType;
+use crate::utils::consts::DEFAULT_KERNEL_SIZE;
 
 pub(crate) mod activation_functions;
 pub(crate) mod layers;
@@ -32,3 +33,51 @@ impl Deref for NNComponent {
         }
     }
 }
+
+impl NNComponent {
+    pub(crate) fn next(&self) -> Option<usize> {
+        match self {
+            NNComponent::Layer { next, .. } => *next,
+            NNComponent::ActivationFunction { next, .. } => *next,
+        }
+    }
+
+    // short description of the key config, as shown in summaries and diagrams
+    pub(crate) fn config_description(&self) -> String {
+        match self {
+            NNComponent::Layer {
+                layer_type,
+                observation_space: o,
+                action_space: a,
+                ..
+            } => match layer_type {
+                LayerType::Linear(c) => format!("in={}, out={}, bias={}", o, a, c.bias),
+                LayerType::Lstm(c) | LayerType::Gru(c) => format!(
+                    "in={}, hidden={}, layers={}, bidirectional={}",
+                    o, a, c.num_layers, c.bidirectional
+                ),
+                LayerType::BatchNorm1D(c)
+                | LayerType::BatchNorm2D(c)
+                | LayerType::BatchNorm3D(c) => format!(
+                    "features={}, eps={}, momentum={}, affine={}",
+                    o, c.eps, c.momentum, c.affine
+                ),
+                LayerType::Conv1D(c) | LayerType::Conv2D(c) | LayerType::Conv3D(c) => format!(
+                    "in={}, out={}, kernel={}, stride={}, padding={}",
+                    o, a, DEFAULT_KERNEL_SIZE, c.stride, c.padding
+                ),
+                LayerType::ConvTranspose1D(c)
+                | LayerType::ConvTranspose2D(c)
+                | LayerType::ConvTranspose3D(c) => format!(
+                    "in={}, out={}, kernel={}, stride={}, padding={}",
+                    o, a, DEFAULT_KERNEL_SIZE, c.stride, c.padding
+                ),
+            },
+            NNComponent::ActivationFunction { fn_type, .. } => match fn_type {
+                ActivationFunctionType::Softmax => "dim=-1".to_string(),
+                ActivationFunctionType::LeakyReLU => "slope=0.01".to_string(),
+                _ => String::new(),
+            },
+        }
+    }
+}
@@ -2,6 +2,9 @@ use crate::app::Application;
 
 mod app;
 pub(crate) mod components;
+pub(crate) mod model;
+pub(crate) mod project;
+pub(crate) mod training;
 pub(crate) mod utils;
 
 fn main() {
@@ -0,0 +1,217 @@
+use std::collections::HashSet;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+
+use tch::nn::{ModuleT, VarStore, RNN};
+use tch::{Device, Kind, Tensor};
+
+use crate::components::layers::LayerType;
+use crate::components::NNComponent;
+use crate::training::early_stopping::EarlyStopping;
+use crate::training::gradients::GradientConfig;
+use crate::training::scheduler::Scheduler;
+use crate::utils::consts::DEFAULT_KERNEL_SIZE;
+use crate::utils::loss_fn::LossFunction;
+use crate::utils::optimizer::OptimizerType;
+
+pub(crate) mod onnx;
+pub(crate) mod python_code;
+pub(crate) mod rust_code;
+pub(crate) mod safetensors;
+pub(crate) mod summary;
+pub(crate) mod torchscript;
+pub(crate) mod weights;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Hyperparameters {
+    pub(crate) save_path: PathBuf,
+    pub(crate) device: Device,
+    pub(crate) optimizer: OptimizerType,
+    pub(crate) loss_fn: LossFunction,
+    pub(crate) lr: f64,
+    pub(crate) scheduler: Scheduler,
+    pub(crate) batch_size: i64,
+    pub(crate) gradients: GradientConfig,
+    pub(crate) epochs: usize,
+    pub(crate) early_stopping: EarlyStopping,
+    // seeds the weight initialization and the run, a new one per run when unset
+    pub(crate) seed: Option<u64>,
+    pub(crate) deterministic: bool,
+}
+
+pub(crate) struct Model {
+    pub(crate) vs: VarStore,
+    layers: Vec<Box<dyn ModuleT>>,
+    // (index in the playground, component) in execution order
+    pub(crate) nodes: Vec<(usize, NNComponent)>,
+    pub(crate) input_shape: Vec<i64>,
+    // of every node on the input shape, in execution order
+    pub(crate) output_shapes: Vec<Vec<i64>>,
+    pub(crate) hyperparameters: Hyperparameters,
+}
+
+impl Model {
+    pub(crate) fn build(
+        components: &[NNComponent],
+        first: Option<usize>,
+        input_shape: Vec<i64>,
+        hyperparameters: Hyperparameters,
+    ) -> Result<Self, String> {
+        let order = execution_order(components, first)?;
+        if let Some(seed) = hyperparameters.seed {
+            tch::manual_seed(seed as i64);
+        }
+        let vs = VarStore::new(hyperparameters.device);
+        let root = vs.root();
+        let mut layers: Vec<Box<dyn ModuleT>> = vec![];
+        let mut nodes = vec![];
+        for i in order {
+            let component = components[i].clone();
+            let name = node_name(i, &component);
+            layers.push(match &component {
+                NNComponent::Layer {
+                    configured: false, ..
+                } => return Err(format!("{} is not configured", name)),
+                NNComponent::Layer {
+                    layer_type,
+                    observation_space: o,
+                    action_space: a,
+                    ..
+                } => {
+                    let (o, a, p) = (*o, *a, &root / &name);
+                    match layer_type.clone() {
+                        LayerType::Linear(c) => Box::new(tch::nn::linear(p, o, a, c)),
+                        LayerType::Lstm(c) => {
+                            let lstm = tch::nn::lstm(p, o, a, c);
+                            Box::new(tch::nn::func(move |xs| lstm.seq(xs).0))
+                        }
+                        LayerType::Gru(c) => {
+                            let gru = tch::nn::gru(p, o, a, c);
+                            Box::new(tch::nn::func(move |xs| gru.seq(xs).0))
+                        }
+                        LayerType::BatchNorm1D(c) => Box::new(tch::nn::batch_norm1d(p, o, c)),
+                        LayerType::BatchNorm2D(c) => Box::new(tch::nn::batch_norm2d(p, o, c)),
+                        LayerType::BatchNorm3D(c) => Box::new(tch::nn::batch_norm3d(p, o, c)),
+                        LayerType::Conv1D(c) => {
+                            Box::new(tch::nn::conv1d(p, o, a, DEFAULT_KERNEL_SIZE, c))
+                        }
+                        LayerType::Conv2D(c) => {
+                            Box::new(tch::nn::conv2d(p, o, a, DEFAULT_KERNEL_SIZE, c))
+                        }
+                        LayerType::Conv3D(c) => {
+                            Box::new(tch::nn::conv3d(p, o, a, DEFAULT_KERNEL_SIZE, c))
+                        }
+                        LayerType::ConvTranspose1D(c) => {
+                            Box::new(tch::nn::conv_transpose1d(p, o, a, DEFAULT_KERNEL_SIZE, c))
+                        }
+                        LayerType::ConvTranspose2D(c) => {
+                            Box::new(tch::nn::conv_transpose2d(p, o, a, DEFAULT_KERNEL_SIZE, c))
+                        }
+                        LayerType::ConvTranspose3D(c) => {
+                            Box::new(tch::nn::conv_transpose3d(p, o, a, DEFAULT_KERNEL_SIZE, c))
+                        }
+                    }
+                }
+                NNComponent::ActivationFunction { fn_type, .. } => {
+                    Box::new(tch::nn::func(fn_type.get()))
+                }
+            });
+            nodes.push((i, component));
+        }
+        let output_shapes = output_shapes(&layers, &nodes, &input_shape, vs.device())?;
+        Ok(Self {
+            vs,
+            layers,
+            nodes,
+            input_shape,
+            output_shapes,
+            hyperparameters,
+        })
+    }
+
+    pub(crate) fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
+        self.layers
+            .iter()
+            .fold(xs.shallow_clone(), |xs, layer| layer.forward_t(&xs, train))
+    }
+
+    pub(crate) fn forward(&self, xs: &Tensor) -> Tensor {
+        self.forward_t(xs, false)
+    }
+
+    pub(crate) fn sample_input(&self) -> Tensor {
+        Tensor::randn(self.input_shape.as_slice(), (Kind::Float, self.vs.device()))
+    }
+}
+
+// runs a sample input through the layers one at a time, tch panics on a shape
+// that does not fit a layer and the panic tells which one
+fn output_shapes(
+    layers: &[Box<dyn ModuleT>],
+    nodes: &[(usize, NNComponent)],
+    input_shape: &[i64],
+    device: Device,
+) -> Result<Vec<Vec<i64>>, String> {
+    tch::no_grad(|| {
+        let mut xs = Tensor::randn(input_shape, (Kind::Float, device));
+        let mut shapes = vec![];
+        for (layer, (i, component)) in layers.iter().zip(nodes.iter()) {
+            xs = std::panic::catch_unwind(AssertUnwindSafe(|| layer.forward_t(&xs, false)))
+                .map_err(|e| {
+                    format!(
+                        "Input shape {:?} does not fit {}: {}",
+                        input_shape,
+                        node_name(*i, component),
+                        e.downcast_ref::<String>()
+                            .cloned()
+                            .or(e.downcast_ref::<&str>().map(|s| s.to_string()))
+                            .unwrap_or_default()
+                    )
+                })?;
+            shapes.push(xs.size());
+        }
+        Ok(shapes)
+    })
+}
+
+// follows the `next` links starting from the first component
+pub(crate) fn execution_order(
+    components: &[NNComponent],
+    first: Option<usize>,
+) -> Result<Vec<usize>, String> {
+    let mut order = vec![];
+    let mut visited = HashSet::new();
+    let mut current = first.ok_or("First component not selected")?;
+    loop {
+        let component = components
+            .get(current)
+            .ok_or(format!("Component {} does not exist", current))?;
+        if !visited.insert(current) {
+            return Err(format!(
+                "Cycle detected at {}",
+                node_name(current, component)
+            ));
+        }
+        order.push(current);
+        match component.next() {
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+    Ok(order)
+}
+
+// name under which the node variables are stored in the var store
+pub(crate) fn node_name(i: usize, component: &NNComponent) -> String {
+    format!("{}_{}", component.to_lowercase(), i)
+}
+
+pub(crate) fn parse_shape(shape: &str) -> Result<Vec<i64>, String> {
+    shape
+        .split(',')
+        .map(|dim| match dim.trim().parse::<i64>() {
+            Ok(v) if v > 0 => Ok(v),
+            _ => Err(format!("Invalid dimension: {}", dim.trim())),
+        })
+        .collect()
+}
@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use tch::nn::{PaddingMode, RNNConfig};
+use tch::{Device, Kind, Tensor};
+
+use crate::components::activation_functions::ActivationFunctionType;
+use crate::components::layers::LayerType;
+use crate::components::NNComponent;
+use crate::model::{node_name, Model};
+use crate::utils::consts::DEFAULT_KERNEL_SIZE;
+use crate::utils::protobuf::Message;
+
+const IR_VERSION: i64 = 8;
+const OPSET_VERSION: i64 = 17;
+// TensorProto.DataType
+const FLOAT: u64 = 1;
+const INT64: u64 = 7;
+// AttributeProto.AttributeType
+const ATTR_FLOAT: u64 = 1;
+const ATTR_INT: u64 = 2;
+const ATTR_STRING: u64 = 3;
+const ATTR_INTS: u64 = 7;
+
+pub(crate) fn export(model: &Model, path: &Path) -> Result<(), String> {
+    let unsupported = unsupported_nodes(model);
+    if !unsupported.is_empty() {
+        return Err(format!(
+            "Unsupported nodes, nothing was exported:\n{}",
+            unsupported.join("\n")
+        ));
+    }
+    let variables = model.vs.variables();
+
+    let mut graph = Graph::default();
+    let mut current = "input".to_string();
+    let mut current_shape = model.input_shape.clone();
+    for ((i, component), shape) in model.nodes.iter().zip(model.output_shapes.iter()) {
+        let name = node_name(*i, component);
+        let var = |suffix: &str| variables.get(&format!("{}.{}", name, suffix));
+        match component {
+            NNComponent::Layer { layer_type, .. } => match layer_type {
+                LayerType::Linear(_) => {
+                    let weight = var("weight").unwrap();
+                    let bias = var("bias");
+                    if current_shape.len() == 2 {
+                        let w = graph.initializer(&format!("{}.weight", name), weight)?;
+                        let mut inputs = vec![current.clone(), w];
+                        if let Some(bias) = bias {
+                            inputs.push(graph.initializer(&format!("{}.bias", name), bias)?);
+                        }
+                        graph.node("Gemm", &name, &inputs, vec![attr_int("transB", 1)]);
+                    } else {
+                        let w = graph.initializer(&format!("{}.weight_t", name), &weight.tr())?;
+                        match bias {
+                            Some(bias) => {
+                                let matmul = format!("{}_matmul", name);
+                                graph.node("MatMul", &matmul, &[current.clone(), w], vec![]);
+                                let b = graph.initializer(&format!("{}.bias", name), bias)?;
+                                graph.node("Add", &name, &[matmul, b], vec![]);
+                            }
+                            None => graph.node("MatMul", &name, &[current.clone(), w], vec![]),
+                        }
+                    }
+                }
+                LayerType::Lstm(c) => graph.rnn("LSTM", &name, &current, c, &variables)?,
+                LayerType::Gru(c) => graph.rnn("GRU", &name, &current, c, &variables)?,
+                LayerType::BatchNorm1D(c)
+                | LayerType::BatchNorm2D(c)
+                | LayerType::BatchNorm3D(c) => {
+                    let mean = var("running_mean").unwrap();
+                    let scale = match var("weight") {
+                        Some(weight) => weight.shallow_clone(),
+                        None => mean.ones_like(),
+                    };
+                    let bias = match var("bias") {
+                        Some(bias) => bias.shallow_clone(),
+                        None => mean.zeros_like(),
+                    };
+                    let inputs = [
+                        current.clone(),
+                        graph.initializer(&format!("{}.weight", name), &scale)?,
+                        graph.initializer(&format!("{}.bias", name), &bias)?,
+                        graph.initializer(&format!("{}.running_mean", name), mean)?,
+                        graph.initializer(
+                            &format!("{}.running_var", name),
+                            var("running_var").unwrap(),
+                        )?,
+                    ];
+                    // onnx momentum weights the running statistics, torch weights the batch ones
+                    let attributes = vec![
+                        attr_float("epsilon", c.eps as f32),
+                        attr_float("momentum", 1.0 - c.momentum as f32),
+                    ];
+                    graph.node("BatchNormalization", &name, &inputs, attributes);
+                }
+                LayerType::Conv1D(c) | LayerType::Conv2D(c) | LayerType::Conv3D(c) => {
+                    let nd = current_shape.len() - 2;
+                    let mut inputs = vec![
+                        current.clone(),
+                        graph.initializer(&format!("{}.weight", name), var("weight").unwrap())?,
+                    ];
+                    if let Some(bias) = var("bias") {
+                        inputs.push(graph.initializer(&format!("{}.bias", name), bias)?);
+                    }
+                    let attributes = vec![
+                        attr_ints("kernel_shape", &vec![DEFAULT_KERNEL_SIZE; nd]),
+                        attr_ints("strides", &vec![c.stride; nd]),
+                        attr_ints("pads", &vec![c.padding; nd * 2]),
+                        attr_ints("dilations", &vec![c.dilation; nd]),
+                        attr_int("group", c.groups),
+                    ];
+                    graph.node("Conv", &name, &inputs, attributes);
+                }
+                LayerType::ConvTranspose1D(c)
+                | LayerType::ConvTranspose2D(c)
+                | LayerType::ConvTranspose3D(c) => {
+                    let nd = current_shape.len() - 2;
+                    let mut inputs = vec![
+                        current.clone(),
+                        graph.initializer(&format!("{}.weight", name), var("weight").unwrap())?,
+                    ];
+                    if let Some(bias) = var("bias") {
+                        inputs.push(graph.initializer(&format!("{}.bias", name), bias)?);
+                    }
+                    let attributes = vec![
+                        attr_ints("kernel_shape", &vec![DEFAULT_KERNEL_SIZE; nd]),
+                        attr_ints("strides", &vec![c.stride; nd]),
+                        attr_ints("pads", &vec![c.padding; nd * 2]),
+                        attr_ints("output_padding", &vec![c.output_padding; nd]),
+                        attr_ints("dilations", &vec![c.dilation; nd]),
+                        attr_int("group", c.groups),
+                    ];
+                    graph.node("ConvTranspose", &name, &inputs, attributes);
+                }
+            },
+            NNComponent::ActivationFunction { fn_type, .. } => {
+                let inputs = [current.clone()];
+                match fn_type {
+                    ActivationFunctionType::ReLU => graph.node("Relu", &name, &inputs, vec![]),
+                    ActivationFunctionType::Sigmoid => {
+                        graph.node("Sigmoid", &name, &inputs, vec![])
+                    }
+                    ActivationFunctionType::Tanh => graph.node("Tanh", &name, &inputs, vec![]),
+                    ActivationFunctionType::Softmax => {
+                        graph.node("Softmax", &name, &inputs, vec![attr_int("axis", -1)])
+                    }
+                    ActivationFunctionType::LeakyReLU => {
+                        graph.node("LeakyRelu", &name, &inputs, vec![attr_float("alpha", 0.01)])
+                    }
+                    ActivationFunctionType::Flatten => {
+                        // mirrors `x.view([-1, x.size()[1]])`
+                        let shape = graph
+                            .int64_initializer(&format!("{}.shape", name), &[-1, current_shape[1]]);
+                        graph.node("Reshape", &name, &[current.clone(), shape], vec![])
+                    }
+                }
+            }
+        }
+        current = name;
+        current_shape = shape.clone();
+    }
+
+    let mut graph_proto = Message::new();
+    graph.nodes.iter().for_each(|node| {
+        graph_proto.message(1, node);
+    });
+    graph_proto.string(2, "mlgui");
+    graph.initializers.iter().for_each(|initializer| {
+        graph_proto.message(5, initializer);
+    });
+    graph_proto
+        .message(11, &value_info("input", &model.input_shape))
+        .message(12, &value_info(&current, &current_shape));
+
+    let mut opset = Message::new();
+    opset.string(1, "").int64(2, OPSET_VERSION);
+    let mut model_proto = Message::new();
+    model_proto
+        .int64(1, IR_VERSION)
+        .string(2, "mlgui")
+        .string(3, env!("CARGO_PKG_VERSION"))
+        .message(7, &graph_proto)
+        .message(8, &opset);
+    std::fs::write(path, model_proto.into_bytes())
+        .map_err(|e| format!("Error writing the onnx file: \n{:?}", e))
+}
+
+// nodes that have no faithful onnx equivalent
+fn unsupported_nodes(model: &Model) -> Vec<String> {
+    model
+        .nodes
+        .iter()
+        .filter_map(|(i, component)| {
+            let name = node_name(*i, component);
+            match component {
+                NNComponent::Layer {
+                    layer_type: LayerType::Conv1D(c) | LayerType::Conv2D(c) | LayerType::Conv3D(c),
+                    ..
+                } if !matches!(c.padding_mode, PaddingMode::Zeros) => Some(format!(
+                    "{} ({}): padding mode {:?}",
+                    name, **component, c.padding_mode
+                )),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Graph {
+    nodes: Vec<Message>,
+    initializers: Vec<Message>,
+}
+
+impl Graph {
+    // the node output is named after the node
+    fn node(&mut self, op_type: &str, name: &str, inputs: &[String], attributes: Vec<Message>) {
+        let mut node = Message::new();
+        inputs.iter().for_each(|input| {
+            node.string(1, input);
+        });
+        node.string(2, name).string(3, name).string(4, op_type);
+        attributes.iter().for_each(|attribute| {
+            node.message(5, attribute);
+        });
+        self.nodes.push(node);
+    }
+
+    fn initializer(&mut self, name: &str, tensor: &Tensor) -> Result<String, String> {
+        let tensor = tensor
+            .detach()
+            .to_device(Device::Cpu)
+            .to_kind(Kind::Float)
+            .contiguous();
+        let values = Vec::<f32>::try_from(&tensor.flatten(0, -1))
+            .map_err(|e| format!("Error reading {}: \n{:?}", name, e))?;
+        let raw: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let mut proto = Message::new();
+        proto
+            .packed_int64(1, &tensor.size())
+            .varint(2, FLOAT)
+            .string(8, name)
+            .bytes(9, &raw);
+        self.initializers.push(proto);
+        Ok(name.to_string())
+    }
+
+    fn int64_initializer(&mut self, name: &str, values: &[i64]) -> String {
+        let raw: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let mut proto = Message::new();
+        proto
+            .packed_int64(1, &[values.len() as i64])
+            .varint(2, INT64)
+            .string(8, name)
+            .bytes(9, &raw);
+        self.initializers.push(proto);
+        name.to_string()
+    }
+
+    // onnx rnn nodes are single layer, stacked layers are chained
+    fn rnn(
+        &mut self,
+        op_type: &str,
+        name: &str,
+        input: &str,
+        c: &RNNConfig,
+        variables: &HashMap<String, Tensor>,
+    ) -> Result<(), String> {
+        let directions: &[&str] = if c.bidirectional {
+            &["", "_reverse"]
+        } else {
+            &[""]
+        };
+        let reorder = |t: &Tensor| match op_type {
+            // torch gates are (i, f, g, o), onnx ones are (i, o, f, c)
+            "LSTM" => {
+                let gates = t.chunk(4, 0);
+                Tensor::cat(&[&gates[0], &gates[3], &gates[1], &gates[2]], 0)
+            }
+            // torch gates are (r, z, n), onnx ones are (z, r, h)
+            _ => {
+                let gates = t.chunk(3, 0);
+                Tensor::cat(&[&gates[1], &gates[0], &gates[2]], 0)
+            }
+        };
+        let mut current = input.to_string();
+        for layer in 0..c.num_layers {
+            let var = |kind: &str, suffix: &str| {
+                variables
+                    .get(&format!("{}.{}_l{}{}", name, kind, layer, suffix))
+                    .map(reorder)
+            };
+            let stack = |kind: &str| {
+                Tensor::stack(
+                    &directions
+                        .iter()
+                        .map(|suffix| var(kind, suffix).unwrap())
+                        .collect::<Vec<_>>(),
+                    0,
+                )
+            };
+            let hidden_size = variables[&format!("{}.weight_hh_l{}", name, layer)].size()[1];
+            let layer_name = format!("{}_l{}", name, layer);
+            let mut inputs = vec![
+                current.clone(),
+                self.initializer(&format!("{}.W", layer_name), &stack("weight_ih"))?,
+                self.initializer(&format!("{}.R", layer_name), &stack("weight_hh"))?,
+            ];
+            if c.has_biases {
+                let biases = directions
+                    .iter()
+                    .map(|suffix| {
+                        Tensor::cat(
+                            &[
+                                var("bias_ih", suffix).unwrap(),
+                                var("bias_hh", suffix).unwrap(),
+                            ],
+                            0,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                inputs.push(
+                    self.initializer(&format!("{}.B", layer_name), &Tensor::stack(&biases, 0))?,
+                );
+            }
+            let mut attributes = vec![
+                attr_int("hidden_size", hidden_size),
+                attr_string(
+                    "direction",
+                    if c.bidirectional {
+                        "bidirectional"
+                    } else {
+                        "forward"
+                    },
+                ),
+                attr_int("layout", c.batch_first as i64),
+            ];
+            if op_type == "GRU" {
+                attributes.push(attr_int("linear_before_reset", 1));
+            }
+            let mut y = format!("{}_y", layer_name);
+            self.node(op_type, &y, &inputs, attributes);
+            // Y is (seq, directions, batch, hidden) or (batch, seq, directions, hidden) with the
+            // batch first layout, torch concatenates the directions in the last dimension
+            if !c.batch_first {
+                let transposed = format!("{}_transpose", layer_name);
+                self.node(
+                    "Transpose",
+                    &transposed,
+                    &[y],
+                    vec![attr_ints("perm", &[0, 2, 1, 3])],
+                );
+                y = transposed;
+            }
+            let shape = self.int64_initializer(&format!("{}.shape", layer_name), &[0, 0, -1]);
+            let output = if layer + 1 == c.num_layers {
+                name.to_string()
+            } else {
+                layer_name
+            };
+            self.node("Reshape", &output, &[y, shape], vec![]);
+            current = output;
+        }
+        Ok(())
+    }
+}
+
+fn attribute(name: &str, attribute_type: u64) -> Message {
+    let mut attribute = Message::new();
+    attribute.string(1, name).varint(20, attribute_type);
+    attribute
+}
+
+fn attr_int(name: &str, value: i64) -> Message {
+    let mut attribute = attribute(name, ATTR_INT);
+    attribute.int64(3, value);
+    attribute
+}
+
+fn attr_ints(name: &str, values: &[i64]) -> Message {
+    let mut attribute = attribute(name, ATTR_INTS);
+    attribute.packed_int64(8, values);
+    attribute
+}
+
+fn attr_float(name: &str, value: f32) -> Message {
+    let mut attribute = attribute(name, ATTR_FLOAT);
+    attribute.float(2, value);
+    attribute
+}
+
+fn attr_string(name: &str, value: &str) -> Message {
+    let mut attribute = attribute(name, ATTR_STRING);
+    attribute.string(4, value);
+    attribute
+}
+
+fn value_info(name: &str, shape: &[i64]) -> Message {
+    let mut dims = Message::new();
+    shape.iter().for_each(|dim| {
+        let mut dimension = Message::new();
+        dimension.int64(1, *dim);
+        dims.message(1, &dimension);
+    });
+    let mut tensor_type = Message::new();
+    tensor_type.varint(1, FLOAT).message(2, &dims);
+    let mut type_proto = Message::new();
+    type_proto.message(1, &tensor_type);
+    let mut value_info = Message::new();
+    value_info.string(1, name).message(2, &type_proto);
+    value_info
+}
@@ -0,0 +1,336 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use tch::nn::PaddingMode;
+use tch::Reduction;
+
+use crate::components::activation_functions::ActivationFunctionType;
+use crate::components::layers::LayerType;
+use crate::components::NNComponent;
+use crate::model::{node_name, Model};
+use crate::utils::consts::DEFAULT_KERNEL_SIZE;
+use crate::utils::loss_fn::LossFunction;
+use crate::utils::optimizer::OptimizerType;
+
+pub(crate) fn export(model: &Model, path: &Path) -> Result<(), String> {
+    std::fs::write(path, generate(model))
+        .map_err(|e| format!("Error writing the python module: \n{:?}", e))
+}
+
+pub(crate) fn generate(model: &Model) -> String {
+    let hp = &model.hyperparameters;
+    let mut code = String::new();
+    writeln!(code, "# Generated by mlgui {}", env!("CARGO_PKG_VERSION")).unwrap();
+    code.push_str("import torch\nfrom torch import nn\n\n");
+    writeln!(code, "LEARNING_RATE = {:?}", hp.lr).unwrap();
+    writeln!(code, "BATCH_SIZE = {}", hp.batch_size).unwrap();
+    writeln!(code, "EPOCHS = {}", hp.epochs).unwrap();
+    writeln!(code, "INPUT_SHAPE = ({},)\n\n", join(&model.input_shape)).unwrap();
+
+    // module definition, attribute names match the tch variable names
+    code.push_str("class Model(nn.Module):\n    def __init__(self):\n        super().__init__()\n");
+    for (i, component) in model.nodes.iter() {
+        if let NNComponent::Layer {
+            layer_type,
+            observation_space,
+            action_space,
+            ..
+        } = component
+        {
+            writeln!(
+                code,
+                "        self.{} = {}",
+                node_name(*i, component),
+                constructor(layer_type, *observation_space, *action_space)
+            )
+            .unwrap();
+        }
+    }
+    code.push_str("\n    def forward(self, x):\n");
+    for (i, component) in model.nodes.iter() {
+        let name = node_name(*i, component);
+        let step = match component {
+            NNComponent::Layer {
+                layer_type: LayerType::Lstm(_) | LayerType::Gru(_),
+                ..
+            } => format!("self.{}(x)[0]", name),
+            NNComponent::Layer { .. } => format!("self.{}(x)", name),
+            NNComponent::ActivationFunction { fn_type, .. } => match fn_type {
+                ActivationFunctionType::ReLU => "torch.relu(x)".to_string(),
+                ActivationFunctionType::Sigmoid => "torch.sigmoid(x)".to_string(),
+                ActivationFunctionType::Tanh => "torch.tanh(x)".to_string(),
+                ActivationFunctionType::Softmax => "torch.softmax(x, dim=-1)".to_string(),
+                ActivationFunctionType::LeakyReLU => {
+                    "nn.functional.leaky_relu(x, 0.01)".to_string()
+                }
+                ActivationFunctionType::Flatten => "x.view(-1, x.size(1))".to_string(),
+            },
+        };
+        writeln!(code, "        x = {}", step).unwrap();
+    }
+    code.push_str("        return x\n\n\n");
+
+    // loss
+    code.push_str("def compute_loss(output, target):\n");
+    code.push_str(&loss(&hp.loss_fn));
+    code.push_str("\n\n");
+
+    // training script
+    code.push_str("def train(model, inputs, targets, device=\"cpu\"):\n    model.to(device)\n");
+    writeln!(
+        code,
+        "    optimizer = torch.optim.{}(model.parameters(), lr=LEARNING_RATE, {})",
+        hp.optimizer.name(),
+        optimizer(&hp.optimizer)
+    )
+    .unwrap();
+    code.push_str(
+        "    loader = torch.utils.data.DataLoader(\n\
+         \x20       torch.utils.data.TensorDataset(inputs, targets),\n\
+         \x20       batch_size=BATCH_SIZE,\n\
+         \x20       shuffle=True,\n\
+         \x20   )\n\
+         \x20   for epoch in range(1, EPOCHS + 1):\n\
+         \x20       model.train()\n\
+         \x20       total = 0.0\n\
+         \x20       for xs, ys in loader:\n\
+         \x20           xs, ys = xs.to(device), ys.to(device)\n\
+         \x20           optimizer.zero_grad()\n\
+         \x20           loss = compute_loss(model(xs), ys)\n\
+         \x20           loss.backward()\n\
+         \x20           optimizer.step()\n\
+         \x20           total += loss.item()\n\
+         \x20       print(f\"epoch {epoch}: loss {total / len(loader)}\")\n\n\n",
+    );
+
+    // entry point, on placeholder data shaped for the model and its loss
+    code.push_str(
+        "def load_dataset(samples=256):\n\
+         \x20   # replace with the real inputs and targets, the first dimension\n\
+         \x20   # indexes the samples\n\
+         \x20   inputs = torch.randn((samples,) + INPUT_SHAPE[1:])\n\
+         \x20   with torch.no_grad():\n\
+         \x20       outputs = Model().eval()(inputs)\n",
+    );
+    writeln!(
+        code,
+        "    {}\n    return inputs, targets\n\n",
+        targets(&hp.loss_fn)
+    )
+    .unwrap();
+    code.push_str(
+        "if __name__ == \"__main__\":\n\
+         \x20   model = Model()\n\
+         \x20   print(model)\n\
+         \x20   inputs, targets = load_dataset()\n\
+         \x20   train(model, inputs, targets, \"cuda\" if torch.cuda.is_available() else \"cpu\")\n\
+         \x20   torch.save(model.state_dict(), \"model.pt\")\n",
+    );
+    code
+}
+
+// placeholder targets the loss accepts for the outputs
+fn targets(loss_fn: &LossFunction) -> &'static str {
+    match loss_fn {
+        LossFunction::Mse { .. } | LossFunction::Huber { .. } | LossFunction::L1 { .. } => {
+            "targets = torch.randn(outputs.shape)"
+        }
+        LossFunction::Bce { .. } => "targets = torch.rand(outputs.shape)",
+        LossFunction::CrossEntropy { .. } | LossFunction::Nll => {
+            "targets = torch.randint(0, outputs.size(-1), outputs.shape[:-1])"
+        }
+        LossFunction::Ctc { .. } => {
+            "raise NotImplementedError(\"load the sequences and their labels\")"
+        }
+    }
+}
+
+fn constructor(layer_type: &LayerType, o: i64, a: i64) -> String {
+    match layer_type {
+        LayerType::Linear(c) => format!("nn.Linear({}, {}, bias={})", o, a, py_bool(c.bias)),
+        LayerType::Lstm(c) | LayerType::Gru(c) => format!(
+            "nn.{}({}, {}, num_layers={}, bias={}, batch_first={}, dropout={:?}, \
+             bidirectional={})",
+            if matches!(layer_type, LayerType::Lstm(_)) {
+                "LSTM"
+            } else {
+                "GRU"
+            },
+            o,
+            a,
+            c.num_layers,
+            py_bool(c.has_biases),
+            py_bool(c.batch_first),
+            c.dropout,
+            py_bool(c.bidirectional)
+        ),
+        LayerType::BatchNorm1D(c) | LayerType::BatchNorm2D(c) | LayerType::BatchNorm3D(c) => {
+            format!(
+                "nn.{}({}, eps={:?}, momentum={:?}, affine={})",
+                layer_type.replace('D', "d"),
+                o,
+                c.eps,
+                c.momentum,
+                py_bool(c.affine)
+            )
+        }
+        LayerType::Conv1D(c) | LayerType::Conv2D(c) | LayerType::Conv3D(c) => format!(
+            "nn.{}({}, {}, {}, stride={}, padding={}, dilation={}, groups={}, bias={}, \
+             padding_mode=\"{}\")",
+            layer_type.replace('D', "d"),
+            o,
+            a,
+            DEFAULT_KERNEL_SIZE,
+            c.stride,
+            c.padding,
+            c.dilation,
+            c.groups,
+            py_bool(c.bias),
+            match c.padding_mode {
+                PaddingMode::Zeros => "zeros",
+                PaddingMode::Reflect => "reflect",
+                PaddingMode::Replicate => "replicate",
+                PaddingMode::Circular => "circular",
+            }
+        ),
+        LayerType::ConvTranspose1D(c)
+        | LayerType::ConvTranspose2D(c)
+        | LayerType::ConvTranspose3D(c) => format!(
+            "nn.{}({}, {}, {}, stride={}, padding={}, output_padding={}, groups={}, bias={}, \
+             dilation={})",
+            layer_type.replace('D', "d"),
+            o,
+            a,
+            DEFAULT_KERNEL_SIZE,
+            c.stride,
+            c.padding,
+            c.output_padding,
+            c.groups,
+            py_bool(c.bias),
+            c.dilation
+        ),
+    }
+}
+
+fn loss(loss_fn: &LossFunction) -> String {
+    let criterion = match loss_fn {
+        LossFunction::Mse { reduction } => {
+            format!("nn.MSELoss(reduction=\"{}\")", py_reduction(reduction))
+        }
+        LossFunction::CrossEntropy {
+            reduction,
+            smoothing,
+        } => format!(
+            "nn.CrossEntropyLoss(reduction=\"{}\", label_smoothing={:?})",
+            py_reduction(reduction),
+            smoothing
+        ),
+        LossFunction::Bce { reduction } => {
+            format!("nn.BCELoss(reduction=\"{}\")", py_reduction(reduction))
+        }
+        LossFunction::Nll => "nn.NLLLoss()".to_string(),
+        LossFunction::Ctc {
+            reduction,
+            blank,
+            zero_infinity,
+        } => {
+            // output is (steps, batch, classes), every sequence is used in full
+            return format!(
+                "    steps, batch = output.size(0), output.size(1)\n\
+                 \x20   input_lengths = torch.full((batch,), steps, dtype=torch.long)\n\
+                 \x20   target_lengths = torch.full((batch,), target.size(1), dtype=torch.long)\n\
+                 \x20   criterion = nn.CTCLoss(blank={}, reduction=\"{}\", zero_infinity={})\n\
+                 \x20   return criterion(output, target, input_lengths, target_lengths)\n",
+                blank,
+                py_reduction(reduction),
+                py_bool(*zero_infinity)
+            );
+        }
+        LossFunction::Huber { reduction, delta } => format!(
+            "nn.HuberLoss(reduction=\"{}\", delta={:?})",
+            py_reduction(reduction),
+            delta
+        ),
+        LossFunction::L1 { reduction } => {
+            format!("nn.L1Loss(reduction=\"{}\")", py_reduction(reduction))
+        }
+    };
+    format!("    return {}(output, target)\n", criterion)
+}
+
+// keyword arguments of the torch.optim constructor
+fn optimizer(optimizer: &OptimizerType) -> String {
+    match optimizer {
+        OptimizerType::Sgd {
+            momentum,
+            dampening,
+            nesterov,
+            wd,
+        } => format!(
+            "momentum={:?}, dampening={:?}, weight_decay={:?}, nesterov={}",
+            momentum,
+            dampening,
+            wd,
+            py_bool(*nesterov)
+        ),
+        OptimizerType::Adam {
+            beta1,
+            beta2,
+            eps,
+            wd,
+            amsgrad,
+        }
+        | OptimizerType::AdamW {
+            beta1,
+            beta2,
+            eps,
+            wd,
+            amsgrad,
+        } => format!(
+            "betas=({:?}, {:?}), eps={:?}, weight_decay={:?}, amsgrad={}",
+            beta1,
+            beta2,
+            eps,
+            wd,
+            py_bool(*amsgrad)
+        ),
+        OptimizerType::RmsProp {
+            alpha,
+            eps,
+            momentum,
+            centered,
+            wd,
+        } => format!(
+            "alpha={:?}, eps={:?}, weight_decay={:?}, momentum={:?}, centered={}",
+            alpha,
+            eps,
+            wd,
+            momentum,
+            py_bool(*centered)
+        ),
+    }
+}
+
+fn py_bool(value: bool) -> &'static str {
+    if value {
+        "True"
+    } else {
+        "False"
+    }
+}
+
+fn py_reduction(reduction: &Reduction) -> &'static str {
+    match reduction {
+        Reduction::Sum => "sum",
+        Reduction::Mean => "mean",
+        _ => "none",
+    }
+}
+
+fn join(values: &[i64]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,308 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::components::activation_functions::ActivationFunctionType;
+use crate::components::layers::LayerType;
+use crate::components::NNComponent;
+use crate::model::{node_name, Model};
+use crate::utils::consts::DEFAULT_KERNEL_SIZE;
+use crate::utils::loss_fn::LossFunction;
+use crate::utils::optimizer::OptimizerType;
+
+pub(crate) fn export(model: &Model, path: &Path) -> Result<(), String> {
+    std::fs::write(path, generate(model))
+        .map_err(|e| format!("Error writing the rust module: \n{:?}", e))
+}
+
+pub(crate) fn generate(model: &Model) -> String {
+    let hp = &model.hyperparameters;
+    let mut code = String::new();
+    writeln!(
+        code,
+        "// Generated by mlgui {}, input shape {:?}",
+        env!("CARGO_PKG_VERSION"),
+        model.input_shape
+    )
+    .unwrap();
+    code.push_str(
+        "use tch::nn::{self, ModuleT, OptimizerConfig, RNN};\n\
+         use tch::{Kind, Reduction, TchError, Tensor};\n\n",
+    );
+    writeln!(code, "pub const LEARNING_RATE: f64 = {:?};", hp.lr).unwrap();
+    writeln!(code, "pub const BATCH_SIZE: i64 = {};", hp.batch_size).unwrap();
+    writeln!(code, "pub const EPOCHS: usize = {};\n", hp.epochs).unwrap();
+
+    // model definition
+    let layers: Vec<(String, &LayerType, i64, i64)> = model
+        .nodes
+        .iter()
+        .filter_map(|(i, component)| match component {
+            NNComponent::Layer {
+                layer_type,
+                observation_space,
+                action_space,
+                ..
+            } => Some((
+                node_name(*i, component),
+                layer_type,
+                *observation_space,
+                *action_space,
+            )),
+            NNComponent::ActivationFunction { .. } => None,
+        })
+        .collect();
+    code.push_str("#[derive(Debug)]\npub struct Model {\n");
+    for (name, layer_type, ..) in layers.iter() {
+        writeln!(code, "    {}: nn::{},", name, module_type(layer_type)).unwrap();
+    }
+    code.push_str("}\n\nimpl Model {\n    pub fn new(vs: &nn::Path) -> Self {\n        Self {\n");
+    for (name, layer_type, o, a) in layers.iter() {
+        writeln!(
+            code,
+            "            {}: {},",
+            name,
+            constructor(name, layer_type, *o, *a)
+        )
+        .unwrap();
+    }
+    code.push_str("        }\n    }\n}\n\n");
+
+    // forward pass
+    code.push_str(
+        "impl ModuleT for Model {\n    fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {\n",
+    );
+    code.push_str("        let xs = xs.shallow_clone();\n");
+    for (i, component) in model.nodes.iter() {
+        let name = node_name(*i, component);
+        let step = match component {
+            NNComponent::Layer { layer_type, .. } => match layer_type {
+                LayerType::Lstm(_) | LayerType::Gru(_) => format!("self.{}.seq(&xs).0", name),
+                LayerType::BatchNorm1D(_)
+                | LayerType::BatchNorm2D(_)
+                | LayerType::BatchNorm3D(_) => format!("xs.apply_t(&self.{}, train)", name),
+                _ => format!("xs.apply(&self.{})", name),
+            },
+            NNComponent::ActivationFunction { fn_type, .. } => match fn_type {
+                ActivationFunctionType::ReLU => "xs.relu()".to_string(),
+                ActivationFunctionType::Sigmoid => "xs.sigmoid()".to_string(),
+                ActivationFunctionType::Tanh => "xs.tanh()".to_string(),
+                ActivationFunctionType::Softmax => "xs.softmax(-1, Kind::Float)".to_string(),
+                ActivationFunctionType::LeakyReLU => "xs.leaky_relu()".to_string(),
+                ActivationFunctionType::Flatten => "xs.view([-1, xs.size()[1]])".to_string(),
+            },
+        };
+        writeln!(code, "        let xs = {};", step).unwrap();
+    }
+    code.push_str("        xs\n    }\n}\n\n");
+
+    // loss
+    code.push_str("pub fn loss(output: &Tensor, target: &Tensor) -> Tensor {\n");
+    writeln!(code, "    {}", loss(&hp.loss_fn)).unwrap();
+    code.push_str("}\n\n");
+
+    // training loop
+    code.push_str(
+        "pub fn train(\n    vs: &nn::VarStore,\n    model: &Model,\n    inputs: &Tensor,\n    \
+         targets: &Tensor,\n) -> Result<(), TchError> {\n",
+    );
+    writeln!(
+        code,
+        "    let mut opt = {}.build(vs, LEARNING_RATE)?;",
+        optimizer(&hp.optimizer)
+    )
+    .unwrap();
+    code.push_str(
+        "    for epoch in 1..=EPOCHS {\n\
+         \x20       let mut total = 0.0;\n\
+         \x20       let mut batches = 0;\n\
+         \x20       for (xs, ys) in tch::data::Iter2::new(inputs, targets, BATCH_SIZE)\n\
+         \x20           .shuffle()\n\
+         \x20           .to_device(vs.device())\n\
+         \x20       {\n\
+         \x20           let loss = loss(&model.forward_t(&xs, true), &ys);\n\
+         \x20           opt.backward_step(&loss);\n\
+         \x20           total += loss.double_value(&[]);\n\
+         \x20           batches += 1;\n\
+         \x20       }\n\
+         \x20       println!(\"epoch {}: loss {}\", epoch, total / batches as f64);\n\
+         \x20   }\n\
+         \x20   Ok(())\n\
+         }\n",
+    );
+    code
+}
+
+fn module_type(layer_type: &LayerType) -> &'static str {
+    match layer_type {
+        LayerType::Linear(_) => "Linear",
+        LayerType::Lstm(_) => "LSTM",
+        LayerType::Gru(_) => "GRU",
+        LayerType::BatchNorm1D(_) | LayerType::BatchNorm2D(_) | LayerType::BatchNorm3D(_) => {
+            "BatchNorm"
+        }
+        LayerType::Conv1D(_) => "Conv1D",
+        LayerType::Conv2D(_) => "Conv2D",
+        LayerType::Conv3D(_) => "Conv3D",
+        LayerType::ConvTranspose1D(_) => "ConvTranspose1D",
+        LayerType::ConvTranspose2D(_) => "ConvTranspose2D",
+        LayerType::ConvTranspose3D(_) => "ConvTranspose3D",
+    }
+}
+
+// initializers are left to their defaults, they can not be edited from the gui
+fn constructor(name: &str, layer_type: &LayerType, o: i64, a: i64) -> String {
+    let path = format!("vs / \"{}\"", name);
+    match layer_type {
+        LayerType::Linear(c) => format!(
+            "nn::linear({}, {}, {}, nn::LinearConfig {{ bias: {}, ..Default::default() }})",
+            path, o, a, c.bias
+        ),
+        LayerType::Lstm(c) | LayerType::Gru(c) => format!(
+            "nn::{}({}, {}, {}, nn::RNNConfig {{ has_biases: {}, num_layers: {}, dropout: {:?}, \
+             train: {}, bidirectional: {}, batch_first: {}, ..Default::default() }})",
+            if matches!(layer_type, LayerType::Lstm(_)) {
+                "lstm"
+            } else {
+                "gru"
+            },
+            path,
+            o,
+            a,
+            c.has_biases,
+            c.num_layers,
+            c.dropout,
+            c.train,
+            c.bidirectional,
+            c.batch_first
+        ),
+        LayerType::BatchNorm1D(c) | LayerType::BatchNorm2D(c) | LayerType::BatchNorm3D(c) => {
+            format!(
+                "nn::{}({}, {}, nn::BatchNormConfig {{ cudnn_enabled: {}, eps: {:?}, \
+                 momentum: {:?}, affine: {}, ..Default::default() }})",
+                layer_type.to_lowercase().replace("batchnorm", "batch_norm"),
+                path,
+                o,
+                c.cudnn_enabled,
+                c.eps,
+                c.momentum,
+                c.affine
+            )
+        }
+        LayerType::Conv1D(c) | LayerType::Conv2D(c) | LayerType::Conv3D(c) => format!(
+            "nn::{}({}, {}, {}, {}, nn::ConvConfig {{ stride: {}, padding: {}, dilation: {}, \
+             groups: {}, bias: {}, padding_mode: nn::PaddingMode::{:?}, ..Default::default() }})",
+            layer_type.to_lowercase(),
+            path,
+            o,
+            a,
+            DEFAULT_KERNEL_SIZE,
+            c.stride,
+            c.padding,
+            c.dilation,
+            c.groups,
+            c.bias,
+            c.padding_mode
+        ),
+        LayerType::ConvTranspose1D(c)
+        | LayerType::ConvTranspose2D(c)
+        | LayerType::ConvTranspose3D(c) => format!(
+            "nn::{}({}, {}, {}, {}, nn::ConvTransposeConfig {{ stride: {}, padding: {}, \
+             output_padding: {}, groups: {}, bias: {}, dilation: {}, ..Default::default() }})",
+            layer_type
+                .to_lowercase()
+                .replace("convtranspose", "conv_transpose"),
+            path,
+            o,
+            a,
+            DEFAULT_KERNEL_SIZE,
+            c.stride,
+            c.padding,
+            c.output_padding,
+            c.groups,
+            c.bias,
+            c.dilation
+        ),
+    }
+}
+
+fn loss(loss_fn: &LossFunction) -> String {
+    match loss_fn {
+        LossFunction::Mse { reduction } => {
+            format!("output.mse_loss(target, Reduction::{:?})", reduction)
+        }
+        LossFunction::CrossEntropy {
+            reduction,
+            smoothing,
+        } => format!(
+            "output.cross_entropy_loss::<Tensor>(target, None, Reduction::{:?}, -100, {:?})",
+            reduction, smoothing
+        ),
+        LossFunction::Bce { reduction } => format!(
+            "output.binary_cross_entropy::<Tensor>(target, None, Reduction::{:?})",
+            reduction
+        ),
+        LossFunction::Nll => "output.nll_loss(target)".to_string(),
+        LossFunction::Ctc {
+            reduction,
+            blank,
+            zero_infinity,
+        } => format!(
+            "let (steps, batch) = (output.size()[0], output.size()[1]);\n    \
+             let input_lengths = Tensor::full([batch], steps, (Kind::Int64, output.device()));\n    \
+             let target_lengths = Tensor::full([batch], target.size()[1], (Kind::Int64, output.device()));\n    \
+             output.ctc_loss_tensor(target, &input_lengths, &target_lengths, {}, Reduction::{:?}, {})",
+            blank, reduction, zero_infinity
+        ),
+        LossFunction::Huber { reduction, delta } => format!(
+            "output.huber_loss(target, Reduction::{:?}, {:?})",
+            reduction, delta
+        ),
+        LossFunction::L1 { reduction } => {
+            format!("output.l1_loss(target, Reduction::{:?})", reduction)
+        }
+    }
+}
+
+fn optimizer(optimizer: &OptimizerType) -> String {
+    match optimizer {
+        OptimizerType::Sgd {
+            momentum,
+            dampening,
+            nesterov,
+            wd,
+        } => format!(
+            "nn::Sgd {{ momentum: {:?}, dampening: {:?}, wd: {:?}, nesterov: {} }}",
+            momentum, dampening, wd, nesterov
+        ),
+        OptimizerType::Adam {
+            beta1,
+            beta2,
+            eps,
+            wd,
+            amsgrad,
+        } => format!(
+            "nn::Adam {{ beta1: {:?}, beta2: {:?}, wd: {:?}, eps: {:?}, amsgrad: {} }}",
+            beta1, beta2, wd, eps, amsgrad
+        ),
+        OptimizerType::AdamW {
+            beta1,
+            beta2,
+            eps,
+            wd,
+            amsgrad,
+        } => format!(
+            "nn::AdamW {{ beta1: {:?}, beta2: {:?}, wd: {:?}, eps: {:?}, amsgrad: {} }}",
+            beta1, beta2, wd, eps, amsgrad
+        ),
+        OptimizerType::RmsProp {
+            alpha,
+            eps,
+            momentum,
+            centered,
+            wd,
+        } => format!(
+            "nn::RmsProp {{ alpha: {:?}, eps: {:?}, wd: {:?}, momentum: {:?}, centered: {} }}",
+            alpha, eps, wd, momentum, centered
+        ),
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tch::{Device, Kind};
+
+use crate::model::{node_name, Model};
+use crate::project::graph::LayerState;
+use crate::project::Settings;
+
+// nodes in execution order, stored in the metadata to check that a file was
+// saved from the same graph
+#[derive(Serialize)]
+struct NodeDescription {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layer: Option<LayerState>,
+}
+
+pub(crate) fn graph_description(model: &Model) -> String {
+    let nodes = model
+        .nodes
+        .iter()
+        .map(|(i, component)| NodeDescription {
+            name: node_name(*i, component),
+            layer: LayerState::from_component(component),
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string(&nodes).unwrap()
+}
+
+pub(crate) fn metadata(model: &Model) -> HashMap<String, String> {
+    // as in the project files, so that they read back the same way
+    let settings = Settings::from_hyperparameters(&model.hyperparameters, &model.input_shape);
+    HashMap::from([
+        (
+            "mlgui_version".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        ),
+        ("graph".to_string(), graph_description(model)),
+        (
+            "hyperparameters".to_string(),
+            serde_json::to_string(&settings).unwrap(),
+        ),
+    ])
+}
+
+// same layout as the safetensors crate: header size, json header, raw data
+pub(crate) fn save(model: &Model, path: &Path) -> Result<(), String> {
+    let mut variables = model.vs.variables().into_iter().collect::<Vec<_>>();
+    variables.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut header = Map::new();
+    header.insert("__metadata__".to_string(), json!(metadata(model)));
+    let mut data = vec![];
+    for (name, var) in variables {
+        let var = var.to_device(Device::Cpu).contiguous();
+        let dtype = dtype(var.kind())?;
+        let numel = var.numel();
+        let mut bytes = vec![0u8; numel * var.kind().elt_size_in_bytes()];
+        var.f_copy_data_u8(&mut bytes, numel)
+            .map_err(|e| format!("Error reading {}: \n{:?}", name, e))?;
+        header.insert(
+            name,
+            json!({
+                "dtype": dtype,
+                "shape": var.size(),
+                "data_offsets": [data.len(), data.len() + bytes.len()],
+            }),
+        );
+        data.extend(bytes);
+    }
+    let mut header = Value::Object(header).to_string().into_bytes();
+    // the data has to start at an 8 bytes boundary
+    header.resize(header.len().div_ceil(8) * 8, b' ');
+    let mut file = (header.len() as u64).to_le_bytes().to_vec();
+    file.extend(header);
+    file.extend(data);
+    std::fs::write(path, file).map_err(|e| format!("Error writing the weights: \n{:?}", e))
+}
+
+// as the safetensors crate, a larger header is not read
+const MAX_HEADER_SIZE: u64 = 100_000_000;
+
+pub(crate) fn read_metadata(path: &Path) -> Result<HashMap<String, String>, String> {
+    let file = File::open(path).map_err(|e| format!("Error reading the weights: \n{:?}", e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Error reading the weights: \n{:?}", e))?
+        .len();
+    let mut reader = BufReader::new(file);
+    let mut size = [0u8; 8];
+    reader
+        .read_exact(&mut size)
+        .map_err(|e| format!("Error reading the weights: \n{:?}", e))?;
+    let size = u64::from_le_bytes(size);
+    if size > MAX_HEADER_SIZE || size > len.saturating_sub(8) {
+        return Err(format!(
+            "Invalid safetensors header size {} in a file of {} bytes",
+            size, len
+        ));
+    }
+    let mut header = vec![0u8; size as usize];
+    reader
+        .read_exact(&mut header)
+        .map_err(|e| format!("Error reading the weights: \n{:?}", e))?;
+    let mut header: HashMap<String, Value> = serde_json::from_slice(&header)
+        .map_err(|e| format!("Error parsing the safetensors header: \n{}", e))?;
+    match header.remove("__metadata__") {
+        Some(metadata) => serde_json::from_value(metadata)
+            .map_err(|e| format!("Error parsing the safetensors metadata: \n{}", e)),
+        None => Ok(HashMap::new()),
+    }
+}
+
+// none when the file was saved from the same graph
+pub(crate) fn check_graph(model: &Model, path: &Path) -> Result<Option<String>, String> {
+    let metadata = read_metadata(path)?;
+    Ok(match metadata.get("graph") {
+        None => Some("The file has no graph description".to_string()),
+        Some(graph) if *graph != graph_description(model) => Some(format!(
+            "The file was saved from a different graph (mlgui {})",
+            metadata
+                .get("mlgui_version")
+                .map(String::as_str)
+                .unwrap_or("unknown")
+        )),
+        Some(_) => None,
+    })
+}
+
+fn dtype(kind: Kind) -> Result<&'static str, String> {
+    match kind {
+        Kind::Float => Ok("F32"),
+        Kind::Double => Ok("F64"),
+        Kind::Half => Ok("F16"),
+        Kind::BFloat16 => Ok("BF16"),
+        Kind::Int64 => Ok("I64"),
+        Kind::Int => Ok("I32"),
+        Kind::Int16 => Ok("I16"),
+        Kind::Int8 => Ok("I8"),
+        Kind::Uint8 => Ok("U8"),
+        Kind::Bool => Ok("BOOL"),
+        _ => Err(format!("Unsupported tensor kind: {:?}", kind)),
+    }
+}
@@ -0,0 +1,165 @@
+use std::path::Path;
+
+use crate::model::{node_name, Model};
+
+const HEADER: [&str; 6] = [
+    "Node",
+    "Type",
+    "Config",
+    "Output Shape",
+    "Params",
+    "Trainable",
+];
+
+pub(crate) struct SummaryRow {
+    pub(crate) name: String,
+    pub(crate) kind: String,
+    pub(crate) config: String,
+    pub(crate) output_shape: Vec<i64>,
+    pub(crate) params: i64,
+    pub(crate) trainable_params: i64,
+}
+
+// per node overview of a built model, in execution order
+pub(crate) struct Summary {
+    pub(crate) input_shape: Vec<i64>,
+    pub(crate) rows: Vec<SummaryRow>,
+}
+
+impl Summary {
+    pub(crate) fn new(model: &Model) -> Self {
+        let variables = model.vs.variables();
+        let rows = model
+            .nodes
+            .iter()
+            .zip(model.output_shapes.iter())
+            .map(|((i, component), output_shape)| {
+                let name = node_name(*i, component);
+                let prefix = format!("{}.", name);
+                let vars = variables
+                    .iter()
+                    .filter(|(var_name, _)| var_name.starts_with(&prefix))
+                    .map(|(_, var)| var)
+                    .collect::<Vec<_>>();
+                SummaryRow {
+                    kind: component.to_string(),
+                    config: component.config_description(),
+                    output_shape: output_shape.clone(),
+                    params: vars.iter().map(|v| v.numel() as i64).sum(),
+                    trainable_params: vars
+                        .iter()
+                        .filter(|v| v.requires_grad())
+                        .map(|v| v.numel() as i64)
+                        .sum(),
+                    name,
+                }
+            })
+            .collect();
+        Self {
+            input_shape: model.input_shape.clone(),
+            rows,
+        }
+    }
+
+    pub(crate) fn total_params(&self) -> i64 {
+        self.rows.iter().map(|r| r.params).sum()
+    }
+
+    pub(crate) fn trainable_params(&self) -> i64 {
+        self.rows.iter().map(|r| r.trainable_params).sum()
+    }
+
+    fn cells(&self) -> Vec<Vec<String>> {
+        let mut cells = vec![HEADER.iter().map(|h| h.to_string()).collect::<Vec<_>>()];
+        for row in self.rows.iter() {
+            cells.push(vec![
+                row.name.clone(),
+                row.kind.clone(),
+                row.config.clone(),
+                format!("{:?}", row.output_shape),
+                row.params.to_string(),
+                match (row.params, row.trainable_params) {
+                    (0, _) => "-",
+                    (_, 0) => "no",
+                    (p, t) if p == t => "yes",
+                    _ => "partly",
+                }
+                .to_string(),
+            ]);
+        }
+        cells
+    }
+
+    fn totals(&self) -> [String; 4] {
+        let total = self.total_params();
+        let trainable = self.trainable_params();
+        [
+            format!("Input shape: {:?}", self.input_shape),
+            format!("Total params: {}", total),
+            format!("Trainable params: {}", trainable),
+            format!("Non-trainable params: {}", total - trainable),
+        ]
+    }
+
+    // fixed width table for monospace displays
+    pub(crate) fn to_text(&self) -> String {
+        let cells = self.cells();
+        let widths = (0..HEADER.len())
+            .map(|c| cells.iter().map(|r| r[c].len()).max().unwrap())
+            .collect::<Vec<_>>();
+        let line = |row: &Vec<String>| {
+            row.iter()
+                .zip(widths.iter())
+                .map(|(cell, w)| format!("{:<w$}", cell, w = w))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        let separator = "=".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1));
+        let mut text = vec![line(&cells[0]), separator.clone()];
+        text.extend(cells[1..].iter().map(line));
+        text.push(separator);
+        text.extend(self.totals());
+        text.join("\n")
+    }
+
+    pub(crate) fn to_markdown(&self) -> String {
+        let cells = self.cells();
+        let row = |r: &Vec<String>| format!("| {} |", r.join(" | "));
+        let mut md = vec![row(&cells[0]), format!("|{}", "---|".repeat(HEADER.len()))];
+        md.extend(cells[1..].iter().map(row));
+        md.push(String::new());
+        md.extend(self.totals().iter().map(|t| format!("- {}", t)));
+        md.push(String::new());
+        md.join("\n")
+    }
+
+    pub(crate) fn to_csv(&self) -> String {
+        let mut csv = self
+            .cells()
+            .iter()
+            .map(|r| r.iter().map(|c| csv_field(c)).collect::<Vec<_>>().join(","))
+            .collect::<Vec<_>>()
+            .join("\n");
+        csv.push('\n');
+        csv
+    }
+
+    // markdown or csv depending on the extension
+    pub(crate) fn save(&self, path: &Path) -> Result<(), String> {
+        let content = match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => self.to_csv(),
+            _ => self.to_markdown(),
+        };
+        std::fs::write(path, content).map_err(|e| format!("Error writing the summary: \n{:?}", e))
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,32 @@
+use std::path::Path;
+
+use tch::{CModule, Tensor};
+
+use crate::model::Model;
+
+// traces the model with a sample input of the declared shape and checks that
+// the saved module reproduces the original outputs
+pub(crate) fn export(model: &Model, path: &Path) -> Result<(), String> {
+    let input = model.sample_input();
+    let expected = tch::no_grad(|| model.forward(&input));
+    let mut forward = |inputs: &[Tensor]| vec![model.forward(&inputs[0])];
+    let module = CModule::create_by_tracing(
+        "MlGuiModel",
+        "forward",
+        &[input.shallow_clone()],
+        &mut forward,
+    )
+    .map_err(|e| format!("Error tracing the model: \n{:?}", e))?;
+    module
+        .save(path)
+        .map_err(|e| format!("Error saving the module: \n{:?}", e))?;
+
+    let loaded = CModule::load_on_device(path, model.vs.device())
+        .map_err(|e| format!("Error loading the saved module: \n{:?}", e))?;
+    let output = tch::no_grad(|| loaded.forward_ts(&[input]))
+        .map_err(|e| format!("Error running the saved module: \n{:?}", e))?;
+    if output.size() != expected.size() || !output.allclose(&expected, 1e-5, 1e-6, false) {
+        return Err("The traced module does not reproduce the model outputs".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+use tch::Tensor;
+
+use crate::model::{safetensors, Model};
+
+pub(crate) const WEIGHT_EXTENSIONS: [&str; 3] = ["pt", "ot", "safetensors"];
+pub(crate) const SAVE_EXTENSIONS: [&str; 2] = ["pt", "safetensors"];
+
+// differences between the variables of a model and a weights file, matched by
+// name (node name and variable path, e.g. "linear_0.weight")
+#[derive(Debug, Default)]
+pub(crate) struct WeightsReport {
+    pub(crate) loaded: Vec<String>,
+    pub(crate) missing: Vec<String>,
+    pub(crate) unexpected: Vec<String>,
+    // (name, model shape, file shape)
+    pub(crate) mismatched: Vec<(String, Vec<i64>, Vec<i64>)>,
+    // set when the safetensors metadata describes another graph
+    pub(crate) graph_mismatch: Option<String>,
+}
+
+impl WeightsReport {
+    pub(crate) fn is_exact(&self) -> bool {
+        self.missing.is_empty()
+            && self.unexpected.is_empty()
+            && self.mismatched.is_empty()
+            && self.graph_mismatch.is_none()
+    }
+}
+
+impl Display for WeightsReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(graph_mismatch) = self.graph_mismatch.as_ref() {
+            writeln!(f, "{}", graph_mismatch)?;
+        }
+        writeln!(f, "Matching tensors: {}", self.loaded.len())?;
+        if !self.missing.is_empty() {
+            writeln!(f, "Missing in the file: {}", self.missing.join(", "))?;
+        }
+        if !self.unexpected.is_empty() {
+            writeln!(f, "Unexpected in the file: {}", self.unexpected.join(", "))?;
+        }
+        for (name, model_shape, file_shape) in self.mismatched.iter() {
+            writeln!(
+                f,
+                "Shape mismatch for {}: model {:?}, file {:?}",
+                name, model_shape, file_shape
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// safetensors files get the graph and hyperparameters as metadata
+pub(crate) fn save(model: &Model, path: &Path) -> Result<(), String> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("safetensors") => safetensors::save(model, path),
+        _ => model
+            .vs
+            .save(path)
+            .map_err(|e| format!("Error saving the model: \n{:?}", e)),
+    }
+}
+
+// reads a VarStore file (.pt/.ot) or a safetensors file
+pub(crate) fn read(path: &Path) -> Result<HashMap<String, Tensor>, String> {
+    let tensors = match path.extension().and_then(|e| e.to_str()) {
+        Some("safetensors") => Tensor::read_safetensors(path),
+        _ => Tensor::load_multi(path),
+    }
+    .map_err(|e| format!("Error reading the weights: \n{:?}", e))?;
+    Ok(tensors.into_iter().collect())
+}
+
+pub(crate) fn compare(
+    model: &Model,
+    path: &Path,
+    tensors: &HashMap<String, Tensor>,
+) -> Result<WeightsReport, String> {
+    let variables = model.vs.variables();
+    let mut report = WeightsReport::default();
+    if path.extension().is_some_and(|e| e == "safetensors") {
+        report.graph_mismatch = safetensors::check_graph(model, path)?;
+    }
+    for (name, var) in variables.iter() {
+        match tensors.get(name) {
+            None => report.missing.push(name.clone()),
+            Some(t) if t.size() != var.size() => {
+                report.mismatched.push((name.clone(), var.size(), t.size()))
+            }
+            Some(_) => report.loaded.push(name.clone()),
+        }
+    }
+    report.unexpected = tensors
+        .keys()
+        .filter(|name| !variables.contains_key(*name))
+        .cloned()
+        .collect();
+    report.loaded.sort();
+    report.missing.sort();
+    report.unexpected.sort();
+    report.mismatched.sort();
+    Ok(report)
+}
+
+// copies the matching tensors into the model, strict loading fails unless the
+// file matches the model exactly, in which case nothing is copied
+pub(crate) fn load(
+    model: &Model,
+    path: &Path,
+    tensors: &HashMap<String, Tensor>,
+    strict: bool,
+) -> Result<WeightsReport, String> {
+    let report = compare(model, path, tensors)?;
+    if strict && !report.is_exact() {
+        return Err(format!("The weights do not match the model: \n{}", report));
+    }
+    let mut variables = model.vs.variables();
+    tch::no_grad(|| {
+        for name in report.loaded.iter() {
+            variables
+                .get_mut(name)
+                .unwrap()
+                .f_copy_(&tensors[name])
+                .map_err(|e| format!("Error copying {}: \n{:?}", name, e))?;
+        }
+        Ok(report)
+    })
+}
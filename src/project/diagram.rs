@@ -0,0 +1,360 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+
+use fltk::draw::LineStyle;
+use fltk::enums::{Align, Color, Font};
+use fltk::prelude::{ImageExt, SurfaceDevice};
+use fltk::surface::ImageSurface;
+
+use crate::components::NNComponent;
+use crate::project::graph::Graph;
+use crate::utils::png;
+
+const MARGIN: i32 = 20;
+const NODE_MIN_WIDTH: i32 = 160;
+const NODE_HEIGHT: i32 = 48;
+const V_GAP: i32 = 32;
+const H_GAP: i32 = 48;
+// rough width of a character of the config line
+const CHAR_WIDTH: i32 = 7;
+const ARROW_SIZE: f64 = 8.0;
+const INK: u32 = 0x282C34;
+const LAYER_FILL: u32 = 0xDCE6F5;
+const ACTIVATION_FILL: u32 = 0xF5EBDC;
+// a larger scale allocates an image of hundreds of megabytes
+pub(crate) const PNG_SCALE_RANGE: (f64, f64) = (0.25, 8.0);
+
+// the diagram is described once and written either as svg or drawn with fltk
+enum Shape {
+    Node {
+        x: i32,
+        y: i32,
+        w: i32,
+        h: i32,
+        radius: i32,
+        fill: u32,
+        stroke_width: i32,
+    },
+    Edge(Vec<(i32, i32)>),
+    Arrow([(f64, f64); 3]),
+    // centered on x, y
+    Text {
+        x: i32,
+        y: i32,
+        text: String,
+        size: i32,
+        bold: bool,
+    },
+}
+
+struct Diagram {
+    shapes: Vec<Shape>,
+    width: i32,
+    height: i32,
+}
+
+// nodes reachable from the first one go in the main column, in execution
+// order, the others in a second column. The output shapes of a built model
+// are drawn under the configs, by node index
+fn diagram(graph: &Graph, output_shapes: &HashMap<usize, Vec<i64>>) -> Result<Diagram, String> {
+    let node_h = if output_shapes.is_empty() {
+        NODE_HEIGHT
+    } else {
+        NODE_HEIGHT + 16
+    };
+    let shape_texts = output_shapes
+        .iter()
+        .map(|(i, shape)| (*i, format!("→ {:?}", shape)))
+        .collect::<HashMap<_, _>>();
+    let components = graph
+        .nodes
+        .iter()
+        .map(|n| n.to_component())
+        .collect::<Result<Vec<_>, _>>()?;
+    let node_w = components
+        .iter()
+        .map(|c| c.config_description().len() as i32 * CHAR_WIDTH + 2 * MARGIN)
+        .chain(
+            shape_texts
+                .values()
+                .map(|t| t.chars().count() as i32 * CHAR_WIDTH + 2 * MARGIN),
+        )
+        .max()
+        .unwrap_or(0)
+        .max(NODE_MIN_WIDTH);
+
+    let mut chain = vec![];
+    let mut visited = HashSet::new();
+    let mut current = graph.first;
+    while let Some(i) = current.filter(|i| *i < components.len() && visited.insert(*i)) {
+        chain.push(i);
+        current = components[i].next();
+    }
+    let others = (0..components.len()).filter(|i| !visited.contains(i));
+
+    let mut positions = vec![(0, 0); components.len()];
+    let mut rows = [0, 0];
+    for (column, i) in chain
+        .into_iter()
+        .map(|i| (0, i))
+        .chain(others.map(|i| (1, i)))
+    {
+        positions[i] = (
+            MARGIN + column as i32 * (node_w + H_GAP),
+            MARGIN + rows[column] * (node_h + V_GAP),
+        );
+        rows[column] += 1;
+    }
+    let columns = if rows[1] > 0 { 2 } else { 1 };
+
+    // edges under the nodes
+    let mut shapes = vec![];
+    for (i, component) in components.iter().enumerate() {
+        let Some(next) = component.next().filter(|n| *n < components.len()) else {
+            continue;
+        };
+        let (from, to) = (positions[i], positions[next]);
+        let (x1, y1) = (from.0 + node_w / 2, from.1 + node_h);
+        let (x2, y2) = (to.0 + node_w / 2, to.1);
+        let points = if y2 > y1 {
+            vec![(x1, y1), (x2, y2)]
+        } else {
+            // backwards edges go around the side of the nodes
+            let side = from.0.max(to.0) + node_w + H_GAP / 2;
+            vec![
+                (x1, y1),
+                (side, y1 + V_GAP / 2),
+                (side, y2 - V_GAP / 2),
+                (x2, y2),
+            ]
+        };
+        let (px, py) = points[points.len() - 2];
+        let (dx, dy) = ((x2 - px) as f64, (y2 - py) as f64);
+        let len = dx.hypot(dy).max(1.0);
+        let (ux, uy) = (dx / len, dy / len);
+        let (bx, by) = (x2 as f64 - ux * ARROW_SIZE, y2 as f64 - uy * ARROW_SIZE);
+        shapes.push(Shape::Edge(points));
+        shapes.push(Shape::Arrow([
+            (x2 as f64, y2 as f64),
+            (bx - uy * ARROW_SIZE / 2.0, by + ux * ARROW_SIZE / 2.0),
+            (bx + uy * ARROW_SIZE / 2.0, by - ux * ARROW_SIZE / 2.0),
+        ]));
+    }
+
+    for (i, component) in components.iter().enumerate() {
+        let (x, y) = positions[i];
+        // layers are boxes, activation functions are pills
+        let (radius, fill) = match component {
+            NNComponent::Layer { .. } => (6, LAYER_FILL),
+            NNComponent::ActivationFunction { .. } => (node_h / 2, ACTIVATION_FILL),
+        };
+        shapes.push(Shape::Node {
+            x,
+            y,
+            w: node_w,
+            h: node_h,
+            radius,
+            fill,
+            stroke_width: if graph.first == Some(i) { 3 } else { 1 },
+        });
+        let config = component.config_description();
+        let shape = shape_texts.get(&i);
+        let lines = 1 + !config.is_empty() as i32 + shape.is_some() as i32;
+        let cx = x + node_w / 2;
+        let title_y = y + node_h / 2 - 8 * (lines - 1);
+        shapes.push(Shape::Text {
+            x: cx,
+            y: title_y,
+            text: component.to_string(),
+            size: 14,
+            bold: true,
+        });
+        let details = [Some(config).filter(|c| !c.is_empty()), shape.cloned()];
+        for (line, text) in details.into_iter().flatten().enumerate() {
+            shapes.push(Shape::Text {
+                x: cx,
+                y: title_y + 16 * (line as i32 + 1),
+                text,
+                size: 11,
+                bold: false,
+            });
+        }
+    }
+    Ok(Diagram {
+        shapes,
+        width: 2 * MARGIN + columns * node_w + (columns - 1) * H_GAP,
+        height: 2 * MARGIN + rows[0].max(rows[1]).max(1) * (node_h + V_GAP) - V_GAP,
+    })
+}
+
+pub(crate) fn svg(graph: &Graph, shapes: &HashMap<usize, Vec<i64>>) -> Result<String, String> {
+    let diagram = diagram(graph, shapes)?;
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+         viewBox=\"0 0 {w} {h}\" font-family=\"Helvetica, Arial, sans-serif\">",
+        w = diagram.width,
+        h = diagram.height
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>",
+        diagram.width, diagram.height
+    )
+    .unwrap();
+    for shape in diagram.shapes.iter() {
+        match shape {
+            Shape::Node {
+                x,
+                y,
+                w,
+                h,
+                radius,
+                fill,
+                stroke_width,
+            } => writeln!(
+                svg,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"#{:06X}\" \
+                 stroke=\"#{:06X}\" stroke-width=\"{}\"/>",
+                x, y, w, h, radius, fill, INK, stroke_width
+            ),
+            Shape::Edge(points) => writeln!(
+                svg,
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"#{:06X}\" stroke-width=\"1.5\"/>",
+                points
+                    .iter()
+                    .map(|(x, y)| format!("{},{}", x, y))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                INK
+            ),
+            Shape::Arrow(points) => writeln!(
+                svg,
+                "<polygon points=\"{}\" fill=\"#{:06X}\"/>",
+                points
+                    .iter()
+                    .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                INK
+            ),
+            Shape::Text {
+                x,
+                y,
+                text,
+                size,
+                bold,
+            } => writeln!(
+                svg,
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" \
+                 font-size=\"{}\"{} fill=\"#{:06X}\">{}</text>",
+                x,
+                y,
+                size,
+                if *bold { " font-weight=\"bold\"" } else { "" },
+                INK,
+                escape(text)
+            ),
+        }
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+pub(crate) fn export_svg(
+    graph: &Graph,
+    shapes: &HashMap<usize, Vec<i64>>,
+    path: &Path,
+) -> Result<(), String> {
+    std::fs::write(path, svg(graph, shapes)?)
+        .map_err(|e| format!("Error writing the diagram: \n{:?}", e))
+}
+
+// draws the diagram offscreen, scale 1 is one pixel per svg unit
+pub(crate) fn export_png(
+    graph: &Graph,
+    shapes: &HashMap<usize, Vec<i64>>,
+    scale: f64,
+    path: &Path,
+) -> Result<(), String> {
+    let scale = scale.clamp(PNG_SCALE_RANGE.0, PNG_SCALE_RANGE.1);
+    let diagram = diagram(graph, shapes)?;
+    let s = |v: i32| (v as f64 * scale).round() as i32;
+    let (width, height) = (s(diagram.width), s(diagram.height));
+    let surface = ImageSurface::new(width, height, false);
+    ImageSurface::push_current(&surface);
+    fltk::draw::draw_rect_fill(0, 0, width, height, Color::White);
+    for shape in diagram.shapes.iter() {
+        match shape {
+            Shape::Node {
+                x,
+                y,
+                w,
+                h,
+                radius,
+                fill,
+                stroke_width,
+            } => {
+                fltk::draw::set_draw_color(Color::from_hex(*fill));
+                fltk::draw::draw_rounded_rectf(s(*x), s(*y), s(*w), s(*h), s(*radius));
+                fltk::draw::set_draw_color(Color::from_hex(INK));
+                fltk::draw::set_line_style(LineStyle::Solid, s(*stroke_width).max(1));
+                fltk::draw::draw_rounded_rect(s(*x), s(*y), s(*w), s(*h), s(*radius));
+            }
+            Shape::Edge(points) => {
+                fltk::draw::set_draw_color(Color::from_hex(INK));
+                fltk::draw::set_line_style(LineStyle::Solid, s(1).max(1));
+                for segment in points.windows(2) {
+                    let ((x1, y1), (x2, y2)) = (segment[0], segment[1]);
+                    fltk::draw::draw_line(s(x1), s(y1), s(x2), s(y2));
+                }
+            }
+            Shape::Arrow(points) => {
+                let p =
+                    points.map(|(x, y)| ((x * scale).round() as i32, (y * scale).round() as i32));
+                fltk::draw::set_draw_color(Color::from_hex(INK));
+                fltk::draw::draw_polygon(p[0].0, p[0].1, p[1].0, p[1].1, p[2].0, p[2].1);
+            }
+            Shape::Text {
+                x,
+                y,
+                text,
+                size,
+                bold,
+            } => {
+                let font = if *bold {
+                    Font::HelveticaBold
+                } else {
+                    Font::Helvetica
+                };
+                fltk::draw::set_font(font, s(*size));
+                fltk::draw::set_draw_color(Color::from_hex(INK));
+                let (w, h) = fltk::draw::measure(text, false);
+                fltk::draw::draw_text2(text, s(*x) - w / 2, s(*y) - h / 2, w, h, Align::Center);
+            }
+        }
+    }
+    fltk::draw::set_line_style(LineStyle::Solid, 0);
+    ImageSurface::pop_current();
+    let image = surface
+        .image()
+        .ok_or("Error rendering the diagram".to_string())?;
+    let png = png::encode(
+        image.data_w() as u32,
+        image.data_h() as u32,
+        image.depth() as u8,
+        &image.to_rgb_data(),
+    );
+    std::fs::write(path, png).map_err(|e| format!("Error writing the diagram: \n{:?}", e))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,611 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::{Chars, FromStr};
+
+use serde_json::Value;
+
+use crate::components::layers::LayerType;
+use crate::components::NNComponent;
+use crate::model::node_name;
+use crate::project::graph::{Graph, LayerConfig, LayerState, Node};
+use crate::utils::consts::BASE_COMPONENT_HEIGHT;
+
+// gap between imported nodes, dot files carry no playground position
+const IMPORT_SPACING: i32 = BASE_COMPONENT_HEIGHT + 20;
+
+// nodes carry the component type and every config field as attributes, the
+// label and shape are only there for rendering
+pub(crate) fn export(graph: &Graph) -> Result<String, String> {
+    let components = graph
+        .nodes
+        .iter()
+        .map(|n| n.to_component())
+        .collect::<Result<Vec<_>, _>>()?;
+    let ids = components
+        .iter()
+        .enumerate()
+        .map(|(i, c)| node_name(i, c))
+        .collect::<Vec<_>>();
+    let mut dot = String::from("digraph mlgui {\n    rankdir=TB;\n");
+    if let Some(first) = graph.first.and_then(|i| ids.get(i)) {
+        writeln!(dot, "    first={};", quote(first)).unwrap();
+    }
+    for (i, (node, component)) in graph.nodes.iter().zip(components.iter()).enumerate() {
+        let label = format!("{}\n{}", node.kind, component.config_description());
+        let shape = match component {
+            NNComponent::Layer { .. } => "box",
+            NNComponent::ActivationFunction { .. } => "ellipse",
+        };
+        let mut attributes = vec![
+            ("type".to_string(), quote(&node.kind)),
+            ("label".to_string(), quote(label.trim_end())),
+            ("shape".to_string(), shape.to_string()),
+            ("mlgui_x".to_string(), quote(&node.x.to_string())),
+            ("mlgui_y".to_string(), quote(&node.y.to_string())),
+        ];
+        if let Some(layer) = node.layer.as_ref() {
+            attributes.push((
+                "in".to_string(),
+                quote(&layer.observation_space.to_string()),
+            ));
+            attributes.push(("out".to_string(), quote(&layer.action_space.to_string())));
+            attributes.push(("configured".to_string(), layer.configured.to_string()));
+            let Value::Object(fields) = serde_json::to_value(&layer.config).unwrap() else {
+                unreachable!()
+            };
+            for (key, value) in fields.into_iter().filter(|(k, _)| k != "type") {
+                attributes.push((
+                    key,
+                    match value {
+                        Value::String(s) => quote(&s),
+                        other => quote(&other.to_string()),
+                    },
+                ));
+            }
+        }
+        if graph.first == Some(i) {
+            attributes.push(("penwidth".to_string(), "2".to_string()));
+        }
+        writeln!(
+            dot,
+            "    {} [{}];",
+            quote(&ids[i]),
+            attributes
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .unwrap();
+    }
+    for (component, id) in components.iter().zip(ids.iter()) {
+        if let Some(next) = component.next().and_then(|n| ids.get(n)) {
+            writeln!(dot, "    {} -> {};", quote(id), quote(next)).unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    Ok(dot)
+}
+
+pub(crate) fn save(graph: &Graph, path: &Path) -> Result<(), String> {
+    std::fs::write(path, export(graph)?)
+        .map_err(|e| format!("Error writing the dot file: \n{:?}", e))
+}
+
+pub(crate) fn load(path: &Path) -> Result<Graph, String> {
+    let dot = std::fs::read_to_string(path)
+        .map_err(|e| format!("Error reading the dot file: \n{:?}", e))?;
+    import(&dot)
+}
+
+// nodes are numbered in order of appearance, missing config attributes keep
+// the defaults of the layer
+pub(crate) fn import(dot: &str) -> Result<Graph, String> {
+    let parsed = Parser::new(dot).parse()?;
+    let index = |id: &str| parsed.nodes.iter().position(|(n, _)| n == id).unwrap();
+    let mut nexts: Vec<Option<usize>> = vec![None; parsed.nodes.len()];
+    for (from, to) in parsed.edges.iter() {
+        let from_i = index(from);
+        if nexts[from_i].replace(index(to)).is_some() {
+            return Err(format!("{} has more than one outgoing edge", from));
+        }
+    }
+    let mut nodes = vec![];
+    for (i, (id, attributes)) in parsed.nodes.iter().enumerate() {
+        let kind = attributes
+            .get("type")
+            .ok_or(format!("{} has no type attribute", id))?;
+        let position = |key: &str, default: i32| -> Result<i32, String> {
+            attributes.get(key).map_or(Ok(default), |v| {
+                v.parse()
+                    .map_err(|_| format!("Invalid {} of {}: {}", key, id, v))
+            })
+        };
+        let layer = match LayerType::from_str(kind) {
+            Ok(layer_type) => Some(layer_state(id, &layer_type, attributes)?),
+            Err(_) => None,
+        };
+        let node = Node {
+            kind: kind.clone(),
+            x: position("mlgui_x", IMPORT_SPACING)?,
+            y: position("mlgui_y", IMPORT_SPACING * (i as i32 + 1))?,
+            layer,
+            next: nexts[i],
+        };
+        // checks the type of activation functions too
+        node.to_component()?;
+        nodes.push(node);
+    }
+    let first = match parsed.attributes.get("first") {
+        Some(first) => Some(
+            parsed
+                .nodes
+                .iter()
+                .position(|(n, _)| n == first)
+                .ok_or(format!("First node {} does not exist", first))?,
+        ),
+        // the only node without incoming edges
+        None => {
+            let sources = (0..nodes.len())
+                .filter(|i| !nexts.contains(&Some(*i)))
+                .collect::<Vec<_>>();
+            (sources.len() == 1).then(|| sources[0])
+        }
+    };
+    Ok(Graph { nodes, first })
+}
+
+fn layer_state(
+    id: &str,
+    layer_type: &LayerType,
+    attributes: &HashMap<String, String>,
+) -> Result<LayerState, String> {
+    let int = |key: &str| -> Result<i64, String> {
+        attributes
+            .get(key)
+            .ok_or(format!("{} has no {} attribute", id, key))?
+            .parse()
+            .map_err(|_| format!("Invalid {} of {}", key, id))
+    };
+    // the defaults of the layer, overwritten field by field
+    let mut config = serde_json::to_value(LayerConfig::from_layer_type(layer_type)).unwrap();
+    for (key, field) in config.as_object_mut().unwrap().iter_mut() {
+        let Some(value) = attributes.get(key).filter(|_| key != "type") else {
+            continue;
+        };
+        let invalid = || format!("Invalid {} of {}: {}", key, id, value);
+        *field = match field {
+            Value::Bool(_) => Value::Bool(value.parse().map_err(|_| invalid())?),
+            Value::Number(n) if n.is_f64() => {
+                serde_json::json!(value.parse::<f64>().map_err(|_| invalid())?)
+            }
+            Value::Number(_) => serde_json::json!(value.parse::<i64>().map_err(|_| invalid())?),
+            _ => Value::String(value.clone()),
+        };
+    }
+    Ok(LayerState {
+        configured: attributes
+            .get("configured")
+            .map_or(Ok(true), |v| v.parse())
+            .map_err(|_| format!("Invalid configured of {}", id))?,
+        observation_space: int("in")?,
+        action_space: int("out")?,
+        config: serde_json::from_value(config).map_err(|e| format!("{}: {}", id, e))?,
+    })
+}
+
+// identifiers and numerals are written as they are, anything else quoted
+fn quote(id: &str) -> String {
+    let identifier = id
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let digits = id.strip_prefix('-').unwrap_or(id);
+    let numeral = digits.chars().any(|c| c.is_ascii_digit())
+        && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && digits.matches('.').count() <= 1;
+    if identifier || numeral {
+        id.to_string()
+    } else {
+        format!(
+            "\"{}\"",
+            id.replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Id(String),
+    Punct(&'static str),
+}
+
+#[derive(Default)]
+struct ParsedGraph {
+    attributes: HashMap<String, String>,
+    // in order of appearance
+    nodes: Vec<(String, HashMap<String, String>)>,
+    edges: Vec<(String, String)>,
+}
+
+// parser for the subset of the dot language used by graph tools: graph
+// attributes, node statements and edge chains, without subgraphs or ports
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    peeked: Option<Token>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(dot: &'a str) -> Self {
+        Self {
+            chars: dot.chars().peekable(),
+            peeked: None,
+        }
+    }
+
+    fn parse(mut self) -> Result<ParsedGraph, String> {
+        let mut graph = ParsedGraph::default();
+        if self.peek()? == Some(&Token::Id("strict".to_string())) {
+            self.next()?;
+        }
+        match self.next()? {
+            Some(Token::Id(kind)) if kind == "digraph" => {}
+            Some(Token::Id(kind)) if kind == "graph" => {
+                return Err("Only directed graphs are supported".to_string())
+            }
+            _ => return Err("Expected digraph".to_string()),
+        }
+        if let Some(Token::Id(_)) = self.peek()? {
+            self.next()?;
+        }
+        self.expect("{")?;
+        loop {
+            match self.next()? {
+                Some(Token::Punct("}")) => break,
+                Some(Token::Punct(";")) => continue,
+                Some(Token::Id(id)) if id == "subgraph" => {
+                    return Err("Subgraphs are not supported".to_string())
+                }
+                Some(Token::Id(id)) if id == "graph" => {
+                    graph.attributes.extend(self.attributes()?);
+                }
+                // defaults only affect rendering
+                Some(Token::Id(id)) if id == "node" || id == "edge" => {
+                    self.attributes()?;
+                }
+                Some(Token::Id(id)) => {
+                    if self.peek()? == Some(&Token::Punct("=")) {
+                        self.next()?;
+                        let value = self.id()?;
+                        graph.attributes.insert(id, value);
+                        continue;
+                    }
+                    let mut chain = vec![id];
+                    while self.peek()? == Some(&Token::Punct("->")) {
+                        self.next()?;
+                        chain.push(self.id()?);
+                    }
+                    let attributes = self.attributes()?;
+                    for id in chain.iter() {
+                        match graph.nodes.iter_mut().find(|(n, _)| n == id) {
+                            Some((_, existing)) if chain.len() == 1 => {
+                                existing.extend(attributes.clone())
+                            }
+                            Some(_) => {}
+                            None => graph.nodes.push((
+                                id.clone(),
+                                if chain.len() == 1 {
+                                    attributes.clone()
+                                } else {
+                                    HashMap::new()
+                                },
+                            )),
+                        }
+                    }
+                    graph
+                        .edges
+                        .extend(chain.windows(2).map(|e| (e[0].clone(), e[1].clone())));
+                }
+                Some(Token::Punct(p)) => return Err(format!("Unexpected {}", p)),
+                None => return Err("Unexpected end of file".to_string()),
+            }
+        }
+        Ok(graph)
+    }
+
+    // zero or more [k=v, ...] lists
+    fn attributes(&mut self) -> Result<HashMap<String, String>, String> {
+        let mut attributes = HashMap::new();
+        while self.peek()? == Some(&Token::Punct("[")) {
+            self.next()?;
+            loop {
+                match self.next()? {
+                    Some(Token::Punct("]")) => break,
+                    Some(Token::Punct(",")) | Some(Token::Punct(";")) => continue,
+                    Some(Token::Id(key)) => {
+                        self.expect("=")?;
+                        attributes.insert(key, self.id()?);
+                    }
+                    _ => return Err("Invalid attribute list".to_string()),
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn id(&mut self) -> Result<String, String> {
+        match self.next()? {
+            Some(Token::Id(id)) => Ok(id),
+            Some(Token::Punct(p)) => Err(format!("Expected an identifier, found {}", p)),
+            None => Err("Unexpected end of file".to_string()),
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), String> {
+        match self.next()? {
+            Some(Token::Punct(p)) if p == punct => Ok(()),
+            _ => Err(format!("Expected {}", punct)),
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<&Token>, String> {
+        if self.peeked.is_none() {
+            self.peeked = self.token()?;
+        }
+        Ok(self.peeked.as_ref())
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, String> {
+        match self.peeked.take() {
+            Some(token) => Ok(Some(token)),
+            None => self.token(),
+        }
+    }
+
+    fn token(&mut self) -> Result<Option<Token>, String> {
+        self.skip_blank()?;
+        let Some(c) = self.chars.next() else {
+            return Ok(None);
+        };
+        Ok(Some(match c {
+            '{' => Token::Punct("{"),
+            '}' => Token::Punct("}"),
+            '[' => Token::Punct("["),
+            ']' => Token::Punct("]"),
+            '=' => Token::Punct("="),
+            ';' => Token::Punct(";"),
+            ',' => Token::Punct(","),
+            '-' if self.chars.peek() == Some(&'>') => {
+                self.chars.next();
+                Token::Punct("->")
+            }
+            '-' if self.chars.peek() == Some(&'-') => {
+                return Err("Undirected edges are not supported".to_string())
+            }
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match self.chars.next() {
+                            Some('n') => id.push('\n'),
+                            // line continuation
+                            Some('\n') => {}
+                            Some(c) if c == '"' || c == '\\' => id.push(c),
+                            Some(c) => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                            None => return Err("Unterminated string".to_string()),
+                        },
+                        Some(c) => id.push(c),
+                        None => return Err("Unterminated string".to_string()),
+                    }
+                }
+                Token::Id(id)
+            }
+            '<' => return Err("HTML labels are not supported".to_string()),
+            c if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' => {
+                let mut id = c.to_string();
+                while let Some(c) = self
+                    .chars
+                    .next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+                {
+                    id.push(c);
+                }
+                Token::Id(id)
+            }
+            c => return Err(format!("Unexpected character {}", c)),
+        }))
+    }
+
+    // a '/' is only consumed when it starts a comment
+    fn skip_blank(&mut self) -> Result<(), String> {
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some('#') => self.skip_line(),
+                Some('/') => {
+                    let mut lookahead = self.chars.clone();
+                    lookahead.next();
+                    match lookahead.next() {
+                        Some('/') => self.skip_line(),
+                        Some('*') => {
+                            self.chars.nth(1);
+                            let mut prev = ' ';
+                            loop {
+                                match self.chars.next() {
+                                    Some('/') if prev == '*' => break,
+                                    Some(c) => prev = c,
+                                    None => return Err("Unterminated comment".to_string()),
+                                }
+                            }
+                        }
+                        _ => return Err("Unexpected character /".to_string()),
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn skip_line(&mut self) {
+        for c in self.chars.by_ref() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(observation_space: i64, action_space: i64, config: LayerConfig) -> LayerState {
+        LayerState {
+            configured: true,
+            observation_space,
+            action_space,
+            config,
+        }
+    }
+
+    fn node(kind: &str, layer: Option<LayerState>, next: Option<usize>) -> Node {
+        Node {
+            kind: kind.to_string(),
+            x: 40,
+            y: -25,
+            layer,
+            next,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let graph = Graph {
+            nodes: vec![
+                node("ReLU", None, Some(2)),
+                node(
+                    "Linear",
+                    Some(layer(4, 8, LayerConfig::Linear { bias: false })),
+                    Some(0),
+                ),
+                node(
+                    "BatchNorm1D",
+                    Some(layer(
+                        8,
+                        8,
+                        LayerConfig::BatchNorm {
+                            cudnn_enabled: false,
+                            eps: 1e-3,
+                            momentum: 0.2,
+                            affine: true,
+                        },
+                    )),
+                    Some(3),
+                ),
+                node(
+                    "Conv1D",
+                    Some(layer(
+                        8,
+                        16,
+                        LayerConfig::Conv {
+                            stride: 2,
+                            padding: 1,
+                            dilation: 1,
+                            groups: 1,
+                            bias: true,
+                            padding_mode: "Reflect".to_string(),
+                        },
+                    )),
+                    None,
+                ),
+            ],
+            // not the first node of the file
+            first: Some(1),
+        };
+        let imported = import(&export(&graph).unwrap()).unwrap();
+        assert_eq!(imported.first, Some(1));
+        assert_eq!(
+            imported.nodes.iter().map(|n| &n.kind).collect::<Vec<_>>(),
+            vec!["ReLU", "Linear", "BatchNorm1D", "Conv1D"]
+        );
+        assert_eq!(imported, graph);
+    }
+
+    #[test]
+    fn quoted_ids() {
+        assert_eq!(quote("relu_0"), "relu_0");
+        assert_eq!(quote("-1.5"), "-1.5");
+        assert_eq!(quote("a b"), "\"a b\"");
+        assert_eq!(quote("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        let graph = import(
+            r#"digraph "my graph" {
+                "dense \"a\"" [type=Linear, in=2, out=3];
+                "act\\b" [type="ReLU"];
+                "dense \"a\"" -> "act\\b";
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.nodes[0].next, Some(1));
+        assert_eq!(graph.first, Some(0));
+        let layer = graph.nodes[0].layer.as_ref().unwrap();
+        assert_eq!((layer.observation_space, layer.action_space), (2, 3));
+        // missing config attributes keep the defaults
+        assert_eq!(layer.config, LayerConfig::Linear { bias: true });
+    }
+
+    #[test]
+    fn comments() {
+        let graph = import(
+            "// leading comment\n\
+             digraph g { # to the end of the line\n\
+             /* a block\n comment */ a [type=ReLU]; // trailing\n\
+             b [type=Tanh] /* inline */ ;\n\
+             a -> b;\n\
+             }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            graph.nodes.iter().map(|n| &n.kind).collect::<Vec<_>>(),
+            vec!["ReLU", "Tanh"]
+        );
+        assert_eq!(graph.nodes[0].next, Some(1));
+        assert_eq!(
+            import("digraph { a [type=ReLU]; /* never closed }"),
+            Err("Unterminated comment".to_string())
+        );
+    }
+
+    #[test]
+    fn slash_in_unquoted_value() {
+        // a slash that starts no comment is not part of an identifier
+        assert_eq!(
+            import("digraph { a [type=ReLU, label=a/b]; }"),
+            Err("Unexpected character /".to_string())
+        );
+        let graph = import("digraph { a [type=ReLU, label=\"a/b\"]; }").unwrap();
+        assert_eq!(graph.nodes[0].kind, "ReLU");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            import("digraph { a [in=1]; }"),
+            Err("a has no type attribute".to_string())
+        );
+        assert_eq!(
+            import("digraph { a [type=ReLU]; b [type=ReLU]; c [type=ReLU]; a -> b; a -> c; }"),
+            Err("a has more than one outgoing edge".to_string())
+        );
+    }
+}
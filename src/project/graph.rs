@@ -0,0 +1,261 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tch::nn::PaddingMode;
+
+use crate::components::activation_functions::ActivationFunctionType;
+use crate::components::layers::LayerType;
+use crate::components::NNComponent;
+
+// serializable description of the playground graph
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct Graph {
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) first: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Node {
+    pub(crate) kind: String,
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) layer: Option<LayerState>,
+    pub(crate) next: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LayerState {
+    pub(crate) configured: bool,
+    pub(crate) observation_space: i64,
+    pub(crate) action_space: i64,
+    pub(crate) config: LayerConfig,
+}
+
+// the layer configs without the initializers, which can not be edited from the gui
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub(crate) enum LayerConfig {
+    Linear {
+        bias: bool,
+    },
+    Rnn {
+        has_biases: bool,
+        num_layers: i64,
+        dropout: f64,
+        train: bool,
+        bidirectional: bool,
+        batch_first: bool,
+    },
+    BatchNorm {
+        cudnn_enabled: bool,
+        eps: f64,
+        momentum: f64,
+        affine: bool,
+    },
+    Conv {
+        stride: i64,
+        padding: i64,
+        dilation: i64,
+        groups: i64,
+        bias: bool,
+        padding_mode: String,
+    },
+    ConvTranspose {
+        stride: i64,
+        padding: i64,
+        output_padding: i64,
+        groups: i64,
+        bias: bool,
+        dilation: i64,
+    },
+}
+
+impl Node {
+    pub(crate) fn from_component(component: &NNComponent, x: i32, y: i32) -> Self {
+        Self {
+            kind: component.to_string(),
+            x,
+            y,
+            layer: LayerState::from_component(component),
+            next: component.next(),
+        }
+    }
+
+    pub(crate) fn to_component(&self) -> Result<NNComponent, String> {
+        if let Ok(fn_type) = ActivationFunctionType::from_str(&self.kind) {
+            return Ok(NNComponent::ActivationFunction {
+                fn_type,
+                next: self.next,
+            });
+        }
+        let mut layer_type = LayerType::from_str(&self.kind)
+            .map_err(|_| format!("Unknown component: {}", self.kind))?;
+        let layer = self
+            .layer
+            .as_ref()
+            .ok_or(format!("Missing layer state for {}", self.kind))?;
+        layer.config.apply(&mut layer_type)?;
+        Ok(NNComponent::Layer {
+            layer_type,
+            configured: layer.configured,
+            observation_space: layer.observation_space,
+            action_space: layer.action_space,
+            next: self.next,
+        })
+    }
+}
+
+impl LayerState {
+    pub(crate) fn from_component(component: &NNComponent) -> Option<Self> {
+        match component {
+            NNComponent::Layer {
+                layer_type,
+                configured,
+                observation_space,
+                action_space,
+                ..
+            } => Some(LayerState {
+                configured: *configured,
+                observation_space: *observation_space,
+                action_space: *action_space,
+                config: LayerConfig::from_layer_type(layer_type),
+            }),
+            NNComponent::ActivationFunction { .. } => None,
+        }
+    }
+}
+
+impl LayerConfig {
+    pub(crate) fn from_layer_type(layer_type: &LayerType) -> Self {
+        match layer_type {
+            LayerType::Linear(c) => LayerConfig::Linear { bias: c.bias },
+            LayerType::Lstm(c) | LayerType::Gru(c) => LayerConfig::Rnn {
+                has_biases: c.has_biases,
+                num_layers: c.num_layers,
+                dropout: c.dropout,
+                train: c.train,
+                bidirectional: c.bidirectional,
+                batch_first: c.batch_first,
+            },
+            LayerType::BatchNorm1D(c) | LayerType::BatchNorm2D(c) | LayerType::BatchNorm3D(c) => {
+                LayerConfig::BatchNorm {
+                    cudnn_enabled: c.cudnn_enabled,
+                    eps: c.eps,
+                    momentum: c.momentum,
+                    affine: c.affine,
+                }
+            }
+            LayerType::Conv1D(c) | LayerType::Conv2D(c) | LayerType::Conv3D(c) => {
+                LayerConfig::Conv {
+                    stride: c.stride,
+                    padding: c.padding,
+                    dilation: c.dilation,
+                    groups: c.groups,
+                    bias: c.bias,
+                    padding_mode: format!("{:?}", c.padding_mode),
+                }
+            }
+            LayerType::ConvTranspose1D(c)
+            | LayerType::ConvTranspose2D(c)
+            | LayerType::ConvTranspose3D(c) => LayerConfig::ConvTranspose {
+                stride: c.stride,
+                padding: c.padding,
+                output_padding: c.output_padding,
+                groups: c.groups,
+                bias: c.bias,
+                dilation: c.dilation,
+            },
+        }
+    }
+
+    // writes the config over the defaults of a layer of the same family
+    pub(crate) fn apply(&self, layer_type: &mut LayerType) -> Result<(), String> {
+        match (self, layer_type) {
+            (LayerConfig::Linear { bias }, LayerType::Linear(c)) => c.bias = *bias,
+            (
+                LayerConfig::Rnn {
+                    has_biases,
+                    num_layers,
+                    dropout,
+                    train,
+                    bidirectional,
+                    batch_first,
+                },
+                LayerType::Lstm(c) | LayerType::Gru(c),
+            ) => {
+                c.has_biases = *has_biases;
+                c.num_layers = *num_layers;
+                c.dropout = *dropout;
+                c.train = *train;
+                c.bidirectional = *bidirectional;
+                c.batch_first = *batch_first;
+            }
+            (
+                LayerConfig::BatchNorm {
+                    cudnn_enabled,
+                    eps,
+                    momentum,
+                    affine,
+                },
+                LayerType::BatchNorm1D(c) | LayerType::BatchNorm2D(c) | LayerType::BatchNorm3D(c),
+            ) => {
+                c.cudnn_enabled = *cudnn_enabled;
+                c.eps = *eps;
+                c.momentum = *momentum;
+                c.affine = *affine;
+            }
+            (
+                LayerConfig::Conv {
+                    stride,
+                    padding,
+                    dilation,
+                    groups,
+                    bias,
+                    padding_mode,
+                },
+                LayerType::Conv1D(c) | LayerType::Conv2D(c) | LayerType::Conv3D(c),
+            ) => {
+                c.stride = *stride;
+                c.padding = *padding;
+                c.dilation = *dilation;
+                c.groups = *groups;
+                c.bias = *bias;
+                c.padding_mode = match padding_mode.as_str() {
+                    "Zeros" => PaddingMode::Zeros,
+                    "Reflect" => PaddingMode::Reflect,
+                    "Replicate" => PaddingMode::Replicate,
+                    "Circular" => PaddingMode::Circular,
+                    _ => return Err(format!("Invalid padding mode: {}", padding_mode)),
+                };
+            }
+            (
+                LayerConfig::ConvTranspose {
+                    stride,
+                    padding,
+                    output_padding,
+                    groups,
+                    bias,
+                    dilation,
+                },
+                LayerType::ConvTranspose1D(c)
+                | LayerType::ConvTranspose2D(c)
+                | LayerType::ConvTranspose3D(c),
+            ) => {
+                c.stride = *stride;
+                c.padding = *padding;
+                c.output_padding = *output_padding;
+                c.groups = *groups;
+                c.bias = *bias;
+                c.dilation = *dilation;
+            }
+            (config, layer_type) => {
+                return Err(format!(
+                    "Config {:?} does not match the layer {}",
+                    config, **layer_type
+                ))
+            }
+        }
+        Ok(())
+    }
+}
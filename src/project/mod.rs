@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tch::Device;
+
+use crate::model::Hyperparameters;
+use crate::project::graph::Graph;
+use crate::training::early_stopping::EarlyStopping;
+use crate::training::gradients::GradientConfig;
+use crate::training::scheduler::Scheduler;
+use crate::utils::consts::{DEFAULT_BATCH_SIZE, DEFAULT_EPOCHS, DEFAULT_INPUT_SHAPE, DEFAULT_LR};
+use crate::utils::loss_fn::LossFunction;
+use crate::utils::optimizer::{self, OptimizerType};
+
+pub(crate) mod diagram;
+pub(crate) mod dot;
+pub(crate) mod graph;
+pub(crate) mod snapshot;
+
+// bumped whenever the format changes in a way older versions can not read
+pub(crate) const PROJECT_VERSION: u32 = 1;
+pub(crate) const PROJECT_EXTENSION: &str = "mlgui";
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub(crate) struct Project {
+    pub(crate) version: u32,
+    pub(crate) graph: Graph,
+    pub(crate) settings: Settings,
+}
+
+// the values of the config list, unset entries are kept as none
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Settings {
+    pub(crate) save_path: Option<PathBuf>,
+    pub(crate) device: Option<String>,
+    #[serde(deserialize_with = "optimizer::deserialize_setting")]
+    pub(crate) optimizer: Option<OptimizerType>,
+    pub(crate) loss_fn: Option<LossFunction>,
+    pub(crate) lr: f64,
+    // the defaulted entries are missing in projects saved before they were added
+    #[serde(default)]
+    pub(crate) scheduler: Scheduler,
+    pub(crate) batch_size: i64,
+    #[serde(default)]
+    pub(crate) gradients: GradientConfig,
+    pub(crate) epochs: usize,
+    #[serde(default)]
+    pub(crate) early_stopping: EarlyStopping,
+    #[serde(default)]
+    pub(crate) seed: Option<u64>,
+    #[serde(default)]
+    pub(crate) deterministic: bool,
+    pub(crate) input_shape: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            save_path: None,
+            device: None,
+            optimizer: None,
+            loss_fn: None,
+            lr: DEFAULT_LR,
+            scheduler: Scheduler::default(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            gradients: GradientConfig::default(),
+            epochs: DEFAULT_EPOCHS,
+            early_stopping: EarlyStopping::default(),
+            seed: None,
+            deterministic: false,
+            input_shape: DEFAULT_INPUT_SHAPE.to_string(),
+        }
+    }
+}
+
+impl Settings {
+    // the settings a model was built with
+    pub(crate) fn from_hyperparameters(
+        hyperparameters: &Hyperparameters,
+        input_shape: &[i64],
+    ) -> Self {
+        Self {
+            save_path: Some(hyperparameters.save_path.clone()),
+            device: Some(device_name(hyperparameters.device)),
+            optimizer: Some(hyperparameters.optimizer.clone()),
+            loss_fn: Some(hyperparameters.loss_fn.clone()),
+            lr: hyperparameters.lr,
+            scheduler: hyperparameters.scheduler.clone(),
+            batch_size: hyperparameters.batch_size,
+            gradients: hyperparameters.gradients,
+            epochs: hyperparameters.epochs,
+            early_stopping: hyperparameters.early_stopping.clone(),
+            seed: hyperparameters.seed,
+            deterministic: hyperparameters.deterministic,
+            input_shape: input_shape
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        }
+    }
+
+    // fails on the first entry that is not set
+    pub(crate) fn hyperparameters(&self) -> Result<Hyperparameters, String> {
+        Ok(Hyperparameters {
+            save_path: self.save_path.clone().ok_or("Save path not selected")?,
+            device: parse_device(self.device.as_deref().ok_or("Device not selected")?)?,
+            optimizer: self.optimizer.clone().ok_or("Optimizer not selected")?,
+            loss_fn: self.loss_fn.clone().ok_or("Loss function not selected")?,
+            lr: self.lr,
+            scheduler: self.scheduler.clone(),
+            batch_size: self.batch_size,
+            gradients: self.gradients,
+            epochs: self.epochs,
+            early_stopping: self.early_stopping.clone(),
+            seed: self.seed,
+            deterministic: self.deterministic,
+        })
+    }
+}
+
+impl Project {
+    pub(crate) fn new(graph: Graph, settings: Settings) -> Self {
+        Self {
+            version: PROJECT_VERSION,
+            graph,
+            settings,
+        }
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Error serializing the project: \n{:?}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Error writing the project: \n{:?}", e))
+    }
+
+    pub(crate) fn load(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Error reading the project: \n{:?}", e))?;
+        let project: Project = serde_json::from_str(&json)
+            .map_err(|e| format!("Error parsing the project: \n{}", e))?;
+        if project.version > PROJECT_VERSION {
+            return Err(format!(
+                "The project was saved with a newer format (v{}), this version reads up to v{}",
+                project.version, PROJECT_VERSION
+            ));
+        }
+        Ok(project)
+    }
+}
+
+// device names as shown by the device selector
+pub(crate) fn device_name(device: Device) -> String {
+    match device {
+        Device::Cpu => "CPU".to_string(),
+        Device::Cuda(i) => format!("CUDA({})", i),
+        Device::Mps => "MPS".to_string(),
+        Device::Vulkan => "VULKAN".to_string(),
+    }
+}
+
+pub(crate) fn parse_device(name: &str) -> Result<Device, String> {
+    match name {
+        "CPU" => Ok(Device::Cpu),
+        "MPS" => Ok(Device::Mps),
+        "VULKAN" => Ok(Device::Vulkan),
+        _ => name
+            .strip_prefix("CUDA(")
+            .and_then(|i| i.strip_suffix(')'))
+            .and_then(|i| i.parse::<usize>().ok())
+            .map(Device::Cuda)
+            .ok_or(format!("Invalid device: {}", name)),
+    }
+}
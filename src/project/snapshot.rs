@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::node_name;
+use crate::project::Project;
+
+// state of the editor at a successful build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Snapshot {
+    pub(crate) hash: String,
+    // milliseconds since the unix epoch, as the runs
+    pub(crate) timestamp: u64,
+    pub(crate) project: Project,
+}
+
+impl Snapshot {
+    pub(crate) fn title(&self) -> String {
+        format!(
+            "{}  {}  {} nodes",
+            format_timestamp(self.timestamp / 1000),
+            self.hash,
+            self.project.graph.nodes.len()
+        )
+    }
+}
+
+// snapshots of model.pt are kept in model.snapshots
+pub(crate) fn directory(save_path: &Path) -> PathBuf {
+    save_path.with_extension("snapshots")
+}
+
+// nothing is written when the project did not change since the last snapshot
+pub(crate) fn take(project: &Project, save_path: &Path) -> Result<Snapshot, String> {
+    let json = serde_json::to_string(project)
+        .map_err(|e| format!("Error serializing the snapshot: \n{:?}", e))?;
+    let hash = format!("{:016x}", fnv1a(json.as_bytes()));
+    let snapshots = list(save_path)?;
+    if let Some(last) = snapshots.last().filter(|s| s.hash == hash) {
+        return Ok(last.clone());
+    }
+    let snapshot = Snapshot {
+        hash,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        project: project.clone(),
+    };
+    let dir = directory(save_path);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Error creating the snapshot directory: \n{:?}", e))?;
+    let path = dir.join(format!("{}-{}.json", snapshot.timestamp, snapshot.hash));
+    let json = serde_json::to_string_pretty(&snapshot)
+        .map_err(|e| format!("Error serializing the snapshot: \n{:?}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Error writing the snapshot: \n{:?}", e))?;
+    Ok(snapshot)
+}
+
+// later than any timestamp in seconds, earlier than any in milliseconds
+const LEGACY_SECONDS: u64 = 100_000_000_000;
+
+// oldest first, unreadable files are skipped
+pub(crate) fn list(save_path: &Path) -> Result<Vec<Snapshot>, String> {
+    let dir = directory(save_path);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let entries = std::fs::read_dir(&dir)
+        .map_err(|e| format!("Error reading the snapshot directory: \n{:?}", e))?;
+    let mut snapshots = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|e| e == "json"))
+        .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+        .filter_map(|json| serde_json::from_str::<Snapshot>(&json).ok())
+        .map(|mut snapshot| {
+            // taken when the timestamps were in seconds
+            if snapshot.timestamp < LEGACY_SECONDS {
+                snapshot.timestamp *= 1000;
+            }
+            snapshot
+        })
+        .collect::<Vec<_>>();
+    snapshots.sort_by_key(|s| s.timestamp);
+    Ok(snapshots)
+}
+
+// added and removed nodes, then every changed field, nodes are matched by name
+pub(crate) fn diff(old: &Project, new: &Project) -> Vec<String> {
+    let nodes = |project: &Project| {
+        project
+            .graph
+            .nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, node)| {
+                let name = node_name(i, &node.to_component().ok()?);
+                Some((name, flatten(&serde_json::to_value(node).ok()?)))
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+    let (old_nodes, new_nodes) = (nodes(old), nodes(new));
+    let mut changes = vec![];
+    for name in new_nodes.keys().filter(|n| !old_nodes.contains_key(*n)) {
+        changes.push(format!("+ {}", name));
+    }
+    for name in old_nodes.keys().filter(|n| !new_nodes.contains_key(*n)) {
+        changes.push(format!("- {}", name));
+    }
+    for (name, new_fields) in new_nodes.iter() {
+        if let Some(old_fields) = old_nodes.get(name) {
+            changes.extend(
+                changed(old_fields, new_fields)
+                    .into_iter()
+                    .filter(|c| !c.starts_with("x:") && !c.starts_with("y:"))
+                    .map(|c| format!("~ {}.{}", name, c)),
+            );
+        }
+    }
+    if old.graph.first != new.graph.first {
+        changes.push(format!(
+            "~ first: {:?} -> {:?}",
+            old.graph.first, new.graph.first
+        ));
+    }
+    let settings = |project: &Project| flatten(&serde_json::to_value(&project.settings).unwrap());
+    changes.extend(
+        changed(&settings(old), &settings(new))
+            .into_iter()
+            .map(|c| format!("~ settings.{}", c)),
+    );
+    changes
+}
+
+// "key: old -> new" for every differing leaf
+fn changed(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> Vec<String> {
+    let mut keys = old.keys().chain(new.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    let none = "none".to_string();
+    keys.into_iter()
+        .filter(|k| old.get(*k) != new.get(*k))
+        .map(|k| {
+            format!(
+                "{}: {} -> {}",
+                k,
+                old.get(k).unwrap_or(&none),
+                new.get(k).unwrap_or(&none)
+            )
+        })
+        .collect()
+}
+
+// leaves of a json value by dotted path
+fn flatten(value: &Value) -> BTreeMap<String, String> {
+    fn walk(prefix: &str, value: &Value, out: &mut BTreeMap<String, String>) {
+        match value {
+            Value::Object(fields) => {
+                for (key, value) in fields {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&path, value, out);
+                }
+            }
+            Value::Null => {}
+            other => {
+                out.insert(prefix.to_string(), other.to_string());
+            }
+        }
+    }
+    let mut out = BTreeMap::new();
+    walk("", value, &mut out);
+    out
+}
+
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+// utc, yyyy-mm-dd hh:mm:ss
+pub(crate) fn format_timestamp(timestamp: u64) -> String {
+    let (days, secs) = ((timestamp / 86400) as i64, timestamp % 86400);
+    // civil from days, from Howard Hinnant's date algorithms
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
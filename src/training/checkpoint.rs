@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{parse_shape, weights, Model};
+use crate::project::graph::Graph;
+use crate::project::Settings;
+use crate::training::early_stopping::EarlyStoppingState;
+use crate::training::metrics::ValidationConfig;
+use crate::training::optimizer::Optimizer;
+use crate::training::scheduler::PlateauState;
+use crate::training::tensorboard::TensorBoardConfig;
+use crate::training::Job;
+
+pub(crate) const CHECKPOINT_EXTENSION: &str = "json";
+pub(crate) const BEST: &str = "best";
+pub(crate) const LAST: &str = "last";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CheckpointConfig {
+    // 0 disables the periodic checkpoints
+    pub(crate) every_epochs: usize,
+    pub(crate) every_minutes: u64,
+    // kept on the best value of the early stopping monitor, or on the lowest
+    // validation loss, or training loss without validation, when it is disabled
+    pub(crate) keep_best: bool,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            every_epochs: 10,
+            every_minutes: 0,
+            keep_best: true,
+        }
+    }
+}
+
+// the moment buffers are stored next to the variables, what is kept here is
+// the configuration and the number of steps taken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OptimizerState {
+    pub(crate) optimizer: String,
+    // the learning rate of the last step
+    pub(crate) lr: f64,
+    pub(crate) steps: usize,
+    #[serde(default)]
+    pub(crate) plateau: PlateauState,
+}
+
+// everything needed to continue a run, the variables are stored next to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Checkpoint {
+    pub(crate) graph: Graph,
+    // the hyperparameters and input shape of the run
+    pub(crate) settings: Settings,
+    pub(crate) dataset: PathBuf,
+    pub(crate) optimizer: OptimizerState,
+    // the run continues at this epoch, after its first `step` batches
+    pub(crate) epoch: usize,
+    pub(crate) step: usize,
+    // the torch generator is reseeded from it, the epoch and the step before
+    // the shuffling of every epoch and before every step. That is its whole
+    // state, a resumed run shuffles and draws dropout masks as the first did
+    pub(crate) seed: u64,
+    // the weights file the run started from, a new initialization when none
+    #[serde(default)]
+    pub(crate) pretrained: Option<PathBuf>,
+    pub(crate) best: Option<f64>,
+    pub(crate) checkpoints: CheckpointConfig,
+    #[serde(default)]
+    pub(crate) early_stopping: EarlyStoppingState,
+    #[serde(default)]
+    pub(crate) validation: ValidationConfig,
+    // the directory of the run, a resumed run keeps logging to it
+    #[serde(default)]
+    pub(crate) tensorboard: TensorBoardConfig,
+}
+
+impl Checkpoint {
+    // checkpoints of model.pt are kept in model.checkpoints
+    pub(crate) fn directory(save_path: &Path) -> PathBuf {
+        save_path.with_extension("checkpoints")
+    }
+
+    fn weights_path(path: &Path) -> PathBuf {
+        path.with_extension("pt")
+    }
+
+    fn optimizer_path(path: &Path) -> PathBuf {
+        path.with_extension("optimizer.pt")
+    }
+
+    // writes name.json, name.pt and name.optimizer.pt in the checkpoint
+    // directory
+    pub(crate) fn save(
+        &self,
+        model: &Model,
+        optimizer: &Optimizer,
+        name: &str,
+    ) -> Result<PathBuf, String> {
+        let dir = Self::directory(&model.hyperparameters.save_path);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Error creating the checkpoint directory: \n{:?}", e))?;
+        let path = dir.join(name).with_extension(CHECKPOINT_EXTENSION);
+        model
+            .vs
+            .save(Self::weights_path(&path))
+            .map_err(|e| format!("Error saving the checkpoint weights: \n{:?}", e))?;
+        optimizer.save(&Self::optimizer_path(&path))?;
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Error serializing the checkpoint: \n{:?}", e))?;
+        std::fs::write(&path, json)
+            .map_err(|e| format!("Error writing the checkpoint: \n{:?}", e))?;
+        Ok(path)
+    }
+
+    pub(crate) fn load(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Error reading the checkpoint: \n{:?}", e))?;
+        serde_json::from_str(&json).map_err(|e| format!("Error parsing the checkpoint: \n{}", e))
+    }
+
+    // a job continuing the run, independent of the editor
+    pub(crate) fn job(self, path: &Path) -> Result<Job, String> {
+        Ok(Job {
+            graph: self.graph.clone(),
+            hyperparameters: self.settings.hyperparameters()?,
+            input_shape: parse_shape(&self.settings.input_shape)?,
+            weights: Some(weights::read(&Self::weights_path(path))?),
+            pretrained: self.pretrained.clone(),
+            // checkpoints written before the buffers were kept start from
+            // zero moments
+            optimizer: Self::optimizer_path(path)
+                .exists()
+                .then(|| Optimizer::read(&Self::optimizer_path(path)))
+                .transpose()?,
+            dataset: self.dataset.clone(),
+            checkpoints: self.checkpoints,
+            validation: self.validation.clone(),
+            tensorboard: self.tensorboard.clone(),
+            resume: Some(self),
+        })
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::{Arc, Condvar, Mutex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Command {
+    #[default]
+    Run,
+    Pause,
+    Stop,
+}
+
+// shared between the ui and the worker, the worker checks it before every step
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Control(Arc<(Mutex<Command>, Condvar)>);
+
+impl Control {
+    pub(crate) fn set(&self, command: Command) {
+        let (lock, condvar) = &*self.0;
+        *lock.lock().unwrap() = command;
+        condvar.notify_all();
+    }
+
+    pub(crate) fn get(&self) -> Command {
+        *self.0 .0.lock().unwrap()
+    }
+
+    // blocks without spinning while paused, true when the run has to stop
+    pub(crate) fn should_stop(&self) -> bool {
+        let (lock, condvar) = &*self.0;
+        let command = condvar
+            .wait_while(lock.lock().unwrap(), |c| *c == Command::Pause)
+            .unwrap();
+        *command == Command::Stop
+    }
+}
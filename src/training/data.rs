@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use tch::data::Iter2;
+use tch::{Device, Kind, Tensor};
+
+pub(crate) const DATASET_EXTENSIONS: [&str; 3] = ["pt", "ot", "safetensors"];
+const INPUTS: &str = "inputs";
+const TARGETS: &str = "targets";
+
+// samples along the first dimension of both tensors
+pub(crate) struct Dataset {
+    pub(crate) inputs: Tensor,
+    pub(crate) targets: Tensor,
+}
+
+impl Dataset {
+    // a tensor file with an "inputs" and a "targets" tensor
+    pub(crate) fn load(path: &Path, target_kind: Kind) -> Result<Self, String> {
+        let tensors = match path.extension().and_then(|e| e.to_str()) {
+            Some("safetensors") => Tensor::read_safetensors(path),
+            _ => Tensor::load_multi(path),
+        }
+        .map_err(|e| format!("Error reading the dataset: \n{:?}", e))?;
+        let mut tensors = tensors.into_iter().collect::<HashMap<_, _>>();
+        let mut take = |name: &str| {
+            tensors
+                .remove(name)
+                .ok_or(format!("The dataset has no \"{}\" tensor", name))
+        };
+        let (inputs, targets) = (take(INPUTS)?, take(TARGETS)?);
+        if inputs.dim() == 0 || targets.dim() == 0 || inputs.size()[0] != targets.size()[0] {
+            return Err(format!(
+                "Inputs {:?} and targets {:?} must have the same number of samples",
+                inputs.size(),
+                targets.size()
+            ));
+        }
+        Ok(Self {
+            inputs: inputs.to_kind(Kind::Float),
+            targets: targets.to_kind(target_kind),
+        })
+    }
+
+    pub(crate) fn samples(&self) -> i64 {
+        self.inputs.size()[0]
+    }
+
+    // the input shape of the model has the batch size as first dimension
+    pub(crate) fn check(&self, input_shape: &[i64]) -> Result<(), String> {
+        let sample = &self.inputs.size()[1..];
+        if sample != &input_shape[1.min(input_shape.len())..] {
+            return Err(format!(
+                "Dataset samples {:?} do not match the input shape {:?}",
+                sample, input_shape
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn batches(&self, batch_size: i64, device: Device) -> Iter2 {
+        let mut batches = Iter2::new(&self.inputs, &self.targets, batch_size);
+        batches
+            .shuffle()
+            .to_device(device)
+            .return_smaller_last_batch();
+        batches
+    }
+
+    // in the stored order, for the evaluation
+    pub(crate) fn ordered_batches(&self, batch_size: i64, device: Device) -> Iter2 {
+        let mut batches = Iter2::new(&self.inputs, &self.targets, batch_size);
+        batches.to_device(device).return_smaller_last_batch();
+        batches
+    }
+
+    // holds out a fraction of the samples, the same ones for the same seed
+    pub(crate) fn split(&self, fraction: f64, seed: u64) -> Result<(Self, Self), String> {
+        let samples = self.samples();
+        let held = (samples as f64 * fraction).round() as i64;
+        if held < 1 || held >= samples {
+            return Err(format!(
+                "A validation fraction of {} leaves no samples to train or validate on",
+                fraction
+            ));
+        }
+        tch::manual_seed(seed as i64);
+        let order = Tensor::randperm(samples, (Kind::Int64, Device::Cpu));
+        let take = |from: i64, length: i64| {
+            let indices = order.narrow(0, from, length);
+            Self {
+                inputs: self.inputs.index_select(0, &indices),
+                targets: self.targets.index_select(0, &indices),
+            }
+        };
+        Ok((take(held, samples - held), take(0, held)))
+    }
+
+    pub(crate) fn steps(&self, batch_size: i64) -> usize {
+        ((self.samples() + batch_size - 1) / batch_size) as usize
+    }
+}
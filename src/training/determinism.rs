@@ -0,0 +1,27 @@
+// libtorch settings of a deterministic run: one thread, so that reductions and
+// the data order do not depend on the scheduling, and no cudnn, whose fastest
+// kernels are not deterministic. they are global and restored when dropped
+pub(crate) struct Deterministic {
+    threads: i32,
+    cudnn: bool,
+}
+
+impl Deterministic {
+    pub(crate) fn enable() -> Self {
+        let previous = Self {
+            threads: tch::get_num_threads(),
+            cudnn: tch::Cuda::user_enabled_cudnn(),
+        };
+        tch::set_num_threads(1);
+        tch::Cuda::cudnn_set_benchmark(false);
+        tch::Cuda::set_user_enabled_cudnn(false);
+        previous
+    }
+}
+
+impl Drop for Deterministic {
+    fn drop(&mut self) {
+        tch::set_num_threads(self.threads);
+        tch::Cuda::set_user_enabled_cudnn(self.cudnn);
+    }
+}
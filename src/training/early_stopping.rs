@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tch::nn::VarStore;
+use tch::Tensor;
+
+use crate::training::metrics::METRICS;
+
+pub(crate) const MODES: [&str; 2] = ["min", "max"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct EarlyStopping {
+    pub(crate) enabled: bool,
+    pub(crate) monitor: String,
+    // "min" when lower values are better
+    pub(crate) mode: String,
+    // epochs without improvement before stopping
+    pub(crate) patience: usize,
+    // smaller changes do not count as an improvement
+    pub(crate) min_delta: f64,
+    pub(crate) restore_best: bool,
+}
+
+impl Default for EarlyStopping {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            monitor: METRICS[0].to_string(),
+            mode: MODES[0].to_string(),
+            patience: 10,
+            min_delta: 0.0,
+            restore_best: true,
+        }
+    }
+}
+
+impl EarlyStopping {
+    // shown on the config list entry
+    pub(crate) fn summary(&self) -> String {
+        if self.enabled {
+            format!("{} ({}), {} epochs", self.monitor, self.mode, self.patience)
+        } else {
+            "Disabled".to_string()
+        }
+    }
+
+    fn improves(&self, value: f64, best: f64) -> bool {
+        if self.mode == "max" {
+            value > best + self.min_delta
+        } else {
+            value < best - self.min_delta
+        }
+    }
+}
+
+// where the monitored value stands, kept in the checkpoints so that a
+// resumed run keeps counting
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct EarlyStoppingState {
+    pub(crate) best: Option<f64>,
+    pub(crate) best_epoch: usize,
+    pub(crate) wait: usize,
+}
+
+pub(crate) struct EarlyStopper {
+    config: EarlyStopping,
+    state: EarlyStoppingState,
+    // the variables at the best epoch, only since the run was (re)started
+    best_weights: Option<HashMap<String, Tensor>>,
+}
+
+impl EarlyStopper {
+    pub(crate) fn new(config: EarlyStopping, state: EarlyStoppingState) -> Self {
+        Self {
+            config,
+            state,
+            best_weights: None,
+        }
+    }
+
+    pub(crate) fn state(&self) -> EarlyStoppingState {
+        self.state.clone()
+    }
+
+    pub(crate) fn monitor(&self) -> &str {
+        &self.config.monitor
+    }
+
+    // records the value of an epoch, the reason to stop is returned once the
+    // patience runs out
+    pub(crate) fn update(&mut self, epoch: usize, value: f64, vs: &VarStore) -> Option<String> {
+        if !self.config.enabled {
+            return None;
+        }
+        if self
+            .state
+            .best
+            .is_none_or(|best| self.config.improves(value, best))
+        {
+            self.state = EarlyStoppingState {
+                best: Some(value),
+                best_epoch: epoch,
+                wait: 0,
+            };
+            if self.config.restore_best {
+                self.best_weights = Some(
+                    vs.variables()
+                        .into_iter()
+                        .map(|(name, var)| (name, var.detach().copy()))
+                        .collect(),
+                );
+            }
+            return None;
+        }
+        self.state.wait += 1;
+        if self.state.wait < self.config.patience {
+            return None;
+        }
+        Some(format!(
+            "Early stopping at epoch {}: {} did not improve by more than {} for {} epochs, best {:.6} at epoch {}",
+            epoch,
+            self.config.monitor,
+            self.config.min_delta,
+            self.state.wait,
+            self.state.best.unwrap_or(value),
+            self.state.best_epoch
+        ))
+    }
+
+    // copies back the best variables, false when they are not in memory
+    pub(crate) fn restore(&self, vs: &VarStore) -> Result<bool, String> {
+        match (&self.best_weights, self.config.restore_best) {
+            (Some(weights), true) => {
+                super::copy_weights(vs, weights)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use crate::training::optimizer::Optimizer;
+
+pub(crate) const CLIPPING_MODES: [&str; 3] = ["None", "Global norm", "Value"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Clipping {
+    #[default]
+    None,
+    // rescales all the gradients together when their norm is above it
+    Norm(f64),
+    // clamps every gradient to [-value, value]
+    Value(f64),
+}
+
+impl Clipping {
+    pub(crate) fn index(&self) -> usize {
+        match self {
+            Clipping::None => 0,
+            Clipping::Norm(_) => 1,
+            Clipping::Value(_) => 2,
+        }
+    }
+
+    pub(crate) fn threshold(&self) -> f64 {
+        match self {
+            Clipping::None => 1.0,
+            Clipping::Norm(threshold) | Clipping::Value(threshold) => *threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct GradientConfig {
+    pub(crate) clipping: Clipping,
+    // micro-batches whose gradients are averaged before an optimizer step
+    pub(crate) accumulation: usize,
+}
+
+impl Default for GradientConfig {
+    fn default() -> Self {
+        Self {
+            clipping: Clipping::None,
+            accumulation: 1,
+        }
+    }
+}
+
+impl GradientConfig {
+    // shown on the config list entry
+    pub(crate) fn summary(&self) -> String {
+        let clipping = match self.clipping {
+            Clipping::None => "No clipping".to_string(),
+            Clipping::Norm(max) => format!("Norm {}", max),
+            Clipping::Value(max) => format!("Value {}", max),
+        };
+        if self.accumulation > 1 {
+            format!("{}, {} batches", clipping, self.accumulation)
+        } else {
+            clipping
+        }
+    }
+
+    pub(crate) fn effective_batch_size(&self, batch_size: i64) -> i64 {
+        batch_size * self.accumulation.max(1) as i64
+    }
+
+    // clips the accumulated gradients, steps and clears them
+    pub(crate) fn step(&self, optimizer: &mut Optimizer) {
+        match self.clipping {
+            Clipping::None => {}
+            Clipping::Norm(max) => optimizer.clip_grad_norm(max),
+            Clipping::Value(max) => optimizer.clip_grad_value(max),
+        }
+        optimizer.step();
+        optimizer.zero_grad();
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tch::{Kind, Tensor};
+
+use crate::model::Model;
+use crate::training::data::Dataset;
+
+pub(crate) const TASKS: [&str; 2] = ["Classification", "Regression"];
+pub(crate) const VAL_LOSS: &str = "val_loss";
+// every metric a run can report, the training loss first
+pub(crate) const METRICS: [&str; 13] = [
+    "loss",
+    VAL_LOSS,
+    "accuracy",
+    "top_k_accuracy",
+    "precision_macro",
+    "recall_macro",
+    "f1_macro",
+    "precision_micro",
+    "recall_micro",
+    "f1_micro",
+    "mae",
+    "rmse",
+    "r2",
+];
+
+pub(crate) const TRAIN_PREFIX: &str = "train_";
+
+// losses and errors, the other metrics are better when higher, on the training
+// set as on the validation set
+pub(crate) fn lower_is_better(metric: &str) -> bool {
+    matches!(
+        metric.strip_prefix(TRAIN_PREFIX).unwrap_or(metric),
+        "loss" | VAL_LOSS | "mae" | "rmse"
+    )
+}
+
+// whether a run validated this way reports the metric every epoch
+pub(crate) fn computed(metric: &str, validation: &ValidationConfig) -> bool {
+    let validated = validation.fraction > 0.0 || validation.dataset.is_some();
+    match metric {
+        "loss" => true,
+        VAL_LOSS => validated,
+        "mae" | "rmse" | "r2" => validated && validation.task == Task::Regression,
+        _ => validated && validation.task == Task::Classification,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Task {
+    // outputs are class scores, targets class indices
+    #[default]
+    Classification,
+    // outputs and targets have the same shape
+    Regression,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ValidationConfig {
+    // held out of the training set, 0 disables the validation
+    pub(crate) fraction: f64,
+    // used instead of the fraction when set
+    pub(crate) dataset: Option<PathBuf>,
+    pub(crate) task: Task,
+    pub(crate) top_k: i64,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            fraction: 0.0,
+            dataset: None,
+            task: Task::Classification,
+            top_k: 5,
+        }
+    }
+}
+
+// loss and metrics of the model on the validation set, in eval mode and
+// without gradients
+pub(crate) fn evaluate(
+    model: &Model,
+    dataset: &Dataset,
+    config: &ValidationConfig,
+) -> Result<BTreeMap<String, f64>, String> {
+    let hp = &model.hyperparameters;
+    let (outputs, targets) = tch::no_grad(|| {
+        let (mut outputs, mut targets) = (vec![], vec![]);
+        for (xs, ys) in dataset.ordered_batches(hp.batch_size, hp.device) {
+            outputs.push(model.forward_t(&xs, false));
+            targets.push(ys);
+        }
+        (Tensor::cat(&outputs, 0), Tensor::cat(&targets, 0))
+    });
+    let mut metrics = BTreeMap::from([(
+        VAL_LOSS.to_string(),
+        tch::no_grad(|| hp.loss_fn.loss(&outputs, &targets))?.double_value(&[]),
+    )]);
+    task_metrics(&outputs, &targets, config, &mut metrics)?;
+    Ok(metrics)
+}
+
+// the metrics of the task on the outputs of an epoch, prefixed with train_
+pub(crate) fn training(
+    outputs: &[Tensor],
+    targets: &[Tensor],
+    config: &ValidationConfig,
+) -> Result<BTreeMap<String, f64>, String> {
+    let mut metrics = BTreeMap::new();
+    tch::no_grad(|| {
+        task_metrics(
+            &Tensor::cat(outputs, 0),
+            &Tensor::cat(targets, 0),
+            config,
+            &mut metrics,
+        )
+    })?;
+    Ok(metrics
+        .into_iter()
+        .map(|(name, value)| (format!("{}{}", TRAIN_PREFIX, name), value))
+        .collect())
+}
+
+fn task_metrics(
+    outputs: &Tensor,
+    targets: &Tensor,
+    config: &ValidationConfig,
+    metrics: &mut BTreeMap<String, f64>,
+) -> Result<(), String> {
+    match config.task {
+        Task::Classification => classification(outputs, targets, config.top_k, metrics),
+        Task::Regression => regression(outputs, targets, metrics),
+    }
+}
+
+fn classification(
+    outputs: &Tensor,
+    targets: &Tensor,
+    top_k: i64,
+    metrics: &mut BTreeMap<String, f64>,
+) -> Result<(), String> {
+    if outputs.dim() != 2 || targets.dim() != 1 {
+        return Err(format!(
+            "Classification metrics need [samples, classes] outputs and [samples] targets, not {:?} and {:?}",
+            outputs.size(),
+            targets.size()
+        ));
+    }
+    let classes = outputs.size()[1];
+    let targets = targets.to_kind(Kind::Int64);
+    let predictions = outputs.argmax(-1, false);
+    let mean = |t: &Tensor| t.to_kind(Kind::Double).mean(Kind::Double).double_value(&[]);
+    metrics.insert("accuracy".into(), mean(&predictions.eq_tensor(&targets)));
+    let (_, top) = outputs.topk(top_k.clamp(1, classes), -1, true, true);
+    let hits = top.eq_tensor(&targets.unsqueeze(-1)).any_dim(-1, false);
+    metrics.insert("top_k_accuracy".into(), mean(&hits));
+
+    // per class counts of true positives, false positives and false negatives
+    let count = |t: Tensor| t.sum(Kind::Double).double_value(&[]);
+    let (mut tp, mut fp, mut fn_) = (vec![], vec![], vec![]);
+    for class in 0..classes {
+        let predicted = predictions.eq(class);
+        let actual = targets.eq(class);
+        tp.push(count(predicted.logical_and(&actual)));
+        fp.push(count(predicted.logical_and(&actual.logical_not())));
+        fn_.push(count(predicted.logical_not().logical_and(&actual)));
+    }
+    let ratio = |a: f64, b: f64| if a + b > 0.0 { a / (a + b) } else { 0.0 };
+    let f1 = |p: f64, r: f64| {
+        if p + r > 0.0 {
+            2.0 * p * r / (p + r)
+        } else {
+            0.0
+        }
+    };
+    let precisions = (0..tp.len())
+        .map(|c| ratio(tp[c], fp[c]))
+        .collect::<Vec<_>>();
+    let recalls = (0..tp.len())
+        .map(|c| ratio(tp[c], fn_[c]))
+        .collect::<Vec<_>>();
+    let average = |values: &[f64]| values.iter().sum::<f64>() / values.len().max(1) as f64;
+    metrics.insert("precision_macro".into(), average(&precisions));
+    metrics.insert("recall_macro".into(), average(&recalls));
+    let f1s = precisions
+        .iter()
+        .zip(&recalls)
+        .map(|(p, r)| f1(*p, *r))
+        .collect::<Vec<_>>();
+    metrics.insert("f1_macro".into(), average(&f1s));
+    let (tp, fp, fn_) = (
+        tp.iter().sum::<f64>(),
+        fp.iter().sum::<f64>(),
+        fn_.iter().sum::<f64>(),
+    );
+    let (precision, recall) = (ratio(tp, fp), ratio(tp, fn_));
+    metrics.insert("precision_micro".into(), precision);
+    metrics.insert("recall_micro".into(), recall);
+    metrics.insert("f1_micro".into(), f1(precision, recall));
+    Ok(())
+}
+
+fn regression(
+    outputs: &Tensor,
+    targets: &Tensor,
+    metrics: &mut BTreeMap<String, f64>,
+) -> Result<(), String> {
+    if outputs.size() != targets.size() {
+        return Err(format!(
+            "Regression metrics need outputs {:?} shaped as the targets {:?}",
+            outputs.size(),
+            targets.size()
+        ));
+    }
+    let (outputs, targets) = (outputs.to_kind(Kind::Double), targets.to_kind(Kind::Double));
+    let error = &outputs - &targets;
+    let value = |t: Tensor| t.double_value(&[]);
+    metrics.insert("mae".into(), value(error.abs().mean(Kind::Double)));
+    metrics.insert(
+        "rmse".into(),
+        value(error.square().mean(Kind::Double).sqrt()),
+    );
+    let residual = value(error.square().sum(Kind::Double));
+    let total = value(
+        (&targets - targets.mean(Kind::Double))
+            .square()
+            .sum(Kind::Double),
+    );
+    metrics.insert(
+        "r2".into(),
+        if total > 0.0 {
+            1.0 - residual / total
+        } else {
+            0.0
+        },
+    );
+    Ok(())
+}
@@ -0,0 +1,475 @@
+use std::collections::{BTreeMap, HashMap};
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use fltk::app::Sender;
+use tch::nn::VarStore;
+use tch::Tensor;
+
+use crate::model::{weights, Hyperparameters, Model};
+use crate::project::graph::{Graph, Node};
+use crate::project::Settings;
+use crate::training::checkpoint::{Checkpoint, CheckpointConfig, OptimizerState, BEST, LAST};
+use crate::training::control::Control;
+use crate::training::data::Dataset;
+use crate::training::determinism::Deterministic;
+use crate::training::early_stopping::{EarlyStopper, EarlyStoppingState};
+use crate::training::metrics::{ValidationConfig, VAL_LOSS};
+use crate::training::optimizer::Optimizer;
+use crate::training::run::{EpochRecord, Run};
+use crate::training::scheduler::{PlateauState, Schedule};
+use crate::training::tensorboard::{EventWriter, TensorBoardConfig};
+use crate::utils::consts::PROGRESS_INTERVAL_MS;
+use crate::utils::enums::AppEvent;
+
+pub(crate) mod checkpoint;
+pub(crate) mod control;
+pub(crate) mod data;
+pub(crate) mod determinism;
+pub(crate) mod early_stopping;
+pub(crate) mod gradients;
+pub(crate) mod metrics;
+pub(crate) mod optimizer;
+pub(crate) mod run;
+pub(crate) mod scheduler;
+pub(crate) mod sweep;
+pub(crate) mod tensorboard;
+
+// everything the worker needs to rebuild the model on its own thread, the
+// layers of a built model can not be sent
+pub(crate) struct Job {
+    pub(crate) graph: Graph,
+    pub(crate) hyperparameters: Hyperparameters,
+    pub(crate) input_shape: Vec<i64>,
+    // copies of the variables to start from, a new initialization from the
+    // seed when none
+    pub(crate) weights: Option<HashMap<String, Tensor>>,
+    // the file the starting weights were loaded from, recorded in the run
+    pub(crate) pretrained: Option<PathBuf>,
+    // the moment buffers of the optimizer, those of the checkpoint when the
+    // run is resumed
+    pub(crate) optimizer: Option<HashMap<String, Tensor>>,
+    pub(crate) dataset: PathBuf,
+    pub(crate) checkpoints: CheckpointConfig,
+    pub(crate) validation: ValidationConfig,
+    pub(crate) tensorboard: TensorBoardConfig,
+    pub(crate) resume: Option<Checkpoint>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Progress {
+    Started {
+        epochs: usize,
+        steps: usize,
+        seed: u64,
+    },
+    // mean loss of the steps since the previous report
+    Step {
+        epoch: usize,
+        step: usize,
+        loss: f64,
+        lr: f64,
+    },
+    Epoch {
+        epoch: usize,
+        loss: f64,
+    },
+    // the validation loss and the metrics of the task, also on the training
+    // outputs as train_<metric>
+    Validation {
+        epoch: usize,
+        metrics: BTreeMap<String, f64>,
+    },
+    Checkpoint(PathBuf),
+    Finished(String),
+    Failed(String),
+}
+
+// trains on a worker thread, progress is posted to the app loop and the
+// returned control pauses or stops the run
+pub(crate) fn spawn(job: Job, evt_sender: Sender<AppEvent>) -> Control {
+    let control = Control::default();
+    let worker_control = control.clone();
+    std::thread::spawn(move || {
+        let report = |progress| evt_sender.send(AppEvent::TrainingProgress(progress));
+        // tch panics on libtorch errors, e.g. shapes that do not fit the layers
+        let result =
+            std::panic::catch_unwind(AssertUnwindSafe(|| train(job, &worker_control, &report)))
+                .unwrap_or_else(|e| {
+                    Err(e
+                        .downcast_ref::<String>()
+                        .cloned()
+                        .or(e.downcast_ref::<&str>().map(|s| s.to_string()))
+                        .unwrap_or("Training panicked".to_string()))
+                });
+        report(match result {
+            Ok(message) => Progress::Finished(message),
+            Err(e) => Progress::Failed(e),
+        });
+    });
+    control
+}
+
+fn train(mut job: Job, control: &Control, report: &dyn Fn(Progress)) -> Result<String, String> {
+    let dataset = Dataset::load(&job.dataset, job.hyperparameters.loss_fn.target_kind())?;
+    dataset.check(&job.input_shape)?;
+    let components = job
+        .graph
+        .nodes
+        .iter()
+        .map(Node::to_component)
+        .collect::<Result<Vec<_>, _>>()?;
+    // picked once per run and kept in its settings, the saved weights and the
+    // checkpoints, so that the run can be repeated
+    let seed = match &job.resume {
+        Some(checkpoint) => checkpoint.seed,
+        None => job.hyperparameters.seed.unwrap_or(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0),
+        ),
+    };
+    job.hyperparameters.seed = Some(seed);
+    let _deterministic = job
+        .hyperparameters
+        .deterministic
+        .then(Deterministic::enable);
+    let settings = Settings::from_hyperparameters(&job.hyperparameters, &job.input_shape);
+    let model = Model::build(
+        &components,
+        job.graph.first,
+        job.input_shape,
+        job.hyperparameters,
+    )?;
+    if let Some(weights) = &job.weights {
+        copy_weights(&model.vs, weights)?;
+    }
+    let hp = &model.hyperparameters;
+    let mut optimizer = Optimizer::new(&hp.optimizer, &model.vs, hp.lr);
+    if let Some(state) = job.optimizer {
+        optimizer.load(state)?;
+    }
+    let resumed = job.resume.is_some();
+    let mut checkpoint = match job.resume {
+        Some(checkpoint) => checkpoint,
+        None => Checkpoint {
+            graph: job.graph,
+            settings,
+            dataset: job.dataset,
+            optimizer: OptimizerState {
+                optimizer: hp.optimizer.name().to_string(),
+                lr: hp.lr,
+                steps: 0,
+                plateau: PlateauState::default(),
+            },
+            epoch: 1,
+            step: 0,
+            seed,
+            pretrained: job.pretrained,
+            best: None,
+            checkpoints: job.checkpoints,
+            early_stopping: EarlyStoppingState::default(),
+            validation: job.validation,
+            tensorboard: job.tensorboard.for_run(),
+        },
+    };
+    let started = Instant::now();
+    let mut run = Run::new(
+        checkpoint.graph.clone(),
+        checkpoint.settings.clone(),
+        checkpoint.dataset.clone(),
+        checkpoint.seed,
+        checkpoint.pretrained.clone(),
+        &hp.save_path,
+    );
+    // the held out samples only depend on the seed, a resumed run validates
+    // on the same ones
+    let validation = checkpoint.validation.clone();
+    if hp.early_stopping.enabled && !metrics::computed(&hp.early_stopping.monitor, &validation) {
+        return Err(format!(
+            "{} is not computed, enable the validation or monitor another metric",
+            hp.early_stopping.monitor
+        ));
+    }
+    let (dataset, validation_set) = match &validation.dataset {
+        Some(path) => {
+            let validation_set = Dataset::load(path, hp.loss_fn.target_kind())?;
+            validation_set.check(&model.input_shape)?;
+            (dataset, Some(validation_set))
+        }
+        None if validation.fraction > 0.0 => {
+            let (dataset, validation_set) = dataset.split(validation.fraction, checkpoint.seed)?;
+            (dataset, Some(validation_set))
+        }
+        None => (dataset, None),
+    };
+    let steps = dataset.steps(hp.batch_size);
+    let mut events = checkpoint
+        .tensorboard
+        .logdir
+        .as_deref()
+        .map(EventWriter::create)
+        .transpose()?;
+    let histograms = checkpoint.tensorboard.histograms;
+    // the graph does not change when a run is resumed
+    if let (Some(events), false) = (events.as_mut(), resumed) {
+        events.graph(&model)?;
+    }
+    report(Progress::Started {
+        epochs: hp.epochs,
+        steps,
+        seed,
+    });
+
+    let config = checkpoint.checkpoints;
+    let interval = Duration::from_millis(PROGRESS_INTERVAL_MS);
+    let mut last_checkpoint = Instant::now();
+    let timed = |last_checkpoint: &Instant| {
+        config.every_minutes > 0
+            && last_checkpoint.elapsed() >= Duration::from_secs(config.every_minutes * 60)
+    };
+    let mut stopped = false;
+    let mut early_stopper =
+        EarlyStopper::new(hp.early_stopping.clone(), checkpoint.early_stopping.clone());
+    let mut early_stop = None;
+    // the best checkpoint follows the monitored metric, or the validation loss
+    // when early stopping is disabled
+    let (best_metric, lower) = if hp.early_stopping.enabled {
+        (
+            hp.early_stopping.monitor.clone(),
+            hp.early_stopping.mode != "max",
+        )
+    } else if validation_set.is_some() {
+        (VAL_LOSS.to_string(), true)
+    } else {
+        ("loss".to_string(), true)
+    };
+    let accumulation = hp.gradients.accumulation.max(1);
+    optimizer.zero_grad();
+    let mut schedule = Schedule::new(
+        hp.scheduler.clone(),
+        hp.lr,
+        hp.epochs,
+        checkpoint.optimizer.plateau.clone(),
+    );
+    'training: for epoch in checkpoint.epoch..=hp.epochs {
+        tch::manual_seed(checkpoint.seed.wrapping_add(epoch as u64) as i64);
+        let skip = if epoch == checkpoint.epoch {
+            checkpoint.step
+        } else {
+            0
+        };
+        let (mut epoch_loss, mut pending_loss, mut pending) = (0.0, 0.0, 0);
+        // the task metrics are computed on the training outputs as well when
+        // the run validates
+        let (mut epoch_outputs, mut epoch_targets) = (vec![], vec![]);
+        let mut last_report = Instant::now();
+        let batches = dataset.batches(hp.batch_size, hp.device);
+        for (step, (xs, ys)) in batches.enumerate().skip(skip) {
+            if control.should_stop() {
+                (checkpoint.epoch, checkpoint.step) = (epoch, step);
+                stopped = true;
+                break 'training;
+            }
+            // the schedule moves within the epoch, not only between them
+            let lr = schedule.lr((epoch - 1) as f64 + step as f64 / steps as f64);
+            optimizer.set_lr(lr);
+            checkpoint.optimizer.lr = lr;
+            // the generator state of a step only depends on where it is, a
+            // resumed run draws the same dropout masks
+            tch::manual_seed(step_seed(checkpoint.seed, epoch, step));
+            let outputs = model.forward_t(&xs, true);
+            let loss = hp.loss_fn.loss(&outputs, &ys)?;
+            if validation_set.is_some() {
+                epoch_outputs.push(outputs.detach());
+                epoch_targets.push(ys);
+            }
+            // the gradients of the micro-batches are averaged, over fewer of
+            // them for the last group of an epoch. Those of an incomplete
+            // accumulation are dropped when the run is stopped
+            let group = accumulation.min(steps - step / accumulation * accumulation);
+            (&loss / group as f64).backward();
+            if (step + 1) % accumulation == 0 || step + 1 == steps {
+                hp.gradients.step(&mut optimizer);
+                checkpoint.optimizer.steps += 1;
+                // between optimizer steps, the last one of the epoch is
+                // checkpointed with the epoch
+                if step + 1 < steps && timed(&last_checkpoint) {
+                    (checkpoint.epoch, checkpoint.step) = (epoch, step + 1);
+                    let name = format!("epoch_{:04}_step_{:06}", epoch, step + 1);
+                    report(Progress::Checkpoint(
+                        checkpoint.save(&model, &optimizer, &name)?,
+                    ));
+                    last_checkpoint = Instant::now();
+                }
+            }
+            let loss = loss.double_value(&[]);
+            if !loss.is_finite() {
+                return Err(format!(
+                    "The loss diverged at epoch {}, step {}",
+                    epoch,
+                    step + 1
+                ));
+            }
+            if let Some(events) = events.as_mut() {
+                let global_step = ((epoch - 1) * steps + step + 1) as i64;
+                events.scalar("train/loss", global_step, loss)?;
+                events.scalar("train/lr", global_step, lr)?;
+            }
+            epoch_loss += loss;
+            pending_loss += loss;
+            pending += 1;
+            if last_report.elapsed() >= interval || step + 1 == steps {
+                report(Progress::Step {
+                    epoch,
+                    step: step + 1,
+                    loss: pending_loss / pending as f64,
+                    lr,
+                });
+                (pending_loss, pending) = (0.0, 0);
+                last_report = Instant::now();
+            }
+        }
+        let loss = epoch_loss / (steps - skip) as f64;
+        report(Progress::Epoch { epoch, loss });
+
+        let mut metrics = BTreeMap::from([("loss".to_string(), loss)]);
+        if let Some(validation_set) = &validation_set {
+            let mut validation_metrics = metrics::evaluate(&model, validation_set, &validation)?;
+            validation_metrics.extend(metrics::training(
+                &epoch_outputs,
+                &epoch_targets,
+                &validation,
+            )?);
+            metrics.extend(validation_metrics.clone());
+            report(Progress::Validation {
+                epoch,
+                metrics: validation_metrics,
+            });
+        }
+        if let (true, Some(monitored)) = (
+            hp.early_stopping.enabled,
+            metrics.get(early_stopper.monitor()),
+        ) {
+            early_stop = early_stopper.update(epoch, *monitored, &model.vs);
+        }
+        if let Some(events) = events.as_mut() {
+            for (name, value) in metrics.iter() {
+                events.scalar(&format!("epoch/{}", name), epoch as i64, *value)?;
+            }
+            if histograms {
+                for (name, var) in model.vs.variables() {
+                    events.histogram(&name, epoch as i64, &var)?;
+                }
+            }
+            events.flush()?;
+        }
+        run.epochs.push(EpochRecord {
+            epoch,
+            metrics: metrics.clone(),
+        });
+        run.duration_secs = started.elapsed().as_secs_f64();
+        run.save()?;
+        // the validation loss when there is one
+        let loss = metrics.get(VAL_LOSS).copied().unwrap_or(loss);
+        schedule.epoch_end(loss);
+        checkpoint.optimizer.plateau = schedule.plateau();
+        (checkpoint.epoch, checkpoint.step) = (epoch + 1, 0);
+        checkpoint.early_stopping = early_stopper.state();
+        let value = metrics.get(&best_metric).copied();
+        if let (true, Some(value)) = (config.keep_best, value) {
+            if checkpoint
+                .best
+                .is_none_or(|best| if lower { value < best } else { value > best })
+            {
+                checkpoint.best = Some(value);
+                report(Progress::Checkpoint(
+                    checkpoint.save(&model, &optimizer, BEST)?,
+                ));
+            }
+        }
+        let periodic = config.every_epochs > 0 && epoch % config.every_epochs == 0;
+        if periodic || timed(&last_checkpoint) {
+            let name = format!("epoch_{:04}", epoch);
+            report(Progress::Checkpoint(
+                checkpoint.save(&model, &optimizer, &name)?,
+            ));
+            last_checkpoint = Instant::now();
+        }
+        if early_stop.is_some() {
+            break;
+        }
+    }
+    // the last checkpoint is where "resume training" continues from, before
+    // the best weights are restored
+    checkpoint.save(&model, &optimizer, LAST)?;
+    // the steps of an epoch that was stopped
+    if let Some(events) = events.as_mut() {
+        events.flush()?;
+    }
+    let restored = early_stop.is_some() && early_stopper.restore(&model.vs)?;
+    weights::save(&model, &hp.save_path)?;
+    run.duration_secs = started.elapsed().as_secs_f64();
+    run.outcome = match (&early_stop, stopped) {
+        (Some(_), _) => "Early stopped",
+        (None, true) => "Stopped",
+        (None, false) => "Finished",
+    }
+    .to_string();
+    run.save()?;
+    // once the run directory exists
+    weights::save(&model, &run.weights)?;
+    if let Some(reason) = early_stop {
+        Ok(format!(
+            "{}, {} saved to {}",
+            reason,
+            if restored {
+                "best weights restored and"
+            } else {
+                "weights"
+            },
+            hp.save_path.display()
+        ))
+    } else if stopped {
+        Ok(format!(
+            "Stopped at epoch {}, step {}, weights saved to {}",
+            checkpoint.epoch,
+            checkpoint.step,
+            hp.save_path.display()
+        ))
+    } else {
+        Ok(format!(
+            "Training finished, weights saved to {}",
+            hp.save_path.display()
+        ))
+    }
+}
+
+// the seed of the generator for a step, mixed as splitmix64 so that nearby
+// steps get unrelated streams
+fn step_seed(seed: u64, epoch: usize, step: usize) -> i64 {
+    let mut z = seed
+        .wrapping_add(((epoch as u64) << 32) | step as u64)
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)) as i64
+}
+
+// the graph must not have changed since the model was built
+fn copy_weights(vs: &VarStore, weights: &HashMap<String, Tensor>) -> Result<(), String> {
+    let mut variables = vs.variables();
+    tch::no_grad(|| {
+        for (name, var) in variables.iter_mut() {
+            let tensor = weights.get(name).ok_or(format!(
+                "{} is not in the built model, build the model again",
+                name
+            ))?;
+            var.f_copy_(tensor)
+                .map_err(|e| format!("Error copying {}: \n{:?}", name, e))?;
+        }
+        Ok(())
+    })
+}
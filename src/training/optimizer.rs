@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use tch::nn::VarStore;
+use tch::Tensor;
+
+use crate::utils::optimizer::OptimizerType;
+
+// the optimizers of torch.optim, written over the trainable variables so that
+// their moment buffers can be kept in the checkpoints, which the tch
+// optimizers do not allow
+pub(crate) struct Optimizer {
+    config: OptimizerType,
+    lr: f64,
+    variables: Vec<(String, Tensor)>,
+    // by variable and buffer name, e.g. "linear_0.weight.exp_avg"
+    state: HashMap<String, Tensor>,
+    steps: usize,
+}
+
+impl Optimizer {
+    pub(crate) fn new(config: &OptimizerType, vs: &VarStore, lr: f64) -> Self {
+        let mut variables = vs
+            .variables()
+            .into_iter()
+            .filter(|(_, var)| var.requires_grad())
+            .collect::<Vec<_>>();
+        variables.sort_by(|a, b| a.0.cmp(&b.0));
+        Self {
+            config: config.clone(),
+            lr,
+            variables,
+            state: HashMap::new(),
+            steps: 0,
+        }
+    }
+
+    pub(crate) fn set_lr(&mut self, lr: f64) {
+        self.lr = lr;
+    }
+
+    pub(crate) fn zero_grad(&mut self) {
+        for (_, var) in self.variables.iter_mut() {
+            var.zero_grad();
+        }
+    }
+
+    // the gradients as if they were one vector, as torch.nn.utils does
+    pub(crate) fn clip_grad_norm(&self, max: f64) {
+        tch::no_grad(|| {
+            let norms = self.gradients().map(|grad| grad.norm()).collect::<Vec<_>>();
+            if norms.is_empty() {
+                return;
+            }
+            let total = Tensor::stack(&norms, 0).norm().double_value(&[]);
+            let coef = max / (total + 1e-6);
+            if coef < 1.0 {
+                for mut grad in self.gradients() {
+                    let _ = grad.g_mul_scalar_(coef);
+                }
+            }
+        })
+    }
+
+    pub(crate) fn clip_grad_value(&self, max: f64) {
+        tch::no_grad(|| {
+            for mut grad in self.gradients() {
+                let _ = grad.clamp_(-max, max);
+            }
+        })
+    }
+
+    pub(crate) fn step(&mut self) {
+        self.steps += 1;
+        let (config, lr, steps) = (self.config.clone(), self.lr, self.steps);
+        tch::no_grad(|| {
+            for (name, var) in self.variables.iter() {
+                let grad = var.grad();
+                if !grad.defined() {
+                    continue;
+                }
+                let mut var = var.shallow_clone();
+                let update = update(&config, lr, steps, name, &var, &grad, &mut self.state);
+                let _ = var.g_sub_(&update);
+            }
+        })
+    }
+
+    // the buffers and the number of steps, saved next to the variables of a
+    // checkpoint
+    pub(crate) fn save(&self, path: &Path) -> Result<(), String> {
+        let steps = Tensor::from(self.steps as i64);
+        let mut named = self
+            .state
+            .iter()
+            .map(|(name, tensor)| (name.as_str(), tensor))
+            .collect::<Vec<_>>();
+        named.push((STEPS, &steps));
+        Tensor::save_multi(&named, path)
+            .map_err(|e| format!("Error saving the optimizer state: \n{:?}", e))
+    }
+
+    pub(crate) fn load(&mut self, mut state: HashMap<String, Tensor>) -> Result<(), String> {
+        let steps = state
+            .remove(STEPS)
+            .ok_or("The optimizer state has no step count".to_string())?;
+        let device = self
+            .variables
+            .first()
+            .map(|(_, var)| var.device())
+            .unwrap_or(tch::Device::Cpu);
+        self.steps = steps.int64_value(&[]) as usize;
+        self.state = state
+            .into_iter()
+            .map(|(name, tensor)| (name, tensor.to_device(device)))
+            .collect();
+        Ok(())
+    }
+
+    pub(crate) fn read(path: &Path) -> Result<HashMap<String, Tensor>, String> {
+        Ok(Tensor::load_multi(path)
+            .map_err(|e| format!("Error reading the optimizer state: \n{:?}", e))?
+            .into_iter()
+            .collect())
+    }
+
+    fn gradients(&self) -> impl Iterator<Item = Tensor> + '_ {
+        self.variables
+            .iter()
+            .map(|(_, var)| var.grad())
+            .filter(|grad| grad.defined())
+    }
+}
+
+const STEPS: &str = "steps";
+
+// what is subtracted from the variable, the buffers are updated in place of
+// the previous ones
+fn update(
+    config: &OptimizerType,
+    lr: f64,
+    steps: usize,
+    name: &str,
+    var: &Tensor,
+    grad: &Tensor,
+    state: &mut HashMap<String, Tensor>,
+) -> Tensor {
+    let previous = |state: &HashMap<String, Tensor>, buffer: &str| {
+        state
+            .get(&format!("{}.{}", name, buffer))
+            .map(Tensor::shallow_clone)
+    };
+    let keep = |state: &mut HashMap<String, Tensor>, buffer: &str, value: Tensor| {
+        state.insert(format!("{}.{}", name, buffer), value.shallow_clone());
+        value
+    };
+    match *config {
+        OptimizerType::Sgd {
+            momentum,
+            dampening,
+            nesterov,
+            wd,
+        } => {
+            let grad = grad + var * wd;
+            if momentum == 0.0 {
+                return grad * lr;
+            }
+            let momentum_buffer = match previous(state, "momentum_buffer") {
+                Some(m) => m * momentum + &grad * (1.0 - dampening),
+                None => grad.copy(),
+            };
+            let momentum_buffer = keep(state, "momentum_buffer", momentum_buffer);
+            if nesterov {
+                (grad + momentum_buffer * momentum) * lr
+            } else {
+                momentum_buffer * lr
+            }
+        }
+        OptimizerType::Adam {
+            beta1,
+            beta2,
+            eps,
+            wd,
+            amsgrad,
+        }
+        | OptimizerType::AdamW {
+            beta1,
+            beta2,
+            eps,
+            wd,
+            amsgrad,
+        } => {
+            // adam adds the decay to the gradient, adamw to the update
+            let decoupled = matches!(config, OptimizerType::AdamW { .. });
+            let grad = if decoupled {
+                grad.shallow_clone()
+            } else {
+                grad + var * wd
+            };
+            let exp_avg = previous(state, "exp_avg").unwrap_or_else(|| grad.zeros_like());
+            let exp_avg_sq = previous(state, "exp_avg_sq").unwrap_or_else(|| grad.zeros_like());
+            let exp_avg = keep(state, "exp_avg", exp_avg * beta1 + &grad * (1.0 - beta1));
+            let exp_avg_sq = keep(
+                state,
+                "exp_avg_sq",
+                exp_avg_sq * beta2 + grad.square() * (1.0 - beta2),
+            );
+            let second = if amsgrad {
+                let max = match previous(state, "max_exp_avg_sq") {
+                    Some(max) => max.maximum(&exp_avg_sq),
+                    None => exp_avg_sq.copy(),
+                };
+                keep(state, "max_exp_avg_sq", max)
+            } else {
+                exp_avg_sq
+            };
+            let bias_correction1 = 1.0 - beta1.powi(steps as i32);
+            let bias_correction2 = 1.0 - beta2.powi(steps as i32);
+            let denom = second.sqrt() / bias_correction2.sqrt() + eps;
+            let update = exp_avg / denom * (lr / bias_correction1);
+            if decoupled {
+                update + var * (lr * wd)
+            } else {
+                update
+            }
+        }
+        OptimizerType::RmsProp {
+            alpha,
+            eps,
+            momentum,
+            centered,
+            wd,
+        } => {
+            let grad = grad + var * wd;
+            let square_avg = previous(state, "square_avg").unwrap_or_else(|| grad.zeros_like());
+            let square_avg = keep(
+                state,
+                "square_avg",
+                square_avg * alpha + grad.square() * (1.0 - alpha),
+            );
+            let avg = if centered {
+                let grad_avg = previous(state, "grad_avg").unwrap_or_else(|| grad.zeros_like());
+                let grad_avg = keep(state, "grad_avg", grad_avg * alpha + &grad * (1.0 - alpha));
+                (square_avg - grad_avg.square()).sqrt() + eps
+            } else {
+                square_avg.sqrt() + eps
+            };
+            if momentum > 0.0 {
+                let momentum_buffer =
+                    previous(state, "momentum_buffer").unwrap_or_else(|| grad.zeros_like());
+                keep(
+                    state,
+                    "momentum_buffer",
+                    momentum_buffer * momentum + grad / avg,
+                ) * lr
+            } else {
+                grad / avg * lr
+            }
+        }
+    }
+}
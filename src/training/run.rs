@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::project::graph::Graph;
+use crate::project::snapshot::format_timestamp;
+use crate::project::Settings;
+use crate::training::metrics::lower_is_better;
+
+// kept when the process ends before the run does
+const INTERRUPTED: &str = "Interrupted";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct EpochRecord {
+    pub(crate) epoch: usize,
+    // the training loss, the validation loss and metrics
+    pub(crate) metrics: BTreeMap<String, f64>,
+}
+
+// a training run, rewritten after every epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Run {
+    // milliseconds since the unix epoch, also the name of the file
+    pub(crate) started: u64,
+    pub(crate) graph: Graph,
+    pub(crate) settings: Settings,
+    pub(crate) dataset: PathBuf,
+    pub(crate) seed: u64,
+    // the weights file the run started from, a new initialization when none
+    #[serde(default)]
+    pub(crate) pretrained: Option<PathBuf>,
+    pub(crate) epochs: Vec<EpochRecord>,
+    // the final weights of the run, next to its file in the run directory
+    pub(crate) weights: PathBuf,
+    pub(crate) duration_secs: f64,
+    // how the run ended
+    pub(crate) outcome: String,
+}
+
+impl Run {
+    pub(crate) fn new(
+        graph: Graph,
+        settings: Settings,
+        dataset: PathBuf,
+        seed: u64,
+        pretrained: Option<PathBuf>,
+        save_path: &Path,
+    ) -> Self {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        // in the format of the save path
+        let extension = save_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("pt");
+        Self {
+            started,
+            graph,
+            settings,
+            dataset,
+            seed,
+            pretrained,
+            epochs: vec![],
+            weights: Self::directory(save_path).join(format!("{}.{}", started, extension)),
+            duration_secs: 0.0,
+            outcome: INTERRUPTED.to_string(),
+        }
+    }
+
+    // runs of model.pt are kept in model.runs
+    pub(crate) fn directory(save_path: &Path) -> PathBuf {
+        save_path.with_extension("runs")
+    }
+
+    pub(crate) fn title(&self) -> String {
+        let mut title = format!(
+            "{}  {} epochs  {:.0}s  {}",
+            format_timestamp(self.started / 1000),
+            self.epochs.len(),
+            self.duration_secs,
+            self.outcome
+        );
+        if let Some(name) = self.pretrained.as_ref().and_then(|p| p.file_name()) {
+            title.push_str(&format!("  from {}", name.to_string_lossy()));
+        }
+        title
+    }
+
+    // the best value of every metric over the epochs
+    pub(crate) fn best(&self) -> BTreeMap<String, f64> {
+        let mut best = BTreeMap::new();
+        for record in self.epochs.iter() {
+            for (name, value) in record.metrics.iter() {
+                best.entry(name.clone())
+                    .and_modify(|b: &mut f64| {
+                        if (*value < *b) == lower_is_better(name) {
+                            *b = *value;
+                        }
+                    })
+                    .or_insert(*value);
+            }
+        }
+        best
+    }
+
+    pub(crate) fn save(&self) -> Result<(), String> {
+        let save_path = self
+            .settings
+            .save_path
+            .as_ref()
+            .ok_or("Save path not selected")?;
+        let dir = Self::directory(save_path);
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Error creating the run directory: \n{:?}", e))?;
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Error serializing the run: \n{:?}", e))?;
+        std::fs::write(dir.join(format!("{}.json", self.started)), json)
+            .map_err(|e| format!("Error writing the run: \n{:?}", e))
+    }
+
+    // oldest first, unreadable files are skipped
+    pub(crate) fn list(save_path: &Path) -> Result<Vec<Self>, String> {
+        let dir = Self::directory(save_path);
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| format!("Error reading the run directory: \n{:?}", e))?;
+        let mut runs = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|e| e == "json"))
+            .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+            .filter_map(|json| serde_json::from_str::<Self>(&json).ok())
+            .collect::<Vec<_>>();
+        runs.sort_by_key(|r| r.started);
+        Ok(runs)
+    }
+}
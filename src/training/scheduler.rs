@@ -0,0 +1,366 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::consts::SCHEDULERS;
+
+// samples of the preview, at least one per epoch
+const PREVIEW_POINTS: usize = 500;
+
+// learning rate over the run, relative to the learning rate entry
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Scheduler {
+    #[default]
+    Constant,
+    // multiplies by gamma every step_size epochs
+    Step {
+        step_size: usize,
+        gamma: f64,
+    },
+    // multiplies by gamma at each milestone epoch
+    MultiStep {
+        milestones: Vec<usize>,
+        gamma: f64,
+    },
+    Exponential {
+        gamma: f64,
+    },
+    // cosine from the learning rate to eta_min over t_0 epochs, the cycles
+    // grow by t_mult after every restart
+    CosineWarmRestarts {
+        t_0: usize,
+        t_mult: usize,
+        eta_min: f64,
+    },
+    // linear from start_factor times the learning rate
+    LinearWarmup {
+        warmup_epochs: usize,
+        start_factor: f64,
+    },
+    // the learning rate entry is the peak, reached after pct_start of the run
+    OneCycle {
+        pct_start: f64,
+        div_factor: f64,
+        final_div_factor: f64,
+    },
+    // multiplies by factor once the epoch loss stops improving
+    ReduceOnPlateau {
+        factor: f64,
+        patience: usize,
+        threshold: f64,
+        min_lr: f64,
+    },
+}
+
+impl Scheduler {
+    // label of the scheduler in the selector
+    pub(crate) fn name(&self) -> &'static str {
+        SCHEDULERS[self.index()]
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        match self {
+            Scheduler::Constant => 0,
+            Scheduler::Step { .. } => 1,
+            Scheduler::MultiStep { .. } => 2,
+            Scheduler::Exponential { .. } => 3,
+            Scheduler::CosineWarmRestarts { .. } => 4,
+            Scheduler::LinearWarmup { .. } => 5,
+            Scheduler::OneCycle { .. } => 6,
+            Scheduler::ReduceOnPlateau { .. } => 7,
+        }
+    }
+
+    // the scheduler at `index` in the selector, with the torch defaults
+    pub(crate) fn with_defaults(index: usize) -> Self {
+        match index {
+            1 => Scheduler::Step {
+                step_size: 30,
+                gamma: 0.1,
+            },
+            2 => Scheduler::MultiStep {
+                milestones: vec![30, 80],
+                gamma: 0.1,
+            },
+            3 => Scheduler::Exponential { gamma: 0.95 },
+            4 => Scheduler::CosineWarmRestarts {
+                t_0: 10,
+                t_mult: 1,
+                eta_min: 0.0,
+            },
+            5 => Scheduler::LinearWarmup {
+                warmup_epochs: 5,
+                start_factor: 0.1,
+            },
+            6 => Scheduler::OneCycle {
+                pct_start: 0.3,
+                div_factor: 25.0,
+                final_div_factor: 1e4,
+            },
+            7 => Scheduler::ReduceOnPlateau {
+                factor: 0.1,
+                patience: 10,
+                threshold: 1e-4,
+                min_lr: 0.0,
+            },
+            _ => Scheduler::Constant,
+        }
+    }
+
+    // (epoch, learning rate) over the run, the plateau scheduler can not
+    // know the losses in advance and stays flat
+    pub(crate) fn preview(&self, lr: f64, epochs: usize) -> Vec<(f64, f64)> {
+        let schedule = Schedule::new(self.clone(), lr, epochs, PlateauState::default());
+        let points = PREVIEW_POINTS.max(epochs);
+        (0..=points)
+            .map(|i| {
+                let progress = epochs as f64 * i as f64 / points as f64;
+                (progress + 1.0, schedule.lr(progress))
+            })
+            .collect()
+    }
+}
+
+// what the plateau scheduler has seen, kept in the checkpoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PlateauState {
+    pub(crate) scale: f64,
+    pub(crate) best: Option<f64>,
+    pub(crate) wait: usize,
+}
+
+impl Default for PlateauState {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            best: None,
+            wait: 0,
+        }
+    }
+}
+
+pub(crate) struct Schedule {
+    scheduler: Scheduler,
+    lr: f64,
+    epochs: usize,
+    plateau: PlateauState,
+}
+
+impl Schedule {
+    pub(crate) fn new(scheduler: Scheduler, lr: f64, epochs: usize, plateau: PlateauState) -> Self {
+        Self {
+            scheduler,
+            lr,
+            epochs,
+            plateau,
+        }
+    }
+
+    pub(crate) fn plateau(&self) -> PlateauState {
+        self.plateau.clone()
+    }
+
+    // learning rate after `progress` epochs, fractional within an epoch
+    pub(crate) fn lr(&self, progress: f64) -> f64 {
+        let epoch = progress.floor();
+        match &self.scheduler {
+            Scheduler::Constant => self.lr,
+            Scheduler::Step { step_size, gamma } => {
+                self.lr * gamma.powf((epoch / (*step_size).max(1) as f64).floor())
+            }
+            Scheduler::MultiStep { milestones, gamma } => {
+                let passed = milestones.iter().filter(|m| **m as f64 <= epoch).count();
+                self.lr * gamma.powi(passed as i32)
+            }
+            Scheduler::Exponential { gamma } => self.lr * gamma.powf(epoch),
+            Scheduler::CosineWarmRestarts {
+                t_0,
+                t_mult,
+                eta_min,
+            } => {
+                let (t_0, t_mult) = ((*t_0).max(1) as f64, (*t_mult).max(1) as f64);
+                let (t_cur, t_i) = if t_mult == 1.0 {
+                    (progress % t_0, t_0)
+                } else {
+                    let n = ((progress / t_0 * (t_mult - 1.0) + 1.0).ln() / t_mult.ln()).floor();
+                    let start = t_0 * (t_mult.powf(n) - 1.0) / (t_mult - 1.0);
+                    (progress - start, t_0 * t_mult.powf(n))
+                };
+                eta_min + (self.lr - eta_min) * (1.0 + (PI * t_cur / t_i).cos()) / 2.0
+            }
+            Scheduler::LinearWarmup {
+                warmup_epochs,
+                start_factor,
+            } => {
+                if progress < *warmup_epochs as f64 {
+                    let t = progress / *warmup_epochs as f64;
+                    self.lr * (start_factor + (1.0 - start_factor) * t)
+                } else {
+                    self.lr
+                }
+            }
+            Scheduler::OneCycle {
+                pct_start,
+                div_factor,
+                final_div_factor,
+            } => {
+                let initial = self.lr / div_factor;
+                let last = initial / final_div_factor;
+                let t = progress / self.epochs.max(1) as f64;
+                // cosine from `from` to `to` as `t` goes from 0 to 1
+                let anneal = |from: f64, to: f64, t: f64| {
+                    to + (from - to) * (1.0 + (PI * t.clamp(0.0, 1.0)).cos()) / 2.0
+                };
+                if t < *pct_start {
+                    anneal(initial, self.lr, t / pct_start)
+                } else {
+                    anneal(self.lr, last, (t - pct_start) / (1.0 - pct_start))
+                }
+            }
+            Scheduler::ReduceOnPlateau { min_lr, .. } => {
+                (self.lr * self.plateau.scale).max(*min_lr)
+            }
+        }
+    }
+
+    // called with the epoch loss, only the plateau scheduler reacts to it
+    pub(crate) fn epoch_end(&mut self, loss: f64) {
+        if let Scheduler::ReduceOnPlateau {
+            factor,
+            patience,
+            threshold,
+            ..
+        } = &self.scheduler
+        {
+            let state = &mut self.plateau;
+            if state
+                .best
+                .is_none_or(|best| loss < best * (1.0 - threshold))
+            {
+                state.best = Some(loss);
+                state.wait = 0;
+            } else {
+                state.wait += 1;
+                if state.wait > *patience {
+                    state.scale *= factor;
+                    state.wait = 0;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(scheduler: Scheduler, epochs: usize) -> Schedule {
+        Schedule::new(scheduler, 1.0, epochs, PlateauState::default())
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn step() {
+        let schedule = schedule(
+            Scheduler::Step {
+                step_size: 3,
+                gamma: 0.5,
+            },
+            10,
+        );
+        assert_close(schedule.lr(0.0), 1.0);
+        assert_close(schedule.lr(2.99), 1.0);
+        assert_close(schedule.lr(3.0), 0.5);
+        assert_close(schedule.lr(5.99), 0.5);
+        assert_close(schedule.lr(6.0), 0.25);
+    }
+
+    #[test]
+    fn multi_step() {
+        let schedule = schedule(
+            Scheduler::MultiStep {
+                milestones: vec![2, 5],
+                gamma: 0.1,
+            },
+            10,
+        );
+        assert_close(schedule.lr(1.99), 1.0);
+        assert_close(schedule.lr(2.0), 0.1);
+        assert_close(schedule.lr(4.99), 0.1);
+        assert_close(schedule.lr(5.0), 0.01);
+        assert_close(schedule.lr(9.0), 0.01);
+    }
+
+    #[test]
+    fn cosine_warm_restarts() {
+        // cycles of 2, 4 and 8 epochs, restarting at 2, 6 and 14
+        let schedule = schedule(
+            Scheduler::CosineWarmRestarts {
+                t_0: 2,
+                t_mult: 2,
+                eta_min: 0.0,
+            },
+            30,
+        );
+        for restart in [0.0, 2.0, 6.0, 14.0] {
+            assert_close(schedule.lr(restart), 1.0);
+        }
+        for end in [1.999, 5.999, 13.999] {
+            assert!(schedule.lr(end) < 1e-5);
+        }
+        assert_close(schedule.lr(4.0), 0.5);
+        assert_close(schedule.lr(10.0), 0.5);
+    }
+
+    #[test]
+    fn one_cycle() {
+        let schedule = schedule(
+            Scheduler::OneCycle {
+                pct_start: 0.3,
+                div_factor: 25.0,
+                final_div_factor: 1e4,
+            },
+            10,
+        );
+        assert_close(schedule.lr(0.0), 1.0 / 25.0);
+        assert_close(schedule.lr(3.0), 1.0);
+        assert!(schedule.lr(2.9) < 1.0 && schedule.lr(3.1) < 1.0);
+        assert_close(schedule.lr(10.0), 1.0 / 25.0 / 1e4);
+    }
+
+    #[test]
+    fn reduce_on_plateau() {
+        let mut schedule = schedule(
+            Scheduler::ReduceOnPlateau {
+                factor: 0.5,
+                patience: 2,
+                threshold: 0.0,
+                min_lr: 0.0,
+            },
+            10,
+        );
+        schedule.epoch_end(1.0);
+        // waits `patience` epochs without improvement
+        for _ in 0..2 {
+            schedule.epoch_end(1.0);
+            assert_close(schedule.lr(0.0), 1.0);
+        }
+        schedule.epoch_end(1.0);
+        assert_close(schedule.lr(0.0), 0.5);
+        // an improvement starts the wait again
+        schedule.epoch_end(0.5);
+        schedule.epoch_end(0.9);
+        schedule.epoch_end(0.9);
+        assert_close(schedule.lr(0.0), 0.5);
+        schedule.epoch_end(0.9);
+        assert_close(schedule.lr(0.0), 0.25);
+    }
+}
@@ -0,0 +1,752 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::model::{node_name, parse_shape};
+use crate::project::graph::{Graph, LayerConfig};
+use crate::project::Settings;
+use crate::training::checkpoint::CheckpointConfig;
+use crate::training::metrics::{lower_is_better, ValidationConfig};
+use crate::training::tensorboard::TensorBoardConfig;
+use crate::training::{Job, Progress};
+use crate::utils::consts::OPTIMIZERS;
+use crate::utils::optimizer::OptimizerType;
+
+pub(crate) const STRATEGIES: [&str; 2] = ["Grid", "Random"];
+pub(crate) const TRIAL_SCHEDULERS: [&str; 3] = ["None", "Successive halving", "Hyperband"];
+
+// the values tried for every hyperparameter, the configured value is kept
+// for the empty ones
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SearchSpace {
+    // a grid takes `lr_points` log-spaced values, a random search samples it
+    // log-uniformly. 0 points keep the configured learning rate
+    pub(crate) lr_min: f64,
+    pub(crate) lr_max: f64,
+    pub(crate) lr_points: usize,
+    pub(crate) batch_sizes: Vec<i64>,
+    pub(crate) epochs: Vec<usize>,
+    // names of OPTIMIZERS, with their default parameters
+    pub(crate) optimizers: Vec<String>,
+    // output sizes of layers by node name, e.g. "linear_0"
+    pub(crate) layers: Vec<(String, Vec<i64>)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Strategy {
+    // every combination of the values
+    Grid,
+    Random { trials: usize, seed: u64 },
+}
+
+// the trials train up to `max_epochs` and are compared at `min_epochs`,
+// `min_epochs * eta`, ... epochs, only the best 1/eta of them go on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TrialScheduler {
+    // every trial trains all its epochs
+    None,
+    SuccessiveHalving {
+        min_epochs: usize,
+        max_epochs: usize,
+        eta: usize,
+    },
+    // brackets of successive halving with growing minimum budgets, the
+    // trials are spread over them
+    Hyperband {
+        min_epochs: usize,
+        max_epochs: usize,
+        eta: usize,
+    },
+}
+
+impl TrialScheduler {
+    // the epochs of every trial, instead of those of the search space
+    fn max_epochs(&self) -> Option<usize> {
+        match *self {
+            TrialScheduler::None => None,
+            TrialScheduler::SuccessiveHalving { max_epochs, .. }
+            | TrialScheduler::Hyperband { max_epochs, .. } => Some(max_epochs),
+        }
+    }
+
+    // the epochs the trials are compared at, before the last one
+    fn rungs(&self) -> Vec<usize> {
+        let (min_epochs, max_epochs, eta) = match *self {
+            TrialScheduler::None => return vec![],
+            TrialScheduler::SuccessiveHalving {
+                min_epochs,
+                max_epochs,
+                eta,
+            }
+            | TrialScheduler::Hyperband {
+                min_epochs,
+                max_epochs,
+                eta,
+            } => (min_epochs.max(1), max_epochs, eta.max(2)),
+        };
+        std::iter::successors(Some(min_epochs), |epochs| Some(epochs * eta))
+            .take_while(|epochs| *epochs < max_epochs)
+            .collect()
+    }
+
+    fn eta(&self) -> usize {
+        match *self {
+            TrialScheduler::None => 1,
+            TrialScheduler::SuccessiveHalving { eta, .. }
+            | TrialScheduler::Hyperband { eta, .. } => eta.max(2),
+        }
+    }
+
+    // the bracket of every trial. Bracket b skips the first b rungs, the last
+    // one trains every trial to the full budget. A hyperband weighs bracket b
+    // with s = s_max - b halvings as ceil((s_max + 1) / (s + 1) * eta^s), as
+    // in the paper, interleaved so that a few trials already use them all
+    fn brackets(&self, trials: usize) -> Vec<usize> {
+        let TrialScheduler::Hyperband { .. } = self else {
+            return vec![0; trials];
+        };
+        let s_max = self.rungs().len();
+        let count = s_max + 1;
+        let eta = self.eta();
+        let weights = (0..count)
+            .map(|b| {
+                let s = s_max - b;
+                (count * eta.pow(s as u32)).div_ceil(s + 1) as f64
+            })
+            .collect::<Vec<_>>();
+        let mut assigned = vec![0.0; count];
+        (0..trials)
+            .map(|_| {
+                let bracket = (0..count)
+                    .min_by(|a, b| {
+                        (assigned[*a] / weights[*a]).total_cmp(&(assigned[*b] / weights[*b]))
+                    })
+                    .unwrap_or(0);
+                assigned[bracket] += 1.0;
+                bracket
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SweepConfig {
+    pub(crate) strategy: Strategy,
+    pub(crate) space: SearchSpace,
+    pub(crate) scheduler: TrialScheduler,
+    // the trials are ranked by it
+    pub(crate) metric: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TrialParams {
+    pub(crate) lr: f64,
+    pub(crate) batch_size: i64,
+    pub(crate) epochs: usize,
+    pub(crate) optimizer: Option<String>,
+    pub(crate) layers: Vec<(String, i64)>,
+}
+
+impl TrialParams {
+    fn from_settings(settings: &Settings) -> Self {
+        Self {
+            lr: settings.lr,
+            batch_size: settings.batch_size,
+            epochs: settings.epochs,
+            optimizer: None,
+            layers: vec![],
+        }
+    }
+
+    // shown in the results
+    pub(crate) fn optimizer_name(&self, settings: &Settings) -> String {
+        match (&self.optimizer, &settings.optimizer) {
+            (Some(name), _) => name.clone(),
+            (None, Some(optimizer)) => optimizer.name().to_string(),
+            (None, None) => "-".to_string(),
+        }
+    }
+
+    pub(crate) fn layers_description(&self) -> String {
+        self.layers
+            .iter()
+            .map(|(name, size)| format!("{}={}", name, size))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    // the graph and settings of the trial, the configured optimizer keeps its
+    // parameters when it is the one tried
+    fn apply(&self, graph: &Graph, settings: &Settings) -> Result<(Graph, Settings), String> {
+        let mut graph = graph.clone();
+        let mut settings = settings.clone();
+        settings.lr = self.lr;
+        settings.batch_size = self.batch_size;
+        settings.epochs = self.epochs;
+        if let Some(name) = &self.optimizer {
+            let index = OPTIMIZERS
+                .iter()
+                .position(|o| o == name)
+                .ok_or(format!("Unknown optimizer: {}", name))?;
+            if settings
+                .optimizer
+                .as_ref()
+                .is_none_or(|o| o.index() != index)
+            {
+                settings.optimizer = Some(OptimizerType::with_defaults(index));
+            }
+        }
+        for (name, size) in self.layers.iter() {
+            resize(&mut graph, name, *size)?;
+        }
+        Ok((graph, settings))
+    }
+}
+
+// sets the output size of a layer, the layers after it take it as input
+// through the activation functions and batch norms, which keep it
+fn resize(graph: &mut Graph, name: &str, size: i64) -> Result<(), String> {
+    let mut index = None;
+    for (i, node) in graph.nodes.iter().enumerate() {
+        if node_name(i, &node.to_component()?) == name {
+            index = Some(i);
+        }
+    }
+    let index = index.ok_or(format!("{} is not in the graph", name))?;
+    let node = &mut graph.nodes[index];
+    let layer = node
+        .layer
+        .as_mut()
+        .ok_or(format!("{} is not a layer", name))?;
+    layer.action_space = size;
+    let features = match layer.config {
+        LayerConfig::Rnn {
+            bidirectional: true,
+            ..
+        } => 2 * size,
+        _ => size,
+    };
+    let mut next = node.next;
+    // bounded in case of a cycle, which building the model reports
+    for _ in 0..graph.nodes.len() {
+        let Some(i) = next else {
+            break;
+        };
+        next = graph.nodes[i].next;
+        let Some(layer) = graph.nodes[i].layer.as_mut() else {
+            continue;
+        };
+        layer.observation_space = features;
+        if !matches!(layer.config, LayerConfig::BatchNorm { .. }) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+impl SweepConfig {
+    pub(crate) fn trials(&self, settings: &Settings) -> Vec<TrialParams> {
+        let mut base = TrialParams::from_settings(settings);
+        let space = &self.space;
+        // a scheduler decides how long the trials train
+        let epochs = match self.scheduler.max_epochs() {
+            Some(max_epochs) => {
+                base.epochs = max_epochs;
+                &[][..]
+            }
+            None => &space.epochs[..],
+        };
+        match self.strategy {
+            Strategy::Grid => {
+                let lrs = if space.lr_points == 0 {
+                    vec![settings.lr]
+                } else {
+                    log_space(space.lr_min, space.lr_max, space.lr_points)
+                };
+                let mut trials = expand(vec![base], &lrs, |t, lr| t.lr = *lr);
+                trials = expand(trials, &space.batch_sizes, |t, b| t.batch_size = *b);
+                trials = expand(trials, epochs, |t, e| t.epochs = *e);
+                trials = expand(trials, &space.optimizers, |t, o| {
+                    t.optimizer = Some(o.clone())
+                });
+                for (name, sizes) in space.layers.iter() {
+                    trials = expand(trials, sizes, |t, size| {
+                        t.layers.push((name.clone(), *size))
+                    });
+                }
+                trials
+            }
+            Strategy::Random { trials, seed } => {
+                let mut random = SplitMix(seed);
+                (0..trials)
+                    .map(|_| {
+                        let mut trial = base.clone();
+                        if space.lr_points > 0 {
+                            let (min, max) = (space.lr_min.ln(), space.lr_max.ln());
+                            trial.lr = (min + (max - min) * random.next_f64()).exp();
+                        }
+                        if let Some(b) = random.pick(&space.batch_sizes) {
+                            trial.batch_size = *b;
+                        }
+                        if let Some(e) = random.pick(epochs) {
+                            trial.epochs = *e;
+                        }
+                        trial.optimizer = random.pick(&space.optimizers).cloned();
+                        for (name, sizes) in space.layers.iter() {
+                            if let Some(size) = random.pick(sizes) {
+                                trial.layers.push((name.clone(), *size));
+                            }
+                        }
+                        trial
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+// one trial per trial and value, the trials are kept when there are no values
+fn expand<T>(
+    trials: Vec<TrialParams>,
+    values: &[T],
+    set: impl Fn(&mut TrialParams, &T),
+) -> Vec<TrialParams> {
+    if values.is_empty() {
+        return trials;
+    }
+    let set = &set;
+    trials
+        .into_iter()
+        .flat_map(|trial| {
+            values.iter().map(move |value| {
+                let mut trial = trial.clone();
+                set(&mut trial, value);
+                trial
+            })
+        })
+        .collect::<Vec<_>>()
+}
+
+fn log_space(min: f64, max: f64, points: usize) -> Vec<f64> {
+    if points == 1 {
+        return vec![min];
+    }
+    (0..points)
+        .map(|i| min * (max / min).powf(i as f64 / (points - 1) as f64))
+        .collect()
+}
+
+// a small generator, the sampled trials only depend on the seed
+struct SplitMix(u64);
+
+impl SplitMix {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn pick<'a, T>(&mut self, values: &'a [T]) -> Option<&'a T> {
+        if values.is_empty() {
+            return None;
+        }
+        values.get((self.next_u64() % values.len() as u64) as usize)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TrialStatus {
+    Pending,
+    Running,
+    Finished,
+    Stopped,
+    // by the scheduler, after the epoch
+    Pruned(usize),
+    Failed(String),
+}
+
+impl TrialStatus {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            TrialStatus::Pending => "Pending",
+            TrialStatus::Running => "Running",
+            TrialStatus::Finished => "Finished",
+            TrialStatus::Stopped => "Stopped",
+            TrialStatus::Pruned(_) => "Pruned",
+            TrialStatus::Failed(_) => "Failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Trial {
+    pub(crate) params: TrialParams,
+    // promoted to the editor as they are
+    pub(crate) graph: Graph,
+    pub(crate) settings: Settings,
+    // the best value of every metric over the epochs
+    pub(crate) metrics: BTreeMap<String, f64>,
+    pub(crate) status: TrialStatus,
+    // the epochs trained so far
+    pub(crate) epochs: usize,
+    // of the scheduler, with the metric at every rung it reached
+    pub(crate) bracket: usize,
+    rungs: Vec<f64>,
+}
+
+// what the trainer does with the current trial after a progress
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Observation {
+    Running,
+    // stop it, its metric is not among the best at a rung
+    Prune(usize),
+    Ended,
+}
+
+// the trials of a sweep, trained one after the other by the trainer
+pub(crate) struct Sweep {
+    pub(crate) metric: String,
+    pub(crate) trials: Vec<Trial>,
+    pub(crate) scheduler: TrialScheduler,
+    current: Option<usize>,
+    stopped: bool,
+    // the current trial was pruned, the trainer is stopping it
+    pruned: Option<usize>,
+    dataset: PathBuf,
+    checkpoints: CheckpointConfig,
+    validation: ValidationConfig,
+    tensorboard: TensorBoardConfig,
+}
+
+impl Sweep {
+    pub(crate) fn new(
+        config: &SweepConfig,
+        graph: &Graph,
+        settings: &Settings,
+        dataset: PathBuf,
+        checkpoints: CheckpointConfig,
+        validation: ValidationConfig,
+        tensorboard: TensorBoardConfig,
+    ) -> Result<Self, String> {
+        // fails early on unset entries, before any trial is trained
+        settings.hyperparameters()?;
+        let validated = validation.fraction > 0.0 || validation.dataset.is_some();
+        if config.scheduler != TrialScheduler::None && config.metric != "loss" && !validated {
+            return Err(format!(
+                "The trials are pruned on {}, select a validation split or dataset",
+                config.metric
+            ));
+        }
+        let params = config.trials(settings);
+        let brackets = config.scheduler.brackets(params.len());
+        let trials = params
+            .into_iter()
+            .zip(brackets)
+            .map(|(params, bracket)| {
+                let (graph, settings) = params.apply(graph, settings)?;
+                Ok(Trial {
+                    params,
+                    graph,
+                    settings,
+                    metrics: BTreeMap::new(),
+                    status: TrialStatus::Pending,
+                    epochs: 0,
+                    bracket,
+                    rungs: vec![],
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        if trials.is_empty() {
+            return Err("The search space has no trials".to_string());
+        }
+        Ok(Self {
+            metric: config.metric.clone(),
+            trials,
+            scheduler: config.scheduler,
+            current: None,
+            stopped: false,
+            pruned: None,
+            dataset,
+            checkpoints,
+            validation,
+            tensorboard,
+        })
+    }
+
+    // the trials save their weights, runs and checkpoints next to each other,
+    // model.pt in model.sweep/trial_001.pt
+    pub(crate) fn trial_path(save_path: &Path, trial: usize) -> PathBuf {
+        let extension = save_path
+            .extension()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or("pt".to_string());
+        save_path
+            .with_extension("sweep")
+            .join(format!("trial_{:03}.{}", trial + 1, extension))
+    }
+
+    pub(crate) fn running(&self) -> bool {
+        self.current.is_some()
+    }
+
+    // the next pending trial, none once the sweep is done or stopped
+    pub(crate) fn next(&mut self) -> Option<usize> {
+        if self.stopped {
+            return None;
+        }
+        let next = self
+            .trials
+            .iter()
+            .position(|t| t.status == TrialStatus::Pending)?;
+        self.trials[next].status = TrialStatus::Running;
+        self.current = Some(next);
+        Some(next)
+    }
+
+    // a job that trains the trial from a new initialization
+    pub(crate) fn job(&self, index: usize) -> Result<Job, String> {
+        let trial = &self.trials[index];
+        let mut hyperparameters = trial.settings.hyperparameters()?;
+        hyperparameters.save_path = Self::trial_path(&hyperparameters.save_path, index);
+        Ok(Job {
+            graph: trial.graph.clone(),
+            hyperparameters,
+            input_shape: parse_shape(&trial.settings.input_shape)?,
+            weights: None,
+            pretrained: None,
+            optimizer: None,
+            dataset: self.dataset.clone(),
+            checkpoints: self.checkpoints,
+            validation: self.validation.clone(),
+            tensorboard: self.tensorboard.clone(),
+            resume: None,
+        })
+    }
+
+    // the current trial fails before it was trained, e.g. on an invalid graph
+    pub(crate) fn fail(&mut self, trial: usize, e: String) {
+        self.trials[trial].status = TrialStatus::Failed(e);
+        self.current = None;
+    }
+
+    // the remaining trials are not trained, the current one is stopped by the
+    // trainer
+    pub(crate) fn stop(&mut self) {
+        self.stopped = true;
+    }
+
+    // records the metrics of the current trial, and whether the trainer stops
+    // it or it ended
+    pub(crate) fn observe(&mut self, progress: &Progress) -> Observation {
+        let Some(current) = self.current else {
+            return Observation::Running;
+        };
+        let trial = &mut self.trials[current];
+        let mut record = |name: &str, value: f64| {
+            trial
+                .metrics
+                .entry(name.to_string())
+                .and_modify(|best| {
+                    if (value < *best) == lower_is_better(name) {
+                        *best = value;
+                    }
+                })
+                .or_insert(value);
+        };
+        // the value of the ranking metric at this epoch
+        let reached = match progress {
+            Progress::Epoch { epoch, loss } => {
+                record("loss", *loss);
+                trial.epochs = *epoch;
+                Some((*epoch, *loss)).filter(|_| self.metric == "loss")
+            }
+            Progress::Validation { epoch, metrics } => {
+                for (name, value) in metrics.iter() {
+                    record(name, *value);
+                }
+                metrics.get(&self.metric).map(|value| (*epoch, *value))
+            }
+            Progress::Finished(_) => {
+                trial.status = match self.pruned.take() {
+                    Some(epoch) => TrialStatus::Pruned(epoch),
+                    None if self.stopped => TrialStatus::Stopped,
+                    None => TrialStatus::Finished,
+                };
+                self.current = None;
+                return Observation::Ended;
+            }
+            Progress::Failed(e) => {
+                trial.status = TrialStatus::Failed(e.clone());
+                self.current = None;
+                self.pruned = None;
+                return Observation::Ended;
+            }
+            _ => None,
+        };
+        match reached {
+            Some((epoch, value))
+                if self.pruned.is_none() && !self.promotable(current, epoch, value) =>
+            {
+                self.pruned = Some(epoch);
+                Observation::Prune(epoch)
+            }
+            _ => Observation::Running,
+        }
+    }
+
+    // a trial reaching the next rung of its bracket goes on when it is in the
+    // best 1/eta of the trials of the bracket that reached it, or the best one
+    // while fewer than eta did
+    fn promotable(&mut self, current: usize, epoch: usize, value: f64) -> bool {
+        let rungs = self.scheduler.rungs();
+        let trial = &mut self.trials[current];
+        let rung = trial.bracket + trial.rungs.len();
+        if rungs.get(rung).is_none_or(|epochs| epoch < *epochs) {
+            return true;
+        }
+        trial.rungs.push(value);
+        let bracket = trial.bracket;
+        let index = rung - bracket;
+        let lower = lower_is_better(&self.metric);
+        let mut competing = self
+            .trials
+            .iter()
+            .filter(|t| t.bracket == bracket)
+            .filter_map(|t| t.rungs.get(index).copied())
+            .collect::<Vec<_>>();
+        competing.sort_by(|a, b| {
+            if lower {
+                a.total_cmp(b)
+            } else {
+                b.total_cmp(a)
+            }
+        });
+        let promoted = (competing.len() / self.scheduler.eta()).max(1);
+        let threshold = competing[promoted - 1];
+        if lower {
+            value <= threshold
+        } else {
+            value >= threshold
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sweep(scheduler: TrialScheduler, metric: &str, brackets: &[usize]) -> Sweep {
+        let trials = brackets
+            .iter()
+            .map(|bracket| Trial {
+                params: TrialParams::from_settings(&Settings::default()),
+                graph: Graph::default(),
+                settings: Settings::default(),
+                metrics: BTreeMap::new(),
+                status: TrialStatus::Pending,
+                epochs: 0,
+                bracket: *bracket,
+                rungs: vec![],
+            })
+            .collect();
+        Sweep {
+            metric: metric.to_string(),
+            trials,
+            scheduler,
+            current: None,
+            stopped: false,
+            pruned: None,
+            dataset: PathBuf::new(),
+            checkpoints: CheckpointConfig::default(),
+            validation: ValidationConfig::default(),
+            tensorboard: TensorBoardConfig::default(),
+        }
+    }
+
+    fn halving(min_epochs: usize, max_epochs: usize, eta: usize) -> TrialScheduler {
+        TrialScheduler::SuccessiveHalving {
+            min_epochs,
+            max_epochs,
+            eta,
+        }
+    }
+
+    fn hyperband(min_epochs: usize, max_epochs: usize, eta: usize) -> TrialScheduler {
+        TrialScheduler::Hyperband {
+            min_epochs,
+            max_epochs,
+            eta,
+        }
+    }
+
+    #[test]
+    fn rungs() {
+        assert_eq!(TrialScheduler::None.rungs(), Vec::<usize>::new());
+        assert_eq!(halving(1, 27, 3).rungs(), vec![1, 3, 9]);
+        assert_eq!(halving(1, 9, 3).rungs(), vec![1, 3]);
+        assert_eq!(halving(2, 10, 2).rungs(), vec![2, 4, 8]);
+        assert_eq!(hyperband(5, 5, 3).rungs(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn brackets() {
+        assert_eq!(halving(1, 27, 3).brackets(3), vec![0, 0, 0]);
+        let scheduler = hyperband(1, 27, 3);
+        // every bracket is used by the first trials
+        assert_eq!(scheduler.brackets(4), vec![0, 1, 2, 3]);
+        // 27:12:6:4 over as many trials
+        let brackets = scheduler.brackets(49);
+        let counts = (0..4)
+            .map(|b| brackets.iter().filter(|t| **t == b).count())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![27, 12, 6, 4]);
+        // a single bracket without rungs
+        assert_eq!(hyperband(5, 5, 3).brackets(2), vec![0, 0]);
+    }
+
+    #[test]
+    fn promotable() {
+        let mut sweep = sweep(halving(2, 18, 3), "loss", &[0, 0, 0, 0]);
+        // before the first rung nothing is compared
+        assert!(sweep.promotable(0, 1, 100.0));
+        assert!(sweep.trials[0].rungs.is_empty());
+        // fewer than eta trials, only the best goes on
+        assert!(sweep.promotable(0, 2, 0.5));
+        assert!(!sweep.promotable(1, 2, 0.7));
+        assert!(sweep.promotable(2, 2, 0.4));
+        // the best third of 4 trials is one trial
+        assert!(!sweep.promotable(3, 2, 0.45));
+        // the next rung is compared separately
+        assert!(sweep.promotable(0, 6, 0.3));
+        assert_eq!(sweep.trials[0].rungs, vec![0.5, 0.3]);
+        // past the last rung every trial goes on
+        assert!(sweep.promotable(0, 18, 10.0));
+    }
+
+    #[test]
+    fn promotable_higher_is_better() {
+        let mut sweep = sweep(halving(1, 9, 3), "accuracy", &[0, 0]);
+        assert!(sweep.promotable(0, 1, 0.6));
+        assert!(!sweep.promotable(1, 1, 0.5));
+    }
+
+    #[test]
+    fn promotable_by_bracket() {
+        let mut sweep = sweep(hyperband(1, 27, 3), "loss", &[0, 1, 1, 3]);
+        // bracket 1 starts comparing at its first rung, 3 epochs
+        assert!(sweep.promotable(1, 1, 0.1));
+        assert!(sweep.promotable(1, 3, 0.5));
+        assert!(!sweep.promotable(2, 3, 0.6));
+        // the trials of bracket 0 do not compete with those of bracket 1
+        assert!(sweep.promotable(0, 1, 0.9));
+        // the last bracket trains to the full budget
+        assert!(sweep.promotable(3, 9, 100.0));
+        assert!(sweep.trials[3].rungs.is_empty());
+    }
+}
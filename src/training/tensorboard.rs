@@ -0,0 +1,351 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tch::{Device, Kind, Tensor};
+
+use crate::model::{node_name, Model};
+use crate::project::snapshot::format_timestamp;
+use crate::utils::protobuf::Message;
+
+const FILE_VERSION: &str = "brain.Event:2";
+// the GraphDef version of the TensorFlow release the format was taken from
+const GRAPH_PRODUCER: u64 = 22;
+const HISTOGRAM_BINS: i64 = 30;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct TensorBoardConfig {
+    // None disables the logging, each run gets a subdirectory of it
+    pub(crate) logdir: Option<PathBuf>,
+    // of every variable at the end of every epoch
+    pub(crate) histograms: bool,
+}
+
+impl Default for TensorBoardConfig {
+    fn default() -> Self {
+        Self {
+            logdir: None,
+            histograms: true,
+        }
+    }
+}
+
+impl TensorBoardConfig {
+    // moves the logging of a new run into its own directory, named after its
+    // start so that the runs sort by time in tensorboard
+    pub(crate) fn for_run(&self) -> Self {
+        let Some(logdir) = self.logdir.as_ref() else {
+            return self.clone();
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let name = format_timestamp(now).replace(' ', "_").replace(':', "-");
+        Self {
+            logdir: Some(logdir.join(name)),
+            histograms: self.histograms,
+        }
+    }
+}
+
+// writes tfevents files as tensorboard reads them: records of events, each
+// framed by its length and masked crc32c checksums
+pub(crate) struct EventWriter {
+    file: BufWriter<File>,
+}
+
+impl EventWriter {
+    pub(crate) fn create(dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Error creating the log directory: \n{:?}", e))?;
+        let host = std::env::var("HOSTNAME").unwrap_or("mlgui".to_string());
+        let path = dir.join(format!(
+            "events.out.tfevents.{}.{}",
+            wall_time() as u64,
+            host
+        ));
+        let file =
+            File::create(&path).map_err(|e| format!("Error creating the event file: \n{:?}", e))?;
+        let mut writer = Self {
+            file: BufWriter::new(file),
+        };
+        let mut event = event(0);
+        event.string(3, FILE_VERSION);
+        writer.write(event)?;
+        Ok(writer)
+    }
+
+    pub(crate) fn scalar(&mut self, tag: &str, step: i64, value: f64) -> Result<(), String> {
+        let mut value_msg = Message::new();
+        value_msg.string(1, tag).float(2, value as f32);
+        self.summary(step, value_msg)
+    }
+
+    pub(crate) fn histogram(
+        &mut self,
+        tag: &str,
+        step: i64,
+        tensor: &Tensor,
+    ) -> Result<(), String> {
+        let values = tensor
+            .detach()
+            .to_device(Device::Cpu)
+            .to_kind(Kind::Double)
+            .flatten(0, -1);
+        if values.numel() == 0 {
+            return Ok(());
+        }
+        let (min, max) = (
+            values.min().double_value(&[]),
+            values.max().double_value(&[]),
+        );
+        let counts = Vec::<f64>::try_from(&values.histc(HISTOGRAM_BINS))
+            .map_err(|e| format!("Error reading the histogram of {}: \n{:?}", tag, e))?;
+        // the right edge of every bin
+        let width = (max - min) / HISTOGRAM_BINS as f64;
+        let limits = (1..=counts.len())
+            .map(|i| {
+                if i == counts.len() {
+                    max
+                } else {
+                    min + width * i as f64
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut histogram = Message::new();
+        histogram
+            .double(1, min)
+            .double(2, max)
+            .double(3, values.numel() as f64)
+            .double(4, values.sum(Kind::Double).double_value(&[]))
+            .double(5, values.square().sum(Kind::Double).double_value(&[]))
+            .packed_double(6, &limits)
+            .packed_double(7, &counts);
+        let mut value_msg = Message::new();
+        value_msg.string(1, tag).message(5, &histogram);
+        self.summary(step, value_msg)
+    }
+
+    // the layers in execution order with their output shapes, as a GraphDef
+    pub(crate) fn graph(&mut self, model: &Model) -> Result<(), String> {
+        let mut graph = Message::new();
+        graph.message(
+            1,
+            &node_def("input", "Placeholder", None, &model.input_shape, ""),
+        );
+        let mut previous = "input".to_string();
+        for ((i, component), shape) in model.nodes.iter().zip(model.output_shapes.iter()) {
+            let name = node_name(*i, component);
+            graph.message(
+                1,
+                &node_def(
+                    &name,
+                    **component,
+                    Some(&previous),
+                    shape,
+                    &component.config_description(),
+                ),
+            );
+            previous = name;
+        }
+        let mut versions = Message::new();
+        versions.varint(1, GRAPH_PRODUCER);
+        graph.message(4, &versions);
+        let mut event = event(0);
+        event.bytes(4, &graph.into_bytes());
+        self.write(event)
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<(), String> {
+        self.file
+            .flush()
+            .map_err(|e| format!("Error writing the event file: \n{:?}", e))
+    }
+
+    fn summary(&mut self, step: i64, value: Message) -> Result<(), String> {
+        let mut summary = Message::new();
+        summary.message(1, &value);
+        let mut event = event(step);
+        event.message(5, &summary);
+        self.write(event)
+    }
+
+    fn write(&mut self, event: Message) -> Result<(), String> {
+        let data = event.into_bytes();
+        let length = (data.len() as u64).to_le_bytes();
+        let mut record = Vec::with_capacity(data.len() + 16);
+        record.extend_from_slice(&length);
+        record.extend_from_slice(&masked_crc32c(&length).to_le_bytes());
+        record.extend_from_slice(&data);
+        record.extend_from_slice(&masked_crc32c(&data).to_le_bytes());
+        self.file
+            .write_all(&record)
+            .map_err(|e| format!("Error writing the event file: \n{:?}", e))
+    }
+}
+
+fn wall_time() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+fn event(step: i64) -> Message {
+    let mut event = Message::new();
+    event.double(1, wall_time()).int64(2, step);
+    event
+}
+
+// a NodeDef with its output shape and the layer config as attributes
+fn node_def(
+    name: &str,
+    op: &str,
+    input: Option<&str>,
+    shape: &[i64],
+    description: &str,
+) -> Message {
+    let mut node = Message::new();
+    node.string(1, name).string(2, op);
+    if let Some(input) = input {
+        node.string(3, input);
+    }
+    let mut dims = Message::new();
+    for size in shape {
+        let mut dim = Message::new();
+        dim.int64(1, *size);
+        dims.message(2, &dim);
+    }
+    let mut shapes = Message::new();
+    shapes.message(7, &dims);
+    let mut shapes_attr = Message::new();
+    shapes_attr.message(1, &shapes);
+    node.message(5, &attr("_output_shapes", &shapes_attr));
+    if !description.is_empty() {
+        let mut description_attr = Message::new();
+        description_attr.string(2, description);
+        node.message(5, &attr("config", &description_attr));
+    }
+    node
+}
+
+// an entry of the attr map of a NodeDef
+fn attr(key: &str, value: &Message) -> Message {
+    let mut entry = Message::new();
+    entry.string(1, key).message(2, value);
+    entry
+}
+
+// crc32c as tfrecord frames use it, rotated and offset
+fn masked_crc32c(data: &[u8]) -> u32 {
+    let crc = crc32c(data);
+    ((crc >> 15) | (crc << 17)).wrapping_add(0xa282_ead8)
+}
+
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x82f6_3b78
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (field, wire type, varint value or payload) of every field of a message
+    fn fields(mut bytes: &[u8]) -> Vec<(u64, u64, u64, Vec<u8>)> {
+        fn varint(bytes: &mut &[u8]) -> u64 {
+            let (mut value, mut shift) = (0, 0);
+            loop {
+                let byte = bytes[0];
+                *bytes = &bytes[1..];
+                value |= ((byte & 0x7f) as u64) << shift;
+                if byte < 0x80 {
+                    return value;
+                }
+                shift += 7;
+            }
+        }
+        let mut fields = vec![];
+        while !bytes.is_empty() {
+            let key = varint(&mut bytes);
+            let (field, wire_type) = (key >> 3, key & 7);
+            let size = match wire_type {
+                0 => {
+                    fields.push((field, wire_type, varint(&mut bytes), vec![]));
+                    continue;
+                }
+                1 => 8,
+                2 => varint(&mut bytes) as usize,
+                5 => 4,
+                _ => panic!("unexpected wire type {}", wire_type),
+            };
+            fields.push((field, wire_type, 0, bytes[..size].to_vec()));
+            bytes = &bytes[size..];
+        }
+        fields
+    }
+
+    // the data of the records of a file, checking their framing
+    fn records(mut file: &[u8]) -> Vec<Vec<u8>> {
+        let mut records = vec![];
+        while !file.is_empty() {
+            let length = &file[..8];
+            let size = u64::from_le_bytes(length.try_into().unwrap()) as usize;
+            let crc = u32::from_le_bytes(file[8..12].try_into().unwrap());
+            assert_eq!(crc, masked_crc32c(length));
+            let data = &file[12..12 + size];
+            let crc = u32::from_le_bytes(file[12 + size..16 + size].try_into().unwrap());
+            assert_eq!(crc, masked_crc32c(data));
+            records.push(data.to_vec());
+            file = &file[16 + size..];
+        }
+        records
+    }
+
+    #[test]
+    fn crc() {
+        assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+        assert_eq!(crc32c(b""), 0);
+    }
+
+    #[test]
+    fn scalar_record() {
+        let dir = std::env::temp_dir().join(format!("mlgui-tensorboard-{}", std::process::id()));
+        let mut writer = EventWriter::create(&dir).unwrap();
+        writer.scalar("train/loss", 7, 0.5).unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+        let entry = std::fs::read_dir(&dir).unwrap().next().unwrap().unwrap();
+        let file = std::fs::read(entry.path()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let records = records(&file);
+        assert_eq!(records.len(), 2);
+        let version = fields(&records[0]);
+        assert_eq!(version[2], (3, 2, 0, FILE_VERSION.as_bytes().to_vec()));
+        // wall_time, step, summary
+        let event = fields(&records[1]);
+        assert_eq!(event.len(), 3);
+        assert_eq!((event[0].0, event[0].1), (1, 1));
+        assert_eq!((event[1].0, event[1].2), (2, 7));
+        assert_eq!(event[2].0, 5);
+        let summary = fields(&event[2].3);
+        assert_eq!(summary.len(), 1);
+        let value = fields(&summary[0].3);
+        assert_eq!(value[0], (1, 2, 0, b"train/loss".to_vec()));
+        assert_eq!(value[1], (2, 5, 0, 0.5f32.to_le_bytes().to_vec()));
+    }
+}
@@ -6,19 +6,47 @@ pub(crate) const DEVICES: [&str; 4] = ["CPU", "CUDA", "MPS", "VULKAN"];
 pub(crate) const OPTIMIZERS: [&str; 4] = ["SGD", "Adam", "AdamW", "RMSprop"];
 pub(crate) const LOSS_FUNCTIONS: [&str; 7] =
     ["MSE", "CrossEntropy", "BCE", "NLL", "CTC", "Huber", "L1"];
+pub(crate) const SCHEDULERS: [&str; 8] = [
+    "Constant",
+    "StepLR",
+    "MultiStep",
+    "Exponential",
+    "CosineWarmRestarts",
+    "LinearWarmup",
+    "OneCycle",
+    "ReduceLROnPlateau",
+];
 pub(crate) const LOSS_WINDOW_WIDTH: i32 = 400;
 pub(crate) const LOSS_WINDOW_HEIGHT: i32 = 400;
+pub(crate) const SUMMARY_WINDOW_WIDTH: i32 = 900;
+pub(crate) const SUMMARY_WINDOW_HEIGHT: i32 = 400;
+// parameter popups, one row per parameter
+pub(crate) const FORM_WINDOW_WIDTH: i32 = 400;
+pub(crate) const FORM_ROW_HEIGHT: i32 = 50;
+pub(crate) const SCHEDULER_PREVIEW_HEIGHT: i32 = 220;
+pub(crate) const SNAPSHOT_WINDOW_WIDTH: i32 = 700;
+pub(crate) const SNAPSHOT_WINDOW_HEIGHT: i32 = 500;
+pub(crate) const RUNS_WINDOW_WIDTH: i32 = 1000;
+pub(crate) const RUNS_WINDOW_HEIGHT: i32 = 650;
+pub(crate) const SWEEP_WINDOW_WIDTH: i32 = 1200;
 pub(crate) const WINDOW_TITLE: &str = "Ml Gui";
 pub(crate) const MENU_BAR_RATIO: i32 = 24;
 pub(crate) const COMPONENT_LIST_RATIO: i32 = 5;
 pub(crate) const CONFIG_LIST_RATIO: i32 = 4;
 pub(crate) const DRAG_THRESHOLD: i32 = 4;
+// minimum time between two step reports of the trainer
+pub(crate) const PROGRESS_INTERVAL_MS: u64 = 50;
 pub(crate) const MENU_BAR_COLOR: Color = Color::from_hex(0x21252B);
 pub(crate) const BG_COLOR: Color = Color::from_hex(0x282C34);
 pub(crate) const HIGHLIGHT_COLOR: Color = Color::from_hex(0x3E4452);
+// colors of the chart series, in order of appearance
+pub(crate) const CHART_COLORS: [u32; 6] =
+    [0x61AFEF, 0xE5C07B, 0x98C379, 0xE06C75, 0xC678DD, 0x56B6C2];
 pub(crate) const DEFAULT_LR: f64 = 0.01;
 pub(crate) const DEFAULT_LR_STR: &str = "0.01";
 pub(crate) const DEFAULT_BATCH_SIZE: i64 = 20;
 pub(crate) const DEFAULT_BATCH_SIZE_STR: &str = "20";
 pub(crate) const DEFAULT_EPOCHS: usize = 100;
 pub(crate) const DEFAULT_EPOCHS_STR: &str = "100";
+pub(crate) const DEFAULT_KERNEL_SIZE: i64 = 3;
+pub(crate) const DEFAULT_INPUT_SHAPE: &str = "1,1";
@@ -1,9 +1,39 @@
+use std::path::PathBuf;
+
+use crate::project::Project;
+use crate::training::sweep::SweepConfig;
+use crate::training::Progress;
+
 #[derive(Debug, Clone)]
 pub(crate) enum AppEvent {
     Editor,
     Training,
     Settings,
     Help,
+    ImportDot,
+    ExportDot,
+    ExportDiagramSvg,
+    ExportDiagramPng,
+    ExportTorchScript,
+    ExportOnnx,
+    ExportRustCode,
+    ExportPythonCode,
+    NewProject,
+    OpenProject,
+    SaveProject,
+    SaveProjectAs,
+    LoadWeights,
+    Summary,
+    Snapshots,
+    Runs,
+    Sweep,
+    RestoreSnapshot(Box<Project>),
+    StartTraining,
+    ResumeTraining(PathBuf),
+    TrainingProgress(Progress),
+    StartSweep(Box<SweepConfig>),
+    StopSweep,
+    PromoteTrial(usize),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Copy)]
@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use fltk::button::{Button, ToggleButton};
+use fltk::enums::{Align, Color, FrameType};
+use fltk::frame::Frame;
+use fltk::input::{FloatInput, Input, IntInput};
+use fltk::menu::Choice;
+use fltk::prelude::{ButtonExt, InputExt, MenuExt, WidgetBase, WidgetExt};
+
+use crate::utils::consts::{BG_COLOR, FORM_ROW_HEIGHT, FORM_WINDOW_WIDTH, HIGHLIGHT_COLOR};
+use crate::utils::CustomDialog;
+
+// rows of the parameter popups, the name on the left half and the value on
+// the right half, as in the loss popup
+
+fn label_row(row: i32, label: &str) -> Frame {
+    let mut border = Frame::default()
+        .with_pos(0, row * FORM_ROW_HEIGHT)
+        .with_size(FORM_WINDOW_WIDTH, FORM_ROW_HEIGHT);
+    border.set_color(Color::White);
+    border.set_frame(FrameType::FlatBox);
+    let mut text = Frame::default()
+        .with_pos(border.x() + 2, border.y() + 2)
+        .with_size(border.w() / 2 - 4, border.h() - 4)
+        .with_label(label);
+    text.set_label_color(Color::White);
+    text.set_frame(FrameType::FlatBox);
+    text.set_color(BG_COLOR);
+    text
+}
+
+fn style_input<I: InputExt>(input: &mut I, value: &str) {
+    input.set_color(BG_COLOR);
+    input.set_frame(FrameType::FlatBox);
+    input.set_selection_color(HIGHLIGHT_COLOR);
+    input.set_value(value);
+    input.set_cursor_color(Color::White);
+    input.set_text_color(Color::White);
+}
+
+pub(crate) fn float_row(row: i32, label: &str, value: f64) -> FloatInput {
+    let text = label_row(row, label);
+    let mut input = FloatInput::default()
+        .with_pos(FORM_WINDOW_WIDTH / 2 + 2, text.y())
+        .with_size(text.w(), text.h());
+    style_input(&mut input, &value.to_string());
+    input
+}
+
+pub(crate) fn int_row(row: i32, label: &str, value: i64) -> IntInput {
+    let text = label_row(row, label);
+    let mut input = IntInput::default()
+        .with_pos(FORM_WINDOW_WIDTH / 2 + 2, text.y())
+        .with_size(text.w(), text.h());
+    style_input(&mut input, &value.to_string());
+    input
+}
+
+pub(crate) fn text_row(row: i32, label: &str, value: &str) -> Input {
+    let text = label_row(row, label);
+    let mut input = Input::default()
+        .with_pos(FORM_WINDOW_WIDTH / 2 + 2, text.y())
+        .with_size(text.w(), text.h());
+    style_input(&mut input, value);
+    input
+}
+
+pub(crate) fn toggle_row(row: i32, label: &str, value: bool) -> ToggleButton {
+    let text = label_row(row, label);
+    let mut toggle = ToggleButton::default()
+        .with_pos(FORM_WINDOW_WIDTH / 2 + 2, text.y())
+        .with_size(text.w(), text.h())
+        .with_label(if value { "✔️" } else { "✖️" });
+    toggle.set_color(BG_COLOR);
+    toggle.set_frame(FrameType::FlatBox);
+    toggle.set_selection_color(HIGHLIGHT_COLOR);
+    toggle.set(value);
+    toggle.set_callback(move |btn| {
+        btn.set_label(if btn.is_set() { "✔️" } else { "✖️" });
+    });
+    toggle
+}
+
+// the caller sets what the button does
+pub(crate) fn button_row(row: i32, label: &str, text: &str) -> Button {
+    let text_frame = label_row(row, label);
+    let mut button = Button::default()
+        .with_pos(FORM_WINDOW_WIDTH / 2 + 2, text_frame.y())
+        .with_size(text_frame.w(), text_frame.h())
+        .with_label(text);
+    button.set_color(BG_COLOR);
+    button.set_frame(FrameType::FlatBox);
+    button.set_selection_color(HIGHLIGHT_COLOR);
+    button.set_label_color(Color::White);
+    button
+}
+
+// the selected option is kept as the label, as the other selectors do
+pub(crate) fn choice_row(row: i32, label: &str, options: &[&str], selected: &str) -> Choice {
+    let text = label_row(row, label);
+    let mut choice = Choice::default()
+        .with_pos(FORM_WINDOW_WIDTH / 2 + 2, text.y())
+        .with_size(text.w(), text.h())
+        .with_label(selected);
+    choice.set_color(BG_COLOR);
+    choice.set_selection_color(HIGHLIGHT_COLOR);
+    choice.set_align(Align::Center);
+    choice.set_label_color(Color::White);
+    choice.set_frame(FrameType::FlatBox);
+    for option in options {
+        let i = choice.add_choice(option);
+        choice.at(i).unwrap().set_label_color(Color::White);
+    }
+    choice.set_callback(move |selector| {
+        selector.set_label(&selector.text(selector.value()).unwrap());
+        selector.set_value(-1);
+    });
+    choice
+}
+
+// shows an error naming the field when the value does not parse
+pub(crate) fn parse<T: FromStr>(value: &str, label: &str) -> Option<T>
+where
+    T::Err: std::fmt::Display,
+{
+    match value.trim().parse::<T>() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            CustomDialog::show(
+                350,
+                60,
+                "Error",
+                &format!("Error parsing {}: \n{}", label.trim_end_matches(": "), e),
+                BG_COLOR,
+                Color::Red,
+            );
+            None
+        }
+    }
+}
+
+// window height for a number of rows
+pub(crate) fn height(rows: i32) -> i32 {
+    rows * FORM_ROW_HEIGHT
+}
@@ -8,38 +8,124 @@ use fltk::input::{FloatInput, IntInput};
 use fltk::menu::Choice;
 use fltk::prelude::{ButtonExt, GroupExt, InputExt, MenuExt, WidgetExt, WindowExt};
 use fltk::window::Window;
-use tch::Reduction;
+use serde::{Deserialize, Serialize};
+use tch::{Kind, Reduction, Tensor};
 
-use crate::utils::consts::{BG_COLOR, HIGHLIGHT_COLOR, LOSS_WINDOW_HEIGHT, LOSS_WINDOW_WIDTH};
+use crate::utils::consts::{
+    BG_COLOR, HIGHLIGHT_COLOR, LOSS_FUNCTIONS, LOSS_WINDOW_HEIGHT, LOSS_WINDOW_WIDTH,
+};
 use crate::utils::CustomDialog;
 
 pub(crate) struct LossWidget {}
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub(crate) enum LossFunction {
     Mse {
+        #[serde(with = "reduction")]
         reduction: Reduction,
     },
     CrossEntropy {
+        #[serde(with = "reduction")]
         reduction: Reduction,
         smoothing: f64,
     },
     Bce {
+        #[serde(with = "reduction")]
         reduction: Reduction,
     },
     Nll,
     Ctc {
+        #[serde(with = "reduction")]
         reduction: Reduction,
         blank: i64,
         zero_infinity: bool,
     },
     Huber {
+        #[serde(with = "reduction")]
         reduction: Reduction,
         delta: f64,
     },
     L1 {
+        #[serde(with = "reduction")]
         reduction: Reduction,
     },
 }
+
+impl LossFunction {
+    // label of the loss in the selector
+    pub(crate) fn name(&self) -> &'static str {
+        LOSS_FUNCTIONS[match self {
+            LossFunction::Mse { .. } => 0,
+            LossFunction::CrossEntropy { .. } => 1,
+            LossFunction::Bce { .. } => 2,
+            LossFunction::Nll => 3,
+            LossFunction::Ctc { .. } => 4,
+            LossFunction::Huber { .. } => 5,
+            LossFunction::L1 { .. } => 6,
+        }]
+    }
+
+    // class indices for the classification losses, values for the others
+    pub(crate) fn target_kind(&self) -> Kind {
+        match self {
+            LossFunction::CrossEntropy { .. } | LossFunction::Nll => Kind::Int64,
+            _ => Kind::Float,
+        }
+    }
+
+    // unreduced losses are averaged so that they can be backpropagated
+    pub(crate) fn loss(&self, output: &Tensor, target: &Tensor) -> Result<Tensor, String> {
+        let loss = match self {
+            LossFunction::Mse { reduction } => output.f_mse_loss(target, *reduction),
+            LossFunction::CrossEntropy {
+                reduction,
+                smoothing,
+            } => output.f_cross_entropy_loss::<Tensor>(target, None, *reduction, -100, *smoothing),
+            LossFunction::Bce { reduction } => {
+                output.f_binary_cross_entropy::<Tensor>(target, None, *reduction)
+            }
+            LossFunction::Nll => output.f_nll_loss::<Tensor>(target, None, Reduction::Mean, -100),
+            LossFunction::Ctc { .. } => {
+                return Err("CTC needs input and target lengths, it can not be trained here".into())
+            }
+            LossFunction::Huber { reduction, delta } => {
+                output.f_huber_loss(target, *reduction, *delta)
+            }
+            LossFunction::L1 { reduction } => output.f_l1_loss(target, *reduction),
+        }
+        .map_err(|e| format!("Error computing the loss: \n{:?}", e))?;
+        if loss.dim() > 0 {
+            Ok(loss.mean(Kind::Float))
+        } else {
+            Ok(loss)
+        }
+    }
+}
+
+// tch reductions are not serializable, they are stored by name
+mod reduction {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use tch::Reduction;
+
+    pub(super) fn serialize<S: Serializer>(value: &Reduction, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(match value {
+            Reduction::Sum => "Sum",
+            Reduction::Mean => "Mean",
+            _ => "None",
+        })
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Reduction, D::Error> {
+        match String::deserialize(d)?.as_str() {
+            "Sum" => Ok(Reduction::Sum),
+            "Mean" => Ok(Reduction::Mean),
+            "None" => Ok(Reduction::None),
+            other => Err(serde::de::Error::custom(format!(
+                "Invalid reduction: {}",
+                other
+            ))),
+        }
+    }
+}
 impl LossWidget {
     pub(crate) fn show(loss_fn_i: i32, title: &str, loss: Rc<RefCell<Option<LossFunction>>>) {
         let mut window = Window::default()
@@ -1,3 +1,7 @@
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+use fltk::dialog::{FileDialogType, NativeFileChooser};
 use fltk::enums::{Align, Color};
 use fltk::frame::Frame;
 use fltk::prelude::{WidgetExt, WindowExt};
@@ -5,9 +9,15 @@ use fltk::window::Window;
 use pyo3::Python;
 use tch::Device;
 
+use crate::utils::consts::BG_COLOR;
+
 pub(crate) mod consts;
 pub(crate) mod enums;
+pub(crate) mod form;
 pub(crate) mod loss_fn;
+pub(crate) mod optimizer;
+pub(crate) mod png;
+pub(crate) mod protobuf;
 
 pub(crate) struct CustomDialog {}
 
@@ -34,6 +44,54 @@ impl CustomDialog {
     }
 }
 
+// asks for an existing file with one of the `exts` extensions
+pub(crate) fn open_file_dialog(exts: &[&str]) -> Option<PathBuf> {
+    let mut dialog = NativeFileChooser::new(FileDialogType::BrowseFile);
+    dialog.set_filter(&format!("*.{{{}}}", exts.join(",")));
+    dialog.show();
+    let filename = dialog.filename();
+    if filename.eq(&PathBuf::new()) {
+        return None;
+    }
+    Some(filename)
+}
+
+pub(crate) fn open_dir_dialog() -> Option<PathBuf> {
+    let mut dialog = NativeFileChooser::new(FileDialogType::BrowseDir);
+    dialog.show();
+    let dirname = dialog.filename();
+    if dirname.eq(&PathBuf::new()) {
+        return None;
+    }
+    Some(dirname)
+}
+
+// the first extension is used when the file name has none
+pub(crate) fn save_file_dialog(exts: &[&str]) -> Option<PathBuf> {
+    let mut dialog = NativeFileChooser::new(FileDialogType::BrowseSaveFile);
+    dialog.show();
+    let filename = dialog.filename();
+    if filename.eq(&PathBuf::new()) {
+        CustomDialog::show(200, 40, "Error", "No file selected", BG_COLOR, Color::Red);
+        return None;
+    }
+    match filename.extension() {
+        None => Some(filename.with_extension(exts[0])),
+        Some(e) if exts.iter().any(|ext| e.eq(OsStr::new(ext))) => Some(filename),
+        Some(_) => {
+            CustomDialog::show(
+                300,
+                40,
+                "Error",
+                &format!("Extension must be either empty or .{}", exts.join("/.")),
+                BG_COLOR,
+                Color::Red,
+            );
+            None
+        }
+    }
+}
+
 pub(crate) fn check_mps_availability(py: Python) -> Result<Device, String> {
     let torch = py
         .import("torch")
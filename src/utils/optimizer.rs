@@ -0,0 +1,242 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use fltk::enums::{Color, FrameType};
+use fltk::menu::Choice;
+use fltk::prelude::{ButtonExt, GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt};
+use fltk::window::Window;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::utils::consts::{BG_COLOR, FORM_WINDOW_WIDTH, OPTIMIZERS};
+use crate::utils::form;
+
+pub(crate) struct OptimizerWidget {}
+
+// the parameters of the torch.optim optimizers, wd is the weight decay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum OptimizerType {
+    Sgd {
+        momentum: f64,
+        dampening: f64,
+        nesterov: bool,
+        wd: f64,
+    },
+    Adam {
+        beta1: f64,
+        beta2: f64,
+        eps: f64,
+        wd: f64,
+        amsgrad: bool,
+    },
+    AdamW {
+        beta1: f64,
+        beta2: f64,
+        eps: f64,
+        wd: f64,
+        amsgrad: bool,
+    },
+    RmsProp {
+        alpha: f64,
+        eps: f64,
+        momentum: f64,
+        centered: bool,
+        wd: f64,
+    },
+}
+
+impl OptimizerType {
+    // label of the optimizer in the selector
+    pub(crate) fn name(&self) -> &'static str {
+        OPTIMIZERS[self.index()]
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        match self {
+            OptimizerType::Sgd { .. } => 0,
+            OptimizerType::Adam { .. } => 1,
+            OptimizerType::AdamW { .. } => 2,
+            OptimizerType::RmsProp { .. } => 3,
+        }
+    }
+
+    // the optimizer at `index` in the selector, with the torch defaults
+    pub(crate) fn with_defaults(index: usize) -> Self {
+        match index {
+            0 => OptimizerType::Sgd {
+                momentum: 0.0,
+                dampening: 0.0,
+                nesterov: false,
+                wd: 0.0,
+            },
+            1 => OptimizerType::Adam {
+                beta1: 0.9,
+                beta2: 0.999,
+                eps: 1e-8,
+                wd: 0.0,
+                amsgrad: false,
+            },
+            2 => OptimizerType::AdamW {
+                beta1: 0.9,
+                beta2: 0.999,
+                eps: 1e-8,
+                wd: 0.01,
+                amsgrad: false,
+            },
+            _ => OptimizerType::RmsProp {
+                alpha: 0.99,
+                eps: 1e-8,
+                momentum: 0.0,
+                centered: false,
+                wd: 0.0,
+            },
+        }
+    }
+}
+
+// projects saved before the optimizers had parameters store only the name,
+// those get the torch defaults
+pub(crate) fn deserialize_setting<'de, D: Deserializer<'de>>(
+    d: D,
+) -> Result<Option<OptimizerType>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Name(String),
+        Config(OptimizerType),
+    }
+    match Option::<Stored>::deserialize(d)? {
+        None => Ok(None),
+        Some(Stored::Config(optimizer)) => Ok(Some(optimizer)),
+        Some(Stored::Name(name)) => match OPTIMIZERS.iter().position(|o| *o == name) {
+            Some(i) => Ok(Some(OptimizerType::with_defaults(i))),
+            None => Err(serde::de::Error::custom(format!(
+                "Unknown optimizer: {}",
+                name
+            ))),
+        },
+    }
+}
+
+impl OptimizerWidget {
+    // the current values are edited when the same optimizer is selected
+    // again, the selector shows the name once the popup closes
+    pub(crate) fn show(
+        index: usize,
+        optimizer: Rc<RefCell<Option<OptimizerType>>>,
+        mut entry: Choice,
+    ) {
+        let current = match optimizer.borrow().clone() {
+            Some(o) if o.index() == index => o,
+            _ => OptimizerType::with_defaults(index),
+        };
+        let mut window = Window::default()
+            .with_size(FORM_WINDOW_WIDTH, form::height(5))
+            .with_label(current.name())
+            .center_screen();
+        window.set_label_color(Color::White);
+        window.set_color(BG_COLOR);
+        window.set_frame(FrameType::FlatBox);
+        let read: Box<dyn Fn() -> Option<OptimizerType>> = match current {
+            OptimizerType::Sgd {
+                momentum,
+                dampening,
+                nesterov,
+                wd,
+            } => {
+                window.set_size(FORM_WINDOW_WIDTH, form::height(4));
+                let momentum = form::float_row(0, "Momentum: ", momentum);
+                let dampening = form::float_row(1, "Dampening: ", dampening);
+                let nesterov = form::toggle_row(2, "Nesterov: ", nesterov);
+                let wd = form::float_row(3, "Weight decay: ", wd);
+                Box::new(move || {
+                    Some(OptimizerType::Sgd {
+                        momentum: form::parse(&momentum.value(), "Momentum")?,
+                        dampening: form::parse(&dampening.value(), "Dampening")?,
+                        nesterov: nesterov.is_set(),
+                        wd: form::parse(&wd.value(), "Weight decay")?,
+                    })
+                })
+            }
+            OptimizerType::Adam {
+                beta1,
+                beta2,
+                eps,
+                wd,
+                amsgrad,
+            }
+            | OptimizerType::AdamW {
+                beta1,
+                beta2,
+                eps,
+                wd,
+                amsgrad,
+            } => {
+                let beta1 = form::float_row(0, "Beta 1: ", beta1);
+                let beta2 = form::float_row(1, "Beta 2: ", beta2);
+                let eps = form::float_row(2, "Eps: ", eps);
+                let wd = form::float_row(3, "Weight decay: ", wd);
+                let amsgrad = form::toggle_row(4, "AMSGrad: ", amsgrad);
+                Box::new(move || {
+                    let (beta1, beta2, eps, wd, amsgrad) = (
+                        form::parse(&beta1.value(), "Beta 1")?,
+                        form::parse(&beta2.value(), "Beta 2")?,
+                        form::parse(&eps.value(), "Eps")?,
+                        form::parse(&wd.value(), "Weight decay")?,
+                        amsgrad.is_set(),
+                    );
+                    Some(if index == 1 {
+                        OptimizerType::Adam {
+                            beta1,
+                            beta2,
+                            eps,
+                            wd,
+                            amsgrad,
+                        }
+                    } else {
+                        OptimizerType::AdamW {
+                            beta1,
+                            beta2,
+                            eps,
+                            wd,
+                            amsgrad,
+                        }
+                    })
+                })
+            }
+            OptimizerType::RmsProp {
+                alpha,
+                eps,
+                momentum,
+                centered,
+                wd,
+            } => {
+                let alpha = form::float_row(0, "Alpha: ", alpha);
+                let eps = form::float_row(1, "Eps: ", eps);
+                let momentum = form::float_row(2, "Momentum: ", momentum);
+                let centered = form::toggle_row(3, "Centered: ", centered);
+                let wd = form::float_row(4, "Weight decay: ", wd);
+                Box::new(move || {
+                    Some(OptimizerType::RmsProp {
+                        alpha: form::parse(&alpha.value(), "Alpha")?,
+                        eps: form::parse(&eps.value(), "Eps")?,
+                        momentum: form::parse(&momentum.value(), "Momentum")?,
+                        centered: centered.is_set(),
+                        wd: form::parse(&wd.value(), "Weight decay")?,
+                    })
+                })
+            }
+        };
+        window.end();
+        window.show();
+        window.set_callback(move |window| {
+            window.hide();
+            if let Some(value) = read() {
+                optimizer.replace(Some(value));
+            }
+            entry.set_label(match &*optimizer.borrow() {
+                Some(optimizer) => optimizer.name(),
+                None => "Select optimizer:",
+            });
+        });
+    }
+}
@@ -0,0 +1,104 @@
+// minimal protocol buffers encoder, only what is needed to write messages
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Message {
+    buf: Vec<u8>,
+}
+
+impl Message {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+
+    fn key(&mut self, field: u32, wire_type: u8) {
+        self.raw_varint(((field as u64) << 3) | wire_type as u64);
+    }
+
+    pub(crate) fn varint(&mut self, field: u32, value: u64) -> &mut Self {
+        self.key(field, 0);
+        self.raw_varint(value);
+        self
+    }
+
+    pub(crate) fn int64(&mut self, field: u32, value: i64) -> &mut Self {
+        self.varint(field, value as u64)
+    }
+
+    pub(crate) fn float(&mut self, field: u32, value: f32) -> &mut Self {
+        self.key(field, 5);
+        self.buf.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(crate) fn double(&mut self, field: u32, value: f64) -> &mut Self {
+        self.key(field, 1);
+        self.buf.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub(crate) fn bytes(&mut self, field: u32, value: &[u8]) -> &mut Self {
+        self.key(field, 2);
+        self.raw_varint(value.len() as u64);
+        self.buf.extend_from_slice(value);
+        self
+    }
+
+    pub(crate) fn string(&mut self, field: u32, value: &str) -> &mut Self {
+        self.bytes(field, value.as_bytes())
+    }
+
+    pub(crate) fn message(&mut self, field: u32, value: &Message) -> &mut Self {
+        self.bytes(field, &value.buf)
+    }
+
+    pub(crate) fn packed_int64(&mut self, field: u32, values: &[i64]) -> &mut Self {
+        let mut packed = Message::new();
+        values.iter().for_each(|v| packed.raw_varint(*v as u64));
+        self.bytes(field, &packed.buf)
+    }
+
+    pub(crate) fn packed_double(&mut self, field: u32, values: &[f64]) -> &mut Self {
+        let packed = values
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        self.bytes(field, &packed)
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(value: u64) -> Vec<u8> {
+        let mut message = Message::new();
+        message.varint(1, value);
+        message.into_bytes()
+    }
+
+    #[test]
+    fn varints() {
+        assert_eq!(varint(1), vec![0x08, 0x01]);
+        assert_eq!(varint(150), vec![0x08, 0x96, 0x01]);
+        let mut max = vec![0x08];
+        max.extend([0xff; 9]);
+        max.push(0x01);
+        assert_eq!(varint(u64::MAX), max);
+        // negative int64 take the ten bytes of their two's complement
+        let mut message = Message::new();
+        message.int64(1, -1);
+        assert_eq!(message.into_bytes(), max);
+    }
+}